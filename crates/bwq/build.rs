@@ -0,0 +1,27 @@
+//! Records the git commit for `bwq --version --verbose`; `unknown` outside a git checkout
+
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short=9", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=BWQ_COMMIT_HASH={commit}");
+
+    // rebuild when HEAD moves; a missing path would rerun this on every build
+    let git_dir = Path::new("../../.git");
+    for path in ["HEAD", "refs/heads", "packed-refs"] {
+        let path = git_dir.join(path);
+        if path.exists() {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
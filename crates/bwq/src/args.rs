@@ -1,12 +1,20 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(name = "bwq")]
 #[command(about = "A linter for Brandwatch query files (.bwq)")]
-#[command(version = "0.2.0")]
+#[command(disable_version_flag = true)]
 pub struct Cli {
+    /// Print version
+    #[arg(short = 'V', long)]
+    pub version: bool,
+
+    /// With --version, also print the git commit, enabled features and rule registry version
+    #[arg(long, requires = "version")]
+    pub verbose: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -15,34 +23,183 @@ pub struct Cli {
 pub enum Commands {
     /// lint files, directories, or query strings
     #[command(name = "check")]
-    Check {
-        /// Files or directories to check (ignored if --query is used) [default: .]
-        files: Vec<PathBuf>,
+    Check(Box<CheckArgs>),
+
+    /// Show example queries
+    Examples,
+
+    /// Explain a diagnostic code (e.g. E004 or W001)
+    Explain(ExplainArgs),
 
-        /// Lint a query string directly (instead of files)
-        #[arg(long, short = 'q')]
-        query: Option<String>,
+    /// Format query files in place, or print a formatted query string
+    Format(FormatArgs),
 
-        /// Suppress warning messages
-        #[arg(long)]
-        no_warnings: bool,
+    /// Start language server
+    #[command(alias = "lsp")]
+    Server(ServerArgs),
+}
 
-        /// Output format (text or json)
-        #[arg(long, default_value = "text")]
-        output_format: String,
+#[derive(Args)]
+pub struct CheckArgs {
+    /// Files or directories to check (ignored if --query is used) [default: .]
+    pub files: Vec<PathBuf>,
 
-        /// Exit with status code 0, even upon detecting lint violations
-        #[arg(long)]
-        exit_zero: bool,
+    /// Lint a query string directly (instead of files)
+    #[arg(long, short = 'q')]
+    pub query: Option<String>,
 
-        /// File extensions to check (can be used multiple times)
-        #[arg(long = "extension", short = 'e', default_values = ["bwq"])]
-        extensions: Vec<String>,
-    },
+    /// Print the --query in another form instead of diagnostics (pretty: an indented clause tree)
+    #[arg(long, value_name = "FORMAT", requires = "query")]
+    pub emit: Option<String>,
 
-    /// Show example queries
-    Examples,
+    /// Suppress warning messages
+    #[arg(long)]
+    pub no_warnings: bool,
 
-    /// Start language server
-    Server,
+    /// Output format (text, json, codeclimate, sarif, or ast-json to dump the parsed --query)
+    #[arg(long, default_value = "text")]
+    pub output_format: String,
+
+    /// Exit with status code 0, even upon detecting lint violations
+    #[arg(long)]
+    pub exit_zero: bool,
+
+    /// File extensions to check (can be used multiple times)
+    #[arg(long = "extension", short = 'e', default_values = ["bwq"])]
+    pub extensions: Vec<String>,
+
+    /// Group text output by file, code, or severity
+    #[arg(long, default_value = "file")]
+    pub group_by: String,
+
+    /// Order files in the output by name or by diagnostic count, worst first
+    #[arg(long, value_name = "ORDER", default_value = "name")]
+    pub sort_files: String,
+
+    /// Print absolute file paths instead of the paths as given on the command line
+    #[arg(long)]
+    pub absolute_paths: bool,
+
+    /// Maximum width of source lines in snippets (defaults to the terminal width)
+    #[arg(long, value_name = "N")]
+    pub max_line_width: Option<usize>,
+
+    /// Show the source snippet under each diagnostic (text output only)
+    #[arg(long, value_name = "BOOL", default_value_t = true, action = clap::ArgAction::Set)]
+    pub show_source: bool,
+
+    /// Follow each diagnostic with a short explanation of its rule (text output only)
+    #[arg(long)]
+    pub explain_diagnostics: bool,
+
+    /// When to color text output (auto, always, or never); auto colors only a terminal
+    #[arg(long, default_value = "auto")]
+    pub color: String,
+
+    /// Enable all preview rules (see `bwq explain` for a rule's stability)
+    #[arg(long)]
+    pub preview: bool,
+
+    /// Enable specific preview rules by code (can be used multiple times)
+    #[arg(long, value_name = "CODE", value_delimiter = ',')]
+    pub extend_select: Vec<String>,
+
+    /// Only report these codes or names, replacing `select` from bwq.toml (can be used multiple
+    /// times)
+    #[arg(long, value_name = "CODE", value_delimiter = ',')]
+    pub select: Vec<String>,
+
+    /// Turn off rules by rule name, code or name, on top of `[rules]` in bwq.toml (can be used
+    /// multiple times)
+    #[arg(long, value_name = "RULE", value_delimiter = ',')]
+    pub ignore: Vec<String>,
+
+    /// Rewrite files in place with the available autofixes, then report what is left
+    #[arg(long, conflicts_with_all = ["query", "preview_fixes"])]
+    pub fix: bool,
+
+    /// List the autofixes available for each diagnostic (old → new) without changing anything
+    #[arg(long)]
+    pub preview_fixes: bool,
+
+    /// Stop at the first parse error in each query instead of recovering and reporting the rest
+    #[arg(long)]
+    pub no_recover: bool,
+
+    /// Write a JSON summary of file and diagnostic counts to this file (file checks only)
+    #[arg(long, value_name = "PATH")]
+    pub summary_json: Option<PathBuf>,
+
+    /// Append a markdown table of diagnostics to this file (file checks only)
+    #[arg(long, value_name = "PATH")]
+    pub markdown_summary: Option<PathBuf>,
+
+    /// Append the markdown summary to $GITHUB_STEP_SUMMARY, when it is set
+    #[arg(long)]
+    pub github_step_summary: bool,
+
+    /// Print the (possibly partial) AST to stderr for queries with any diagnostic
+    #[arg(long)]
+    pub print_ast_on_error: bool,
+
+    /// Lint one query per line of stdin, printing one JSON object per line (NDJSON) until
+    /// stdin closes
+    #[arg(long, conflicts_with_all = ["query", "fix", "preview_fixes"])]
+    pub watch_stdin: bool,
+
+    /// Skip files matching the gitignore-style patterns in this file (`.bwqignore` files are
+    /// always read)
+    #[arg(long, value_name = "PATH")]
+    pub ignore_file: Option<PathBuf>,
+
+    /// Only check files modified within this long, e.g. 30m, 1h or 2d
+    #[arg(long, value_name = "DURATION")]
+    pub since: Option<String>,
+
+    /// Reuse results for unchanged files from `.bwq-cache` in the working directory, and update it
+    #[arg(long)]
+    pub cache: bool,
+
+    /// Maximum number of files to lint in parallel [default: number of CPUs]
+    #[arg(long, short = 'j', value_name = "N")]
+    pub jobs: Option<usize>,
+}
+
+#[derive(Args)]
+pub struct ExplainArgs {
+    /// Diagnostic code to explain
+    pub code: String,
+
+    /// Output the rule metadata as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct FormatArgs {
+    /// Files or directories to format (ignored if --query is used) [default: .]
+    pub files: Vec<PathBuf>,
+
+    /// Print a query string formatted (instead of formatting files)
+    #[arg(long, short = 'q')]
+    pub query: Option<String>,
+
+    /// List files that would be reformatted without writing them, and exit 1 if there are any
+    #[arg(long)]
+    pub check: bool,
+
+    /// File extensions to format (can be used multiple times)
+    #[arg(long = "extension", short = 'e', default_values = ["bwq"])]
+    pub extensions: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct ServerArgs {
+    /// Communicate over stdin/stdout (the default, and currently only, transport)
+    #[arg(long)]
+    pub stdio: bool,
+
+    /// Start an in-process server, run initialize/shutdown against it, and exit 0 if healthy
+    #[arg(long)]
+    pub health: bool,
 }
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use bwq_linter::AnalysisResult;
+use bwq_linter::error::{LintError, LintWarning};
+use bwq_linter::fix::Fix;
+use serde::{Deserialize, Serialize};
+
+/// name of the results cache written by `bwq check --cache`, in the working directory
+pub const CACHE_FILE_NAME: &str = ".bwq-cache";
+
+/// Lint results from a previous run, keyed by file path.
+///
+/// An entry is reused only while the file's content hash matches. The whole cache is dropped
+/// when the bwq version or the project configuration changes, since either can change results.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ResultsCache {
+    version: String,
+    settings: u64,
+    files: HashMap<PathBuf, CachedResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedResult {
+    content_hash: u64,
+    is_valid: bool,
+    errors: Vec<LintError>,
+    warnings: Vec<LintWarning>,
+    fixes: Vec<Fix>,
+}
+
+impl ResultsCache {
+    /// the cache at `path` if it was written by this version with the same `settings`,
+    /// otherwise an empty one
+    pub fn load(path: &Path, settings: &impl Hash) -> Self {
+        let empty = Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            settings: hash(settings),
+            files: HashMap::new(),
+        };
+
+        std::fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Self>(&bytes).ok())
+            .filter(|cache| cache.version == empty.version && cache.settings == empty.settings)
+            .unwrap_or(empty)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), anyhow::Error> {
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// the cached analysis of `file` if its `query` is unchanged
+    pub fn get(&self, file: &Path, query: &str) -> Option<AnalysisResult> {
+        let cached = self.files.get(file)?;
+        (cached.content_hash == hash(query)).then(|| AnalysisResult {
+            is_valid: cached.is_valid,
+            errors: cached.errors.clone(),
+            warnings: cached.warnings.clone(),
+            fixes: cached.fixes.clone(),
+            query: query.to_string(),
+        })
+    }
+
+    pub fn insert(&mut self, file: PathBuf, analysis: &AnalysisResult) {
+        self.files.insert(
+            file,
+            CachedResult {
+                content_hash: hash(&analysis.query),
+                is_valid: analysis.is_valid,
+                errors: analysis.errors.clone(),
+                warnings: analysis.warnings.clone(),
+                fixes: analysis.fixes.clone(),
+            },
+        );
+    }
+}
+
+fn hash(value: &(impl Hash + ?Sized)) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
@@ -1,45 +1,190 @@
 use std::fs;
+use std::io::BufRead;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use ignore::WalkBuilder;
 use rayon::prelude::*;
 
 use crate::{
     ExitStatus,
-    output::{FileResults, OutputFormat, Printer},
+    args::CheckArgs,
+    cache::{CACHE_FILE_NAME, ResultsCache},
+    output::{self, ColorChoice, FileResults, GroupBy, OutputFormat, Printer, SortFiles},
 };
-use bwq_linter::analyze_query;
-
-pub fn run_check(
-    files: Vec<PathBuf>,
-    query: Option<String>,
-    no_warnings: bool,
-    output_format: String,
-    extensions: Vec<String>,
-    exit_zero: bool,
-) -> Result<ExitStatus, anyhow::Error> {
-    let show_warnings = !no_warnings;
+use bwq_linter::{
+    AnalysisResult, BrandwatchLinter,
+    config::{self, LinterConfig, RuleSeverity},
+    fix, registry,
+};
+
+/// per-directory ignore files, read like `.gitignore` wherever they appear
+const IGNORE_FILE_NAME: &str = ".bwqignore";
+
+pub fn run_check(args: CheckArgs) -> Result<ExitStatus, anyhow::Error> {
+    let show_warnings = !args.no_warnings;
+    let printer = Printer::new(
+        OutputFormat::from(args.output_format.as_str()),
+        show_warnings,
+    )
+    .with_group_by(GroupBy::from(args.group_by.as_str()))
+    .with_max_line_width(
+        args.max_line_width
+            .unwrap_or_else(output::default_max_line_width),
+    )
+    .with_show_source(args.show_source)
+    .with_absolute_paths(args.absolute_paths)
+    .with_explain(args.explain_diagnostics)
+    .with_color(ColorChoice::from(args.color.as_str()).should_color());
+
+    let mut config = match LinterConfig::discover(&std::env::current_dir()?) {
+        Some(path) => LinterConfig::load(&path)?,
+        None => LinterConfig::default(),
+    };
+    if let Some(unknown) = args
+        .extend_select
+        .iter()
+        .find(|code| registry::lookup(code).is_none())
+    {
+        anyhow::bail!("Unknown diagnostic code '{unknown}' in --extend-select");
+    }
+    if let Some(unknown) = args
+        .select
+        .iter()
+        .find(|key| registry::resolve(key).is_none())
+    {
+        anyhow::bail!("Unknown diagnostic code '{unknown}' in --select");
+    }
+    if let Some(unknown) = args.ignore.iter().find(|key| !config::is_rule_key(key)) {
+        anyhow::bail!("Unknown rule '{unknown}' in --ignore");
+    }
+    config.preview |= args.preview;
+    config.extend_select.extend(args.extend_select);
+    // command-line flags win over bwq.toml: selected codes are reported even if the file turns
+    // their rule off (nothing else is, so every "off" can go), and ignored ones are always off
+    if !args.select.is_empty() {
+        config
+            .rules
+            .retain(|_, severity| *severity != RuleSeverity::Off);
+        config.select = args.select;
+    }
+    for key in args.ignore {
+        config.rules.insert(key, RuleSeverity::Off);
+    }
+    config.no_recover |= args.no_recover;
 
-    if let Some(query_str) = query {
+    let ast_json = args.output_format.eq_ignore_ascii_case("ast-json");
+    if ast_json && args.query.is_none() {
+        anyhow::bail!("--output-format ast-json requires --query");
+    }
+
+    if args.watch_stdin {
+        return watch_stdin(&config, &printer);
+    }
+
+    if let Some(query_str) = args.query {
+        if ast_json {
+            return print_ast_json(&query_str, &config);
+        }
+        if let Some(emit) = args.emit {
+            return emit_query(&query_str, &emit, &config, &printer);
+        }
+        if args.preview_fixes {
+            let analysis = analyze(&query_str, &config, None);
+            printer.print_fix_preview([("query".to_string(), &analysis)]);
+            return Ok(ExitStatus::Success);
+        }
         Ok(check_single_query_string(
             &query_str,
-            show_warnings,
-            &output_format,
-            exit_zero,
+            &config,
+            &printer,
+            args.exit_zero,
+            args.print_ast_on_error,
         ))
     } else {
-        let target_files = if files.is_empty() {
+        let target_files = if args.files.is_empty() {
             vec![PathBuf::from(".")]
         } else {
-            files
+            args.files
+        };
+
+        let modified_since = match &args.since {
+            Some(since) => {
+                let Some(duration) = parse_duration(since) else {
+                    anyhow::bail!(
+                        "Invalid --since duration '{since}' (expected e.g. 30m, 1h or 2d)"
+                    );
+                };
+                // a duration reaching back before 1970 checks every file
+                Some(
+                    SystemTime::now()
+                        .checked_sub(duration)
+                        .unwrap_or(UNIX_EPOCH),
+                )
+            }
+            None => None,
         };
 
-        let results = check_files(&target_files, &extensions)?;
+        // the config is part of the key: changing bwq.toml can change every result
+        let mut cache = args
+            .cache
+            .then(|| ResultsCache::load(Path::new(CACHE_FILE_NAME), &format!("{config:?}")));
 
-        let printer = Printer::new(OutputFormat::from(output_format.as_str()), show_warnings);
+        let filter = FileFilter {
+            extensions: &args.extensions,
+            modified_since,
+            ignore_file: args.ignore_file.as_deref(),
+        };
+        let mut results = check_files(
+            &target_files,
+            &filter,
+            &config,
+            args.jobs,
+            args.print_ast_on_error,
+            cache.as_ref(),
+        )?;
+
+        if args.fix {
+            let (fixed, files) = fix_files(&mut results, &config)?;
+            if fixed > 0 {
+                let issues = if fixed == 1 { "issue" } else { "issues" };
+                let file_count = if files == 1 { "file" } else { "files" };
+                eprintln!("Fixed {fixed} {issues} in {files} {file_count}");
+            }
+        }
+
+        if let Some(cache) = &mut cache {
+            for (file_path, analysis, _) in &results.successful {
+                cache.insert(file_path.clone(), analysis);
+            }
+            cache.save(Path::new(CACHE_FILE_NAME))?;
+        }
+
+        results.sort_files(SortFiles::from(args.sort_files.as_str()));
+        if args.preview_fixes {
+            printer.print_fix_preview(
+                results
+                    .successful
+                    .iter()
+                    .map(|(file_path, analysis, _)| (printer.display_path(file_path), analysis)),
+            );
+            return Ok(ExitStatus::Success);
+        }
         printer.print_file_results(&results);
 
-        Ok(if results.has_errors() && !exit_zero {
+        let summary_path = args.markdown_summary.or_else(|| {
+            args.github_step_summary
+                .then(|| std::env::var_os("GITHUB_STEP_SUMMARY").map(PathBuf::from))
+                .flatten()
+        });
+        if let Some(path) = summary_path {
+            printer.write_markdown_summary(&results, &path)?;
+        }
+        if let Some(path) = &args.summary_json {
+            printer.write_json_summary(&results, path)?;
+        }
+
+        Ok(if results.has_errors() && !args.exit_zero {
             ExitStatus::LintFailure
         } else {
             ExitStatus::Success
@@ -47,7 +192,23 @@ pub fn run_check(
     }
 }
 
-fn check_files(paths: &[PathBuf], extensions: &[String]) -> Result<FileResults, anyhow::Error> {
+/// which files under a directory argument get checked
+pub(crate) struct FileFilter<'a> {
+    pub(crate) extensions: &'a [String],
+    /// only files modified at or after this time
+    pub(crate) modified_since: Option<SystemTime>,
+    /// gitignore-style patterns to skip, on top of `.gitignore` and `.bwqignore`
+    pub(crate) ignore_file: Option<&'a Path>,
+}
+
+fn check_files(
+    paths: &[PathBuf],
+    filter: &FileFilter,
+    config: &LinterConfig,
+    jobs: Option<usize>,
+    print_ast: bool,
+    cache: Option<&ResultsCache>,
+) -> Result<FileResults, anyhow::Error> {
     // Validate that all paths exist
     for file_path in paths {
         if !file_path.exists() {
@@ -55,30 +216,46 @@ fn check_files(paths: &[PathBuf], extensions: &[String]) -> Result<FileResults,
         }
     }
 
-    let files = discover_files(paths, extensions);
+    let files = discover_files(paths, filter)?;
 
     if files.is_empty() {
         eprintln!(
             "Warning: No files found that have the extension(s): {}",
-            extensions.join(", ")
+            filter.extensions.join(", ")
         );
         return Ok(FileResults::new());
     }
 
-    let results: Vec<_> = files
-        .par_iter()
-        .map(|file_path| match fs::read_to_string(file_path) {
-            Ok(content) => {
-                let query = content.trim();
-                let analysis = analyze_query(query);
-                Ok((file_path.clone(), analysis, query.to_string()))
-            }
-            Err(e) => {
-                eprintln!("Error reading file {}: {}", file_path.display(), e);
-                Err(file_path.clone())
-            }
-        })
-        .collect();
+    // 0 threads lets rayon pick the number of CPUs
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.unwrap_or(0))
+        .build()?;
+
+    // files are sorted and par_iter().collect() keeps that order, so output is stable
+    // regardless of how many threads are used
+    let results: Vec<_> = pool.install(|| {
+        files
+            .par_iter()
+            .map(|file_path| match fs::read_to_string(file_path) {
+                Ok(content) => {
+                    let query = content.trim();
+                    let label = file_path.display().to_string();
+                    // a cache hit has no AST to print, so --print-ast-on-error always re-lints
+                    let analysis = cache
+                        .filter(|_| !print_ast)
+                        .and_then(|cache| cache.get(file_path, query))
+                        .unwrap_or_else(|| {
+                            analyze(query, config, print_ast.then_some(label.as_str()))
+                        });
+                    Ok((file_path.clone(), analysis, query.to_string()))
+                }
+                Err(e) => {
+                    eprintln!("Error reading file {}: {}", file_path.display(), e);
+                    Err(file_path.clone())
+                }
+            })
+            .collect()
+    });
 
     let read_errors = results.iter().filter(|r| r.is_err()).count();
     let successful = results.into_iter().filter_map(|r| r.ok()).collect();
@@ -91,12 +268,12 @@ fn check_files(paths: &[PathBuf], extensions: &[String]) -> Result<FileResults,
 
 fn check_single_query_string(
     query: &str,
-    show_warnings: bool,
-    output_format: &str,
+    config: &LinterConfig,
+    printer: &Printer,
     exit_zero: bool,
+    print_ast: bool,
 ) -> ExitStatus {
-    let analysis = analyze_query(query);
-    let printer = Printer::new(OutputFormat::from(output_format), show_warnings);
+    let analysis = analyze(query, config, print_ast.then_some("query"));
     printer.print_analysis(&analysis);
 
     if analysis.is_valid || exit_zero {
@@ -106,6 +283,87 @@ fn check_single_query_string(
     }
 }
 
+/// `--watch-stdin`: one NDJSON line of diagnostics per line read, for editors that shell out on
+/// every keystroke instead of speaking LSP
+fn watch_stdin(config: &LinterConfig, printer: &Printer) -> Result<ExitStatus, anyhow::Error> {
+    let mut linter = BrandwatchLinter::new().with_config(config);
+    for line in std::io::stdin().lock().lines() {
+        printer.print_ndjson(&linter.analyze(&line?));
+    }
+    Ok(ExitStatus::Success)
+}
+
+/// `--emit pretty`: print the query as an indented clause tree, or its errors if it has any
+fn emit_query(
+    query: &str,
+    emit: &str,
+    config: &LinterConfig,
+    printer: &Printer,
+) -> Result<ExitStatus, anyhow::Error> {
+    if emit != "pretty" {
+        anyhow::bail!("Unknown --emit format '{emit}' (expected 'pretty')");
+    }
+
+    let analysis = BrandwatchLinter::new()
+        .with_config(config)
+        .analyze_for_server(query);
+    match analysis.ast {
+        Some(ast) if analysis.is_valid => {
+            println!("{}", ast.pretty(4));
+            Ok(ExitStatus::Success)
+        }
+        _ => {
+            printer.print_analysis(&AnalysisResult {
+                is_valid: analysis.is_valid,
+                errors: analysis.errors,
+                warnings: analysis.warnings,
+                fixes: analysis.fixes,
+                query: analysis.query,
+            });
+            Ok(ExitStatus::LintFailure)
+        }
+    }
+}
+
+/// dump the parsed AST of `query` as JSON, or its first parse error when it doesn't parse
+fn print_ast_json(query: &str, config: &LinterConfig) -> Result<ExitStatus, anyhow::Error> {
+    match BrandwatchLinter::new().with_config(config).parse(query) {
+        Ok(ast) => {
+            println!("{}", serde_json::to_string_pretty(&ast)?);
+            Ok(ExitStatus::Success)
+        }
+        Err(error) => {
+            let errors = serde_json::json!({ "errors": [error.to_json()] });
+            println!("{}", serde_json::to_string_pretty(&errors)?);
+            Ok(ExitStatus::LintFailure)
+        }
+    }
+}
+
+/// lint one query; with `print_ast_label`, dump its AST to stderr when there are diagnostics
+fn analyze(query: &str, config: &LinterConfig, print_ast_label: Option<&str>) -> AnalysisResult {
+    let mut linter = BrandwatchLinter::new().with_config(config);
+    let Some(label) = print_ast_label else {
+        return linter.analyze(query);
+    };
+
+    let analysis = linter.analyze_for_server(query);
+    if !analysis.errors.is_empty() || !analysis.warnings.is_empty() {
+        match &analysis.ast {
+            Some(ast) => eprintln!("AST for {label}:\n{ast:#?}"),
+            None => eprintln!("AST for {label}: none (the query failed before parsing finished)"),
+        }
+    }
+
+    AnalysisResult {
+        is_valid: analysis.is_valid,
+        errors: analysis.errors,
+        warnings: analysis.warnings,
+        fixes: analysis.fixes,
+        query: analysis.query,
+    }
+}
+
 fn matches_extensions(file_path: &Path, extensions: &[String]) -> bool {
     if let Some(file_ext) = file_path.extension().and_then(|ext| ext.to_str()) {
         extensions.iter().any(|ext| ext == file_ext)
@@ -114,7 +372,80 @@ fn matches_extensions(file_path: &Path, extensions: &[String]) -> bool {
     }
 }
 
-fn discover_files(paths: &[PathBuf], extensions: &[String]) -> Vec<PathBuf> {
+/// re-lint and fix again until nothing changes, since overlapping fixes wait for the next pass
+const MAX_FIX_PASSES: usize = 10;
+
+/// apply autofixes to each file in place, replacing its result with the re-linted one.
+/// Returns how many fixes were applied and to how many files
+fn fix_files(
+    results: &mut FileResults,
+    config: &LinterConfig,
+) -> Result<(usize, usize), anyhow::Error> {
+    let (mut total, mut files) = (0, 0);
+    for (file_path, analysis, query) in &mut results.successful {
+        let mut fixed = 0;
+        for _ in 0..MAX_FIX_PASSES {
+            let (source, applied) = fix::apply_with_count(query, &analysis.fixes);
+            if applied == 0 || source == *query {
+                break;
+            }
+            fixed += applied;
+            *analysis = analyze(&source, config, None);
+            *query = source;
+        }
+        if fixed == 0 {
+            continue;
+        }
+
+        // results are for the trimmed file, so keep its surrounding whitespace as it was
+        let content = fs::read_to_string(&*file_path)?;
+        let leading = content.len() - content.trim_start().len();
+        let trailing = content.trim_end().len();
+        let content = format!("{}{query}{}", &content[..leading], &content[trailing..]);
+        fs::write(&*file_path, content)?;
+        total += fixed;
+        files += 1;
+    }
+    Ok((total, files))
+}
+
+/// `30m`, `1h`, `2d`, or several of them together like `1h30m`
+fn parse_duration(text: &str) -> Option<Duration> {
+    let mut total: u64 = 0;
+    let mut rest = text.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let amount: u64 = rest[..digits].parse().ok()?;
+        let unit_len = rest[digits..]
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len() - digits);
+        let seconds = match &rest[digits..digits + unit_len] {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+        total = amount
+            .checked_mul(seconds)
+            .and_then(|seconds| total.checked_add(seconds))?;
+        rest = &rest[digits + unit_len..];
+    }
+
+    Some(Duration::from_secs(total))
+}
+
+/// files under `paths` that pass `filter` and aren't ignored (explicit file arguments are
+/// always kept)
+pub(crate) fn discover_files(
+    paths: &[PathBuf],
+    filter: &FileFilter,
+) -> Result<Vec<PathBuf>, anyhow::Error> {
     let mut discovered_files = Vec::new();
 
     for path in paths {
@@ -124,16 +455,38 @@ fn discover_files(paths: &[PathBuf], extensions: &[String]) -> Vec<PathBuf> {
         } else if path.is_dir() {
             // For directories, discover files with matching extensions
             let mut builder = WalkBuilder::new(path);
-            builder.hidden(false);
+            builder
+                .hidden(false)
+                .add_custom_ignore_filename(IGNORE_FILE_NAME);
+            if let Some(ignore_file) = filter.ignore_file {
+                if let Some(error) = builder.add_ignore(ignore_file) {
+                    anyhow::bail!(
+                        "Failed to read ignore file {}: {error}",
+                        ignore_file.display()
+                    );
+                }
+            }
 
             for dir_entry in builder.build().flatten() {
                 let file_path = dir_entry.path();
-                if file_path.is_file() && matches_extensions(file_path, extensions) {
+                let recent = || {
+                    filter.modified_since.is_none_or(|since| {
+                        dir_entry
+                            .metadata()
+                            .and_then(|metadata| Ok(metadata.modified()?))
+                            .is_ok_and(|modified| modified >= since)
+                    })
+                };
+                if file_path.is_file()
+                    && matches_extensions(file_path, filter.extensions)
+                    && recent()
+                {
                     discovered_files.push(file_path.to_path_buf());
                 }
             }
         }
     }
 
-    discovered_files
+    discovered_files.sort();
+    Ok(discovered_files)
 }
@@ -0,0 +1,31 @@
+use crate::{ExitStatus, args::ExplainArgs};
+use bwq_linter::registry;
+
+pub fn run_explain(args: ExplainArgs) -> Result<ExitStatus, anyhow::Error> {
+    let Some(info) = registry::lookup(&args.code) else {
+        eprintln!("Error: Unknown diagnostic code '{}'", args.code);
+        return Ok(ExitStatus::Error);
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&info.to_json())?);
+    } else {
+        println!("{} ({}) [{}]", info.code, info.name, info.severity.as_str());
+        if info.stability == registry::Stability::Preview {
+            println!(
+                "Preview: enable with --preview or --extend-select {}",
+                info.code
+            );
+        }
+        println!();
+        println!("{}", info.summary);
+        println!();
+        println!("Before:");
+        println!("  {}", info.example_before);
+        println!();
+        println!("After:");
+        println!("  {}", info.example_after);
+    }
+
+    Ok(ExitStatus::Success)
+}
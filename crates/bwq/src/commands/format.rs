@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::{
+    ExitStatus,
+    args::FormatArgs,
+    commands::check::{FileFilter, discover_files},
+};
+use bwq_linter::config::LinterConfig;
+use bwq_linter::format::format_query_with;
+
+pub fn run_format(args: FormatArgs) -> Result<ExitStatus, anyhow::Error> {
+    // parse as `bwq check` does, so `//` is only a comment where line_comments is on
+    let config = match LinterConfig::discover(&std::env::current_dir()?) {
+        Some(path) => LinterConfig::load(&path)?,
+        None => LinterConfig::default(),
+    };
+
+    if let Some(query) = args.query {
+        return match format_query_with(&query, &config) {
+            Ok(formatted) => {
+                println!("{formatted}");
+                Ok(ExitStatus::Success)
+            }
+            Err(error) => {
+                eprintln!("Error: {error}");
+                Ok(ExitStatus::LintFailure)
+            }
+        };
+    }
+
+    let paths = if args.files.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        args.files
+    };
+    for path in &paths {
+        if !path.exists() {
+            anyhow::bail!("Path does not exist: {}", path.display());
+        }
+    }
+
+    let filter = FileFilter {
+        extensions: &args.extensions,
+        modified_since: None,
+        ignore_file: None,
+    };
+    let files = discover_files(&paths, &filter)?;
+
+    let mut changed = 0;
+    let mut failed = 0;
+    for file_path in &files {
+        let content = fs::read_to_string(file_path)?;
+        let formatted = match format_query_with(&content, &config) {
+            Ok(formatted) if formatted.is_empty() => formatted,
+            Ok(formatted) => format!("{formatted}\n"),
+            // a query that doesn't parse can't be formatted; `bwq check` explains why
+            Err(error) => {
+                eprintln!("Failed to format {}: {error}", file_path.display());
+                failed += 1;
+                continue;
+            }
+        };
+        if formatted == content {
+            continue;
+        }
+
+        changed += 1;
+        if args.check {
+            println!("Would reformat: {}", file_path.display());
+        } else {
+            fs::write(file_path, formatted)?;
+        }
+    }
+
+    let unchanged = files.len() - changed - failed;
+    let files_word = |count: usize| if count == 1 { "file" } else { "files" };
+    let verb = if args.check {
+        "would be reformatted"
+    } else {
+        "reformatted"
+    };
+    eprintln!(
+        "{changed} {} {verb}, {unchanged} {} left unchanged",
+        files_word(changed),
+        files_word(unchanged)
+    );
+
+    if failed > 0 || (args.check && changed > 0) {
+        Ok(ExitStatus::LintFailure)
+    } else {
+        Ok(ExitStatus::Success)
+    }
+}
@@ -1,3 +1,6 @@
 pub(crate) mod check;
 pub(crate) mod examples;
+pub(crate) mod explain;
+pub(crate) mod format;
 pub(crate) mod server;
+pub(crate) mod version;
@@ -1,7 +1,20 @@
-use crate::ExitStatus;
+use crate::{ExitStatus, args::ServerArgs};
 use anyhow::Result;
 
-pub(crate) fn run_server() -> Result<ExitStatus> {
+pub(crate) fn run_server(args: ServerArgs) -> Result<ExitStatus> {
+    if args.health {
+        return Ok(match bwq_server::health_check() {
+            Ok(()) => {
+                println!("bwq language server is healthy");
+                ExitStatus::Success
+            }
+            Err(err) => {
+                eprintln!("bwq language server health check failed: {err:#}");
+                ExitStatus::Error
+            }
+        });
+    }
+
     bwq_server::run()?;
     Ok(ExitStatus::Success)
 }
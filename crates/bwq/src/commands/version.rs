@@ -0,0 +1,28 @@
+use bwq_linter::registry;
+
+use crate::ExitStatus;
+
+/// git commit the binary was built from, set by build.rs
+const COMMIT_HASH: &str = env!("BWQ_COMMIT_HASH");
+
+pub fn run_version(verbose: bool) -> Result<ExitStatus, anyhow::Error> {
+    println!("bwq {}", env!("CARGO_PKG_VERSION"));
+    if verbose {
+        let features = bwq_linter::enabled_features();
+        println!("commit: {COMMIT_HASH}");
+        println!(
+            "features: {}",
+            if features.is_empty() {
+                "none".to_string()
+            } else {
+                features.join(", ")
+            }
+        );
+        println!(
+            "rule registry: {} ({} codes)",
+            registry::VERSION,
+            registry::all().len()
+        );
+    }
+    Ok(ExitStatus::Success)
+}
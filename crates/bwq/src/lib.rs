@@ -1,4 +1,5 @@
 pub mod args;
+mod cache;
 mod commands;
 mod output;
 
@@ -18,30 +19,24 @@ impl From<ExitStatus> for i32 {
 }
 
 pub fn run(args: Cli) -> Result<ExitStatus, anyhow::Error> {
+    if args.version {
+        return commands::version::run_version(args.verbose);
+    }
+
     match args.command {
-        Some(args::Commands::Check {
-            files,
-            query,
-            no_warnings,
-            output_format,
-            extensions,
-            exit_zero,
-        }) => commands::check::run_check(
-            files,
-            query,
-            no_warnings,
-            output_format,
-            extensions,
-            exit_zero,
-        ),
+        Some(args::Commands::Check(check_args)) => commands::check::run_check(*check_args),
         Some(args::Commands::Examples) => commands::examples::run_examples(),
-        Some(args::Commands::Server) => commands::server::run_server(),
+        Some(args::Commands::Explain(explain_args)) => commands::explain::run_explain(explain_args),
+        Some(args::Commands::Format(format_args)) => commands::format::run_format(format_args),
+        Some(args::Commands::Server(server_args)) => commands::server::run_server(server_args),
         None => {
             eprintln!("Error: A subcommand is required");
             eprintln!("\nUsage: bwq <COMMAND>");
             eprintln!("\nCommands:");
             eprintln!("  check        Lint files, directories, or queries");
             eprintln!("  examples     Show example queries");
+            eprintln!("  explain      Explain a diagnostic code");
+            eprintln!("  format       Format query files or a query string");
             eprintln!("  server       Start language server");
             eprintln!("\nFor more information, try 'bwq --help'");
             Ok(ExitStatus::Error)
@@ -1,8 +1,9 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use bwq_linter::{
     AnalysisResult,
     error::{LintError, LintWarning},
+    registry,
 };
 
 #[derive(Debug)]
@@ -22,12 +23,117 @@ struct UnderlineStyle {
 pub struct Printer {
     pub format: OutputFormat,
     pub show_warnings: bool,
+    pub group_by: GroupBy,
+    /// maximum display width of a source line in a snippet before it is truncated
+    pub max_line_width: usize,
+    /// print the source snippet and underline under each text diagnostic header
+    pub show_source: bool,
+    /// emit ANSI color codes in text output
+    pub color: bool,
+    /// render file paths as absolute paths in every format
+    pub absolute_paths: bool,
+    /// follow each text diagnostic with its rule summary from the registry
+    pub explain: bool,
+}
+
+const BOLD: &str = "\x1b[1m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const BOLD_YELLOW: &str = "\x1b[1;33m";
+const RESET: &str = "\x1b[0m";
+
+/// When to color text output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// only when stdout is a terminal
+    Auto,
+    Always,
+    Never,
+}
+
+impl From<&str> for ColorChoice {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "always" => ColorChoice::Always,
+            "never" => ColorChoice::Never,
+            _ => ColorChoice::Auto,
+        }
+    }
+}
+
+impl ColorChoice {
+    pub fn should_color(self) -> bool {
+        match self {
+            ColorChoice::Auto => terminal::is_terminal(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OutputFormat {
     Text,
     Json,
+    CodeClimate,
+    Sarif,
+}
+
+/// How text diagnostics are ordered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    /// per file, errors before warnings (discovery order)
+    File,
+    /// all diagnostics with the same code together
+    Code,
+    /// all errors, then all warnings
+    Severity,
+}
+
+impl From<&str> for GroupBy {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "code" => GroupBy::Code,
+            "severity" => GroupBy::Severity,
+            _ => GroupBy::File,
+        }
+    }
+}
+
+/// Order of files in directory output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortFiles {
+    /// by path
+    Name,
+    /// most errors first, then most warnings
+    Diagnostics,
+}
+
+impl From<&str> for SortFiles {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "diagnostics" => SortFiles::Diagnostics,
+            _ => SortFiles::Name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Diagnostic<'a> {
+    Error(&'a LintError),
+    Warning(&'a LintWarning),
+}
+
+impl Diagnostic<'_> {
+    fn code(&self) -> &'static str {
+        match self {
+            Diagnostic::Error(error) => error.code(),
+            Diagnostic::Warning(warning) => warning.code(),
+        }
+    }
+
+    fn is_error(&self) -> bool {
+        matches!(self, Diagnostic::Error(_))
+    }
 }
 
 #[derive(Debug)]
@@ -50,6 +156,16 @@ impl FileResults {
         }
     }
 
+    /// stable reordering, so files with equal counts stay in name order
+    pub fn sort_files(&mut self, order: SortFiles) {
+        match order {
+            SortFiles::Name => self.successful.sort_by(|(a, ..), (b, ..)| a.cmp(b)),
+            SortFiles::Diagnostics => self.successful.sort_by_key(|(_, analysis, _)| {
+                std::cmp::Reverse((analysis.errors.len(), analysis.warnings.len()))
+            }),
+        }
+    }
+
     pub fn total_files_processed(&self) -> usize {
         self.successful.len()
     }
@@ -74,6 +190,8 @@ impl From<&str> for OutputFormat {
     fn from(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "json" => OutputFormat::Json,
+            "codeclimate" => OutputFormat::CodeClimate,
+            "sarif" => OutputFormat::Sarif,
             _ => OutputFormat::Text,
         }
     }
@@ -100,6 +218,159 @@ mod width_utils {
 
 use width_utils::{char_width, str_width};
 
+/// Snippet width used when the terminal width can't be detected (e.g. output is piped)
+pub const DEFAULT_MAX_LINE_WIDTH: usize = 200;
+
+/// Room reserved for the `NNN | ` gutter in front of each snippet line
+const GUTTER_WIDTH: usize = 8;
+
+/// Terminal width detection
+mod terminal {
+    use std::io::IsTerminal;
+
+    pub fn is_terminal() -> bool {
+        std::io::stdout().is_terminal()
+    }
+
+    /// Width of the terminal attached to stdout, if any
+    pub fn width() -> Option<usize> {
+        if !is_terminal() {
+            return None;
+        }
+        ioctl_width().or_else(|| {
+            std::env::var("COLUMNS")
+                .ok()
+                .and_then(|columns| columns.parse().ok())
+        })
+    }
+
+    #[cfg(unix)]
+    fn ioctl_width() -> Option<usize> {
+        let mut size = libc::winsize {
+            ws_row: 0,
+            ws_col: 0,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        // SAFETY: TIOCGWINSZ only writes into the winsize struct we pass in
+        let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+        (result == 0 && size.ws_col > 0).then_some(size.ws_col as usize)
+    }
+
+    #[cfg(not(unix))]
+    fn ioctl_width() -> Option<usize> {
+        None
+    }
+}
+
+/// Snippet width for the current terminal, or [`DEFAULT_MAX_LINE_WIDTH`] when not attached to one
+pub fn default_max_line_width() -> usize {
+    terminal::width()
+        .map(|width| width.saturating_sub(GUTTER_WIDTH).max(40))
+        .unwrap_or(DEFAULT_MAX_LINE_WIDTH)
+}
+
+/// Code Climate report helpers (consumed by GitLab code quality)
+mod codeclimate {
+    /// Stable 64-bit FNV-1a hash, so fingerprints don't change between runs or toolchains
+    fn fnv1a(parts: &[&str]) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for part in parts {
+            for byte in part.bytes().chain(std::iter::once(0)) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+        hash
+    }
+
+    pub fn issue(
+        code: &str,
+        message: &str,
+        path: &str,
+        line: usize,
+        severity: &str,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "description": message,
+            "check_name": code,
+            "fingerprint": format!("{:016x}", fnv1a(&[code, path, message])),
+            "severity": severity,
+            "location": {
+                "path": path,
+                "lines": { "begin": line }
+            }
+        })
+    }
+}
+
+/// SARIF 2.1.0 report helpers (consumed by GitHub and GitLab code scanning)
+mod sarif {
+    use bwq_linter::{error::Span, registry};
+
+    /// one finding; SARIF regions are 1-based with an exclusive end column, like `Span`
+    pub fn result(
+        code: &str,
+        message: &str,
+        path: &str,
+        span: &Span,
+        level: &str,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "ruleId": code,
+            "level": level,
+            "message": { "text": message },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": path },
+                    "region": {
+                        "startLine": span.start.line,
+                        "startColumn": span.start.column,
+                        "endLine": span.end.line,
+                        "endColumn": span.end.column
+                    }
+                }
+            }]
+        })
+    }
+
+    /// a complete log with a single run, describing every diagnostic code as a rule
+    pub fn log(results: Vec<serde_json::Value>) -> serde_json::Value {
+        let rules: Vec<_> = registry::all()
+            .iter()
+            .map(|info| {
+                serde_json::json!({
+                    "id": info.code,
+                    "name": info.name,
+                    "shortDescription": { "text": info.summary },
+                    "defaultConfiguration": { "level": info.severity.as_str() }
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "bwq",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules
+                    }
+                },
+                "results": results
+            }]
+        })
+    }
+}
+
+/// escape `text` for a single markdown table cell
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
 #[derive(Debug)]
 struct ContextWindow {
     start_char: usize,
@@ -182,12 +453,65 @@ impl Printer {
         Self {
             format,
             show_warnings,
+            group_by: GroupBy::File,
+            max_line_width: DEFAULT_MAX_LINE_WIDTH,
+            show_source: true,
+            color: false,
+            absolute_paths: false,
+            explain: false,
+        }
+    }
+
+    pub fn with_group_by(mut self, group_by: GroupBy) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    pub fn with_max_line_width(mut self, max_line_width: usize) -> Self {
+        self.max_line_width = max_line_width;
+        self
+    }
+
+    pub fn with_show_source(mut self, show_source: bool) -> Self {
+        self.show_source = show_source;
+        self
+    }
+
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_absolute_paths(mut self, absolute_paths: bool) -> Self {
+        self.absolute_paths = absolute_paths;
+        self
+    }
+
+    pub fn with_explain(mut self, explain: bool) -> Self {
+        self.explain = explain;
+        self
+    }
+
+    /// `path` as given, or made absolute (without resolving symlinks) with `--absolute-paths`
+    pub fn display_path(&self, path: &Path) -> String {
+        if self.absolute_paths {
+            if let Ok(absolute) = std::path::absolute(path) {
+                return absolute.display().to_string();
+            }
         }
+        path.display().to_string()
+    }
+
+    /// `code` when color is enabled, otherwise nothing
+    fn ansi(&self, code: &'static str) -> &'static str {
+        if self.color { code } else { "" }
     }
 
     pub fn print_analysis(&self, analysis: &AnalysisResult) {
         match self.format {
             OutputFormat::Json => self.print_json(analysis),
+            OutputFormat::CodeClimate => self.print_codeclimate(analysis),
+            OutputFormat::Sarif => self.print_sarif(analysis),
             OutputFormat::Text => self.print_text(analysis),
         }
     }
@@ -195,24 +519,88 @@ impl Printer {
     pub fn print_file_results(&self, results: &FileResults) {
         match self.format {
             OutputFormat::Json => self.print_file_results_json(results),
+            OutputFormat::CodeClimate => self.print_file_results_codeclimate(results),
+            OutputFormat::Sarif => self.print_file_results_sarif(results),
             OutputFormat::Text => self.print_file_results_text(results),
         }
     }
 
-    fn print_text(&self, analysis: &AnalysisResult) {
-        if !analysis.is_valid {
-            for error in &analysis.errors {
-                self.print_error_with_context(&analysis.query, error, None);
-                println!();
+    /// one line per available autofix, `label:line:col: code edit`, then a count
+    pub fn print_fix_preview<'a>(
+        &self,
+        analyses: impl IntoIterator<Item = (String, &'a AnalysisResult)>,
+    ) {
+        let mut count = 0;
+        for (label, analysis) in analyses {
+            for fix in &analysis.fixes {
+                let old = analysis
+                    .query
+                    .get(fix.span.start.byte_offset..fix.span.end.byte_offset)
+                    .unwrap_or_default();
+                let edit = if old.is_empty() {
+                    format!("insert {:?}", fix.replacement)
+                } else {
+                    format!("{old:?} → {:?}", fix.replacement)
+                };
+                println!(
+                    "{label}:{}:{}: {}{}{} {edit}",
+                    fix.span.start.line,
+                    fix.span.start.column,
+                    self.ansi(BOLD),
+                    fix.code,
+                    self.ansi(RESET),
+                );
+                count += 1;
             }
         }
 
-        if self.show_warnings && !analysis.warnings.is_empty() {
-            for warning in &analysis.warnings {
-                self.print_warning_with_context(&analysis.query, warning, None);
-                println!();
+        match count {
+            0 => println!("No fixes available"),
+            1 => println!("1 fix available"),
+            _ => println!("{count} fixes available"),
+        }
+    }
+
+    /// errors (then warnings, if shown) of a single analysis
+    fn collect_diagnostics<'a>(&self, analysis: &'a AnalysisResult) -> Vec<Diagnostic<'a>> {
+        let mut diagnostics: Vec<_> = analysis.errors.iter().map(Diagnostic::Error).collect();
+        if self.show_warnings {
+            diagnostics.extend(analysis.warnings.iter().map(Diagnostic::Warning));
+        }
+        diagnostics
+    }
+
+    /// stable reordering, so ties keep file and position order
+    fn sort_diagnostics<T>(&self, items: &mut [T], diagnostic: impl Fn(&T) -> Diagnostic<'_>) {
+        match self.group_by {
+            GroupBy::File => {}
+            GroupBy::Code => items.sort_by_key(|item| diagnostic(item).code()),
+            GroupBy::Severity => items.sort_by_key(|item| !diagnostic(item).is_error()),
+        }
+    }
+
+    fn print_diagnostic(&self, query: &str, diagnostic: Diagnostic, file_path: Option<&PathBuf>) {
+        match diagnostic {
+            Diagnostic::Error(error) => self.print_error_with_context(query, error, file_path),
+            Diagnostic::Warning(warning) => {
+                self.print_warning_with_context(query, warning, file_path)
+            }
+        }
+        if self.explain {
+            if let Some(info) = registry::lookup(diagnostic.code()) {
+                println!("  = {}: {}", info.name, info.summary);
             }
         }
+        println!();
+    }
+
+    fn print_text(&self, analysis: &AnalysisResult) {
+        let mut diagnostics = self.collect_diagnostics(analysis);
+        self.sort_diagnostics(&mut diagnostics, |diagnostic| *diagnostic);
+
+        for diagnostic in diagnostics {
+            self.print_diagnostic(&analysis.query, diagnostic, None);
+        }
 
         if analysis.is_valid && (!self.show_warnings || analysis.warnings.is_empty()) {
             println!("All checks passed!");
@@ -220,6 +608,21 @@ impl Printer {
     }
 
     fn print_json(&self, analysis: &AnalysisResult) {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&self.json_output(analysis)).unwrap()
+        );
+    }
+
+    /// the `--output-format json` object for `analysis` on a single line, for NDJSON streams
+    pub fn print_ndjson(&self, analysis: &AnalysisResult) {
+        println!(
+            "{}",
+            serde_json::to_string(&self.json_output(analysis)).unwrap()
+        );
+    }
+
+    fn json_output(&self, analysis: &AnalysisResult) -> serde_json::Value {
         let errors: Vec<_> = analysis.errors.iter().map(|e| e.to_json()).collect();
         let warnings: Vec<_> = if self.show_warnings {
             analysis.warnings.iter().map(|w| w.to_json()).collect()
@@ -227,30 +630,27 @@ impl Printer {
             Vec::new()
         };
 
-        let json_output = serde_json::json!({
+        serde_json::json!({
             "query": analysis.query,
             "errors": errors,
             "warnings": warnings
-        });
-
-        println!("{}", serde_json::to_string_pretty(&json_output).unwrap());
+        })
     }
 
     fn print_file_results_text(&self, results: &FileResults) {
-        for (file_path, analysis, query) in &results.successful {
-            if !analysis.is_valid || (self.show_warnings && !analysis.warnings.is_empty()) {
-                for error in &analysis.errors {
-                    self.print_error_with_context(query, error, Some(file_path));
-                    println!();
-                }
-
-                if self.show_warnings {
-                    for warning in &analysis.warnings {
-                        self.print_warning_with_context(query, warning, Some(file_path));
-                        println!();
-                    }
-                }
-            }
+        let mut diagnostics: Vec<_> = results
+            .successful
+            .iter()
+            .flat_map(|(file_path, analysis, query)| {
+                self.collect_diagnostics(analysis)
+                    .into_iter()
+                    .map(move |diagnostic| (file_path, query.as_str(), diagnostic))
+            })
+            .collect();
+        self.sort_diagnostics(&mut diagnostics, |(_, _, diagnostic)| *diagnostic);
+
+        for (file_path, query, diagnostic) in diagnostics {
+            self.print_diagnostic(query, diagnostic, Some(file_path));
         }
 
         let valid_files = results.valid_files();
@@ -279,7 +679,7 @@ impl Printer {
                 if let Some(obj) = error_json.as_object_mut() {
                     obj.insert(
                         "filename".to_string(),
-                        serde_json::Value::String(file_path.display().to_string()),
+                        serde_json::Value::String(self.display_path(file_path)),
                     );
                 }
                 errors.push(error_json);
@@ -291,7 +691,7 @@ impl Printer {
                     if let Some(obj) = warning_json.as_object_mut() {
                         obj.insert(
                             "filename".to_string(),
-                            serde_json::Value::String(file_path.display().to_string()),
+                            serde_json::Value::String(self.display_path(file_path)),
                         );
                     }
                     warnings.push(warning_json);
@@ -314,6 +714,179 @@ impl Printer {
 
         println!("{}", serde_json::to_string_pretty(&output).unwrap());
     }
+    fn codeclimate_issues(&self, analysis: &AnalysisResult, path: &str) -> Vec<serde_json::Value> {
+        let mut issues: Vec<_> = analysis
+            .errors
+            .iter()
+            .map(|error| {
+                codeclimate::issue(
+                    error.code(),
+                    &error.to_string(),
+                    path,
+                    error.span().start.line,
+                    "major",
+                )
+            })
+            .collect();
+
+        if self.show_warnings {
+            issues.extend(analysis.warnings.iter().map(|warning| {
+                codeclimate::issue(
+                    warning.code(),
+                    &warning.to_string(),
+                    path,
+                    warning.span().start.line,
+                    "minor",
+                )
+            }));
+        }
+
+        issues
+    }
+
+    fn print_codeclimate(&self, analysis: &AnalysisResult) {
+        let issues = self.codeclimate_issues(analysis, "query");
+        println!("{}", serde_json::to_string_pretty(&issues).unwrap());
+    }
+
+    fn print_file_results_codeclimate(&self, results: &FileResults) {
+        let issues: Vec<_> = results
+            .successful
+            .iter()
+            .flat_map(|(file_path, analysis, _)| {
+                self.codeclimate_issues(analysis, &self.display_path(file_path))
+            })
+            .collect();
+
+        println!("{}", serde_json::to_string_pretty(&issues).unwrap());
+    }
+
+    fn sarif_results(&self, analysis: &AnalysisResult, path: &str) -> Vec<serde_json::Value> {
+        let mut results: Vec<_> = analysis
+            .errors
+            .iter()
+            .map(|error| {
+                sarif::result(
+                    error.code(),
+                    &error.to_string(),
+                    path,
+                    error.span(),
+                    "error",
+                )
+            })
+            .collect();
+
+        if self.show_warnings {
+            results.extend(analysis.warnings.iter().map(|warning| {
+                sarif::result(
+                    warning.code(),
+                    &warning.to_string(),
+                    path,
+                    warning.span(),
+                    "warning",
+                )
+            }));
+        }
+
+        results
+    }
+
+    fn print_sarif(&self, analysis: &AnalysisResult) {
+        let log = sarif::log(self.sarif_results(analysis, "query"));
+        println!("{}", serde_json::to_string_pretty(&log).unwrap());
+    }
+
+    fn print_file_results_sarif(&self, results: &FileResults) {
+        let log = sarif::log(
+            results
+                .successful
+                .iter()
+                .flat_map(|(file_path, analysis, _)| {
+                    self.sarif_results(analysis, &self.display_path(file_path))
+                })
+                .collect(),
+        );
+        println!("{}", serde_json::to_string_pretty(&log).unwrap());
+    }
+
+    /// write file and diagnostic counts to `path` as JSON, replacing what was there
+    pub fn write_json_summary(&self, results: &FileResults, path: &Path) -> std::io::Result<()> {
+        let valid_files = results.valid_files();
+        let total_files = results.total_files_processed();
+        let count = |diagnostics: fn(&AnalysisResult) -> usize| -> usize {
+            results
+                .successful
+                .iter()
+                .map(|(_, analysis, _)| diagnostics(analysis))
+                .sum()
+        };
+        let warning_count = if self.show_warnings {
+            count(|analysis| analysis.warnings.len())
+        } else {
+            0
+        };
+
+        let summary = serde_json::json!({
+            "total_files": total_files,
+            "valid_files": valid_files,
+            "invalid_files": total_files - valid_files,
+            "error_count": count(|analysis| analysis.errors.len()),
+            "warning_count": warning_count,
+        });
+        std::fs::write(
+            path,
+            format!("{}\n", serde_json::to_string_pretty(&summary).unwrap()),
+        )
+    }
+
+    /// append a markdown table of diagnostics to `path`, e.g. `$GITHUB_STEP_SUMMARY`
+    pub fn write_markdown_summary(
+        &self,
+        results: &FileResults,
+        path: &Path,
+    ) -> std::io::Result<()> {
+        let mut markdown = format!(
+            "## bwq\n\n{}/{} files valid\n",
+            results.valid_files(),
+            results.total_files_processed()
+        );
+
+        let mut diagnostics: Vec<_> = results
+            .successful
+            .iter()
+            .flat_map(|(file_path, analysis, _)| {
+                self.collect_diagnostics(analysis)
+                    .into_iter()
+                    .map(move |diagnostic| (file_path, diagnostic))
+            })
+            .collect();
+        self.sort_diagnostics(&mut diagnostics, |(_, diagnostic)| *diagnostic);
+
+        if !diagnostics.is_empty() {
+            markdown.push_str("\n| File | Line | Code | Message |\n| --- | --- | --- | --- |\n");
+            for (file_path, diagnostic) in diagnostics {
+                let (line, message) = match diagnostic {
+                    Diagnostic::Error(error) => (error.span().start.line, error.to_string()),
+                    Diagnostic::Warning(warning) => {
+                        (warning.span().start.line, warning.to_string())
+                    }
+                };
+                markdown.push_str(&format!(
+                    "| {} | {line} | {} | {} |\n",
+                    markdown_cell(&self.display_path(file_path)),
+                    diagnostic.code(),
+                    markdown_cell(&message)
+                ));
+            }
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        std::io::Write::write_all(&mut file, markdown.as_bytes())
+    }
+
     fn print_error_with_context(
         &self,
         query: &str,
@@ -323,12 +896,20 @@ impl Printer {
         let span = error.span();
 
         // Print header in ty-style format with color
-        println!("\x1b[1;31merror[{}]\x1b[0m: {}", error.code(), error);
+        println!(
+            "{}error[{}]{}: {}",
+            self.ansi(BOLD_RED),
+            error.code(),
+            self.ansi(RESET),
+            error
+        );
 
         if let Some(path) = file_path {
             println!(
-                "  --> \x1b[1m{}\x1b[0m:{}:{}",
-                path.display(),
+                "  --> {}{}{}:{}:{}",
+                self.ansi(BOLD),
+                self.display_path(path),
+                self.ansi(RESET),
                 span.start.line,
                 span.start.column
             );
@@ -336,11 +917,15 @@ impl Printer {
             println!("  --> {}:{}", span.start.line, span.start.column);
         }
 
+        if !self.show_source {
+            return;
+        }
+
         let style = UnderlineStyle {
             underline_char: '^',
             pipe_indent: String::new(),
-            color_start: "\x1b[1;31m".to_string(),
-            color_end: "\x1b[0m".to_string(),
+            color_start: self.ansi(BOLD_RED).to_string(),
+            color_end: self.ansi(RESET).to_string(),
         };
         self.print_snippet_with_underline(query, span, style);
     }
@@ -354,12 +939,20 @@ impl Printer {
         let span = warning.span();
 
         // Print header in ty-style format with color
-        println!("\x1b[1;33mwarning[{}]\x1b[0m: {}", warning.code(), warning);
+        println!(
+            "{}warning[{}]{}: {}",
+            self.ansi(BOLD_YELLOW),
+            warning.code(),
+            self.ansi(RESET),
+            warning
+        );
 
         if let Some(path) = file_path {
             println!(
-                "  --> \x1b[1m{}\x1b[0m:{}:{}",
-                path.display(),
+                "  --> {}{}{}:{}:{}",
+                self.ansi(BOLD),
+                self.display_path(path),
+                self.ansi(RESET),
                 span.start.line,
                 span.start.column
             );
@@ -367,11 +960,15 @@ impl Printer {
             println!("  --> {}:{}", span.start.line, span.start.column);
         }
 
+        if !self.show_source {
+            return;
+        }
+
         let style = UnderlineStyle {
             underline_char: '^',
             pipe_indent: String::new(),
-            color_start: "\x1b[1;33m".to_string(),
-            color_end: "\x1b[0m".to_string(),
+            color_start: self.ansi(BOLD_YELLOW).to_string(),
+            color_end: self.ansi(RESET).to_string(),
         };
         self.print_snippet_with_underline(query, span, style);
     }
@@ -392,8 +989,8 @@ impl Printer {
             return;
         }
 
-        let max_chars_per_context = 200;
-        let max_total_context_chars = 800;
+        let max_chars_per_context = self.max_line_width;
+        let max_total_context_chars = max_chars_per_context.saturating_mul(4);
 
         let context_result = self.calculate_context_window(
             &lines,
@@ -420,7 +1017,7 @@ impl Printer {
 
             let is_error_line = line_idx >= start_line_idx && line_idx <= end_line_idx;
 
-            let display_line = if line.len() > max_chars_per_context {
+            let display_line = if str_width(line) > max_chars_per_context {
                 self.truncate_line_for_span(
                     line,
                     if is_error_line {
@@ -548,7 +1145,8 @@ impl Printer {
             let span_end_char = end_col.saturating_sub(1);
 
             let span_start_char = span_start_char.min(chars.len());
-            let span_end_char = span_end_char.min(chars.len());
+            // the first line of a multiline span can end at a smaller column
+            let span_end_char = span_end_char.min(chars.len()).max(span_start_char);
 
             let span_width: usize = chars[span_start_char..span_end_char]
                 .iter()
@@ -589,7 +1187,16 @@ impl Printer {
         available_width: usize,
         ellipsis: &str,
     ) -> (String, usize) {
+        // with nothing cut on the left, the leading ellipsis's width goes to the span
+        let leading = if span_start > 0 { ellipsis } else { "" };
+        let available_width = if leading.is_empty() {
+            available_width + str_width(ellipsis)
+        } else {
+            available_width
+        };
+
         let mut result_width = 0;
+        let mut shown = span_start;
         let mut truncated = String::new();
 
         for &ch in &chars[span_start..span_end] {
@@ -599,9 +1206,11 @@ impl Printer {
             }
             result_width += ch_width;
             truncated.push(ch);
+            shown += 1;
         }
 
-        (format!("{ellipsis}{truncated}{ellipsis}"), span_start)
+        let trailing = if shown < chars.len() { ellipsis } else { "" };
+        (format!("{leading}{truncated}{trailing}"), span_start)
     }
 
     fn truncate_from_start(
@@ -661,14 +1270,20 @@ impl Printer {
         let display_start_char = line_start_char.saturating_sub(char_offset);
         let display_end_char = line_end_char.saturating_sub(char_offset);
 
+        // a zero-width span is an insertion point, marked with one caret; it may sit just
+        // past the last character, e.g. a missing operand at the end of the query
+        let zero_width = span.is_empty();
+
         // Check if the span is visible in the truncated line
-        if display_start_char >= display_chars.len() {
+        if display_start_char > display_chars.len()
+            || (!zero_width && display_start_char == display_chars.len())
+        {
             return;
         }
 
         let actual_end_char = display_end_char.min(display_chars.len());
 
-        if actual_end_char <= display_start_char {
+        if !zero_width && actual_end_char <= display_start_char {
             return;
         }
 
@@ -682,6 +1297,10 @@ impl Printer {
             }
         }
 
+        if zero_width {
+            underline.push(style.underline_char);
+        }
+
         // Add underline characters for the span
         for &ch in &display_chars[display_start_char..actual_end_char] {
             let ch_width = char_width(ch);
@@ -258,6 +258,24 @@ fn test_json_output() {
     assert_eq!(error["span"]["start"]["column"], 1);
 }
 
+#[test]
+fn test_json_output_byte_offsets() {
+    let (stdout, _, _) = check_query_json("café AND rating:15");
+    let json: serde_json::Value =
+        serde_json::from_str(&stdout).expect("Output should be valid JSON");
+
+    // "é" is two bytes, so the error starts at byte 10 but char 9
+    let error = &json["errors"][0];
+    assert_eq!(error["byte_offset"], 10);
+    assert_eq!(error["end_byte_offset"], 19);
+    assert_eq!(error["span"]["start"]["char_offset"], 9);
+    // `offset` only ever names a char offset
+    assert_eq!(error["span"]["start"]["offset"], 9);
+    assert_eq!(error["span"]["end"]["offset"], 18);
+    assert!(error.get("offset").is_none());
+    assert!(error.get("end_offset").is_none());
+}
+
 #[test]
 fn test_file_based_checking() -> Result<(), Box<dyn std::error::Error>> {
     let temp_dir = TempDir::new()?;
@@ -350,3 +368,1248 @@ fn test_error_spans_complex_query() {
     assert!(stdout.contains("rating:15"));
     assert!(stdout.contains("^^^^^^^^^"));
 }
+
+#[test]
+fn test_near_error_underlines_operator() {
+    let cmd_result = check_query("apple NEAR/5 juice AND fresh");
+
+    assert_cmd_output(
+        cmd_result,
+        r"
+success: false
+exit_code: 1
+----- stdout -----
+error[E010]: Please use parentheses for disambiguation when using the AND operator with a NEAR operator - e.g. (vanilla NEAR/5 chocolate) AND (ice-cream NEAR/5 cake).
+  --> 1:7
+  |
+1 | apple NEAR/5 juice AND fresh
+  |       ^^^^^^
+  |
+----- stderr -----
+",
+    );
+}
+
+#[test]
+fn test_max_line_width_truncation() {
+    let query = format!("{} AND *bad AND {}", "a".repeat(60), "b".repeat(60));
+    let output = bwq_cmd()
+        .args(["check", "--query", &query, "--max-line-width", "30"])
+        .output()
+        .expect("Failed to execute bwq");
+
+    assert_cmd_output(
+        (
+            strip_ansi_codes(&String::from_utf8_lossy(&output.stdout)),
+            strip_ansi_codes(&String::from_utf8_lossy(&output.stderr)),
+            output.status.code().unwrap_or(-1),
+        ),
+        r"
+success: false
+exit_code: 1
+----- stdout -----
+error[E004]: Invalid wildcard placement: Wildcard operators (* and ?) cannot be used at the start of a search term. They're used within or at the end of a word to find any possible match.
+  --> 1:66
+  |
+1 | …aaaaaaa AND *bad AND bbbbbbb…
+  |             ^^^^
+  |
+----- stderr -----
+",
+    );
+}
+
+#[test]
+fn test_print_ast_on_error() {
+    let output = bwq_cmd()
+        .args(["check", "--query", "apple AND *bad", "--print-ast-on-error"])
+        .output()
+        .expect("Failed to execute bwq");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr.starts_with("AST for query:\nQuery {"), "{stderr}");
+    assert!(stderr.contains("BooleanOp"), "{stderr}");
+    assert!(stderr.contains("\"*bad\""), "{stderr}");
+
+    // lexer errors leave no AST to print
+    let output = bwq_cmd()
+        .args(["check", "--query", "apple \"pie", "--print-ast-on-error"])
+        .output()
+        .expect("Failed to execute bwq");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr).trim(),
+        "AST for query: none (the query failed before parsing finished)"
+    );
+
+    // clean queries print nothing extra
+    let output = bwq_cmd()
+        .args([
+            "check",
+            "--query",
+            "apple AND juice",
+            "--print-ast-on-error",
+        ])
+        .output()
+        .expect("Failed to execute bwq");
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_show_source_false() {
+    let output = bwq_cmd()
+        .args(["check", "--query", "*bad", "--show-source=false"])
+        .output()
+        .expect("Failed to execute bwq");
+
+    assert_cmd_output(
+        (
+            strip_ansi_codes(&String::from_utf8_lossy(&output.stdout)),
+            strip_ansi_codes(&String::from_utf8_lossy(&output.stderr)),
+            output.status.code().unwrap_or(-1),
+        ),
+        r"
+success: false
+exit_code: 1
+----- stdout -----
+error[E004]: Invalid wildcard placement: Wildcard operators (* and ?) cannot be used at the start of a search term. They're used within or at the end of a word to find any possible match.
+  --> 1:1
+----- stderr -----
+",
+    );
+}
+
+#[test]
+fn test_no_color_when_not_a_terminal() {
+    // stdout is a pipe here, so the default auto mode must not emit escape codes
+    let output = bwq_cmd()
+        .args(["check", "--query", "*bad AND apple juice"])
+        .output()
+        .expect("Failed to execute bwq");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("error[E004]"));
+    assert!(!stdout.contains('\x1b'));
+
+    let output = bwq_cmd()
+        .args(["check", "--query", "*bad", "--color", "always"])
+        .output()
+        .expect("Failed to execute bwq");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\x1b[1;31merror[E004]\x1b[0m"));
+}
+
+#[test]
+fn test_require_fields_config() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("bwq.toml"),
+        "require_fields = [\"language\"]\n",
+    )?;
+    fs::write(temp_dir.path().join("missing.bwq"), "apple AND juice")?;
+    fs::write(temp_dir.path().join("present.bwq"), "apple AND language:en")?;
+
+    let run = |file: &str| {
+        let output = bwq_cmd()
+            .current_dir(temp_dir.path())
+            .args(["check", file])
+            .output()
+            .expect("Failed to execute bwq");
+        (
+            strip_ansi_codes(&String::from_utf8_lossy(&output.stdout)),
+            output.status.code().unwrap_or(-1),
+        )
+    };
+
+    let (stdout, exit_code) = run("missing.bwq");
+    assert_eq!(exit_code, 1);
+    assert!(stdout.contains(
+        "error[E031]: Query must include a language: filter (required by project configuration)"
+    ));
+
+    let (stdout, exit_code) = run("present.bwq");
+    assert_eq!(exit_code, 0);
+    assert!(!stdout.contains("E031"));
+
+    Ok(())
+}
+
+#[test]
+fn test_parallel_output_order_is_stable() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let nested = temp_dir.path().join("nested");
+    fs::create_dir(&nested)?;
+    for i in (0..20).rev() {
+        let dir = if i % 2 == 0 { temp_dir.path() } else { &nested };
+        fs::write(
+            dir.join(format!("query{i:02}.bwq")),
+            format!("rating:{}", i + 6),
+        )?;
+    }
+
+    let run = |jobs: &str| {
+        let output = bwq_cmd()
+            .args(["check", "--jobs", jobs, temp_dir.path().to_str().unwrap()])
+            .output()
+            .expect("Failed to execute bwq");
+        strip_ansi_codes(&String::from_utf8_lossy(&output.stdout))
+    };
+
+    let serial = run("1");
+    let headers: Vec<&str> = serial
+        .lines()
+        .filter(|l| l.trim_start().starts_with("-->"))
+        .collect();
+    let mut sorted = headers.clone();
+    sorted.sort();
+    assert_eq!(headers.len(), 20);
+    assert_eq!(headers, sorted);
+
+    for jobs in ["2", "8"] {
+        assert_eq!(run(jobs), serial);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_explain_json() {
+    let output = bwq_cmd()
+        .args(["explain", "E012", "--json"])
+        .output()
+        .expect("Failed to execute bwq");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Output should be valid JSON");
+
+    assert_eq!(json["code"], "E012");
+    assert_eq!(json["severity"], "error");
+    for field in ["name", "summary", "example_before", "example_after"] {
+        assert!(
+            json[field].as_str().is_some_and(|value| !value.is_empty()),
+            "{field} should be populated"
+        );
+    }
+
+    let output = bwq_cmd()
+        .args(["explain", "E999", "--json"])
+        .output()
+        .expect("Failed to execute bwq");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_server_health_check() {
+    let output = bwq_cmd()
+        .args(["server", "--health"])
+        .output()
+        .expect("Failed to execute bwq");
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "bwq language server is healthy"
+    );
+}
+
+#[test]
+fn test_codeclimate_output() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let file_path = temp_dir.path().join("test.bwq");
+
+    fs::write(&file_path, "rating:15 AND apple this")?;
+
+    let run = || {
+        bwq_cmd()
+            .args([
+                "check",
+                file_path.to_str().unwrap(),
+                "--output-format",
+                "codeclimate",
+            ])
+            .output()
+            .expect("Failed to execute bwq")
+    };
+
+    let first = run();
+    let second = run();
+    assert_eq!(first.status.code(), Some(1));
+
+    let first_json: serde_json::Value = serde_json::from_slice(&first.stdout)?;
+    let second_json: serde_json::Value = serde_json::from_slice(&second.stdout)?;
+
+    let issues = first_json
+        .as_array()
+        .expect("Output should be a JSON array");
+    assert_eq!(issues.len(), 2);
+
+    let error = &issues[0];
+    assert_eq!(error["check_name"], "E009");
+    assert_eq!(error["severity"], "major");
+    assert_eq!(error["location"]["path"], file_path.display().to_string());
+    assert_eq!(error["location"]["lines"]["begin"], 1);
+    assert_eq!(issues[1]["severity"], "minor");
+
+    // fingerprints must be stable across runs so GitLab can dedupe issues
+    for (first_issue, second_issue) in issues.iter().zip(second_json.as_array().unwrap()) {
+        assert_eq!(first_issue["fingerprint"], second_issue["fingerprint"]);
+    }
+    assert_ne!(issues[0]["fingerprint"], issues[1]["fingerprint"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_sarif_output() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let file_path = temp_dir.path().join("test.bwq");
+
+    fs::write(&file_path, "rating:15 AND apple this")?;
+
+    let output = bwq_cmd()
+        .args([
+            "check",
+            file_path.to_str().unwrap(),
+            "--output-format",
+            "sarif",
+        ])
+        .output()
+        .expect("Failed to execute bwq");
+    assert_eq!(output.status.code(), Some(1));
+
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "bwq");
+    let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+    assert!(
+        rules
+            .iter()
+            .any(|rule| rule["id"] == "E009" && rule["defaultConfiguration"]["level"] == "error")
+    );
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    let error = &results[0];
+    assert_eq!(error["ruleId"], "E009");
+    assert_eq!(error["level"], "error");
+    assert_eq!(error["message"]["text"], "Rating must be between 0 and 5");
+    let location = &error["locations"][0]["physicalLocation"];
+    assert_eq!(
+        location["artifactLocation"]["uri"],
+        file_path.display().to_string()
+    );
+    assert_eq!(location["region"]["startLine"], 1);
+    assert_eq!(location["region"]["startColumn"], 1);
+    assert_eq!(location["region"]["endColumn"], 10);
+    assert_eq!(results[1]["level"], "warning");
+
+    Ok(())
+}
+
+#[test]
+fn test_sort_files_by_diagnostics() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("a.bwq"), "*bad")?;
+    fs::write(temp_dir.path().join("b.bwq"), "apple AND juice")?;
+    fs::write(temp_dir.path().join("c.bwq"), "*bad AND rating:15")?;
+    fs::write(temp_dir.path().join("d.bwq"), "rating:15 AND apple this")?;
+
+    let files = |sort_files: &str| -> Vec<String> {
+        let output = bwq_cmd()
+            .args([
+                "check",
+                temp_dir.path().to_str().unwrap(),
+                "--sort-files",
+                sort_files,
+            ])
+            .output()
+            .expect("Failed to execute bwq");
+        let mut files: Vec<String> = strip_ansi_codes(&String::from_utf8_lossy(&output.stdout))
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("--> "))
+            .map(|location| {
+                location
+                    .rsplit('/')
+                    .next()
+                    .unwrap()
+                    .split(':')
+                    .next()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        files.dedup();
+        files
+    };
+
+    assert_eq!(files("name"), ["a.bwq", "c.bwq", "d.bwq"]);
+    // c has two errors; d ties with a on errors but also has a warning
+    assert_eq!(files("diagnostics"), ["c.bwq", "d.bwq", "a.bwq"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_absolute_paths() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("test.bwq"), "*bad")?;
+
+    let filenames = |extra: &[&str]| -> Vec<String> {
+        let output = bwq_cmd()
+            .current_dir(temp_dir.path())
+            .args(["check", "test.bwq", "--output-format", "json"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute bwq");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|error| error["filename"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(filenames(&[]), ["test.bwq"]);
+
+    let absolute = std::path::absolute(temp_dir.path().join("test.bwq"))?;
+    assert_eq!(
+        filenames(&["--absolute-paths"]),
+        [absolute.display().to_string()]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cache_reuses_unchanged_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let file_path = temp_dir.path().join("test.bwq");
+    let cache_path = temp_dir.path().join(".bwq-cache");
+    fs::write(&file_path, "*bad")?;
+
+    let run = || {
+        let output = bwq_cmd()
+            .current_dir(temp_dir.path())
+            .args(["check", "test.bwq", "--cache", "--show-source=false"])
+            .output()
+            .expect("Failed to execute bwq");
+        strip_ansi_codes(&String::from_utf8_lossy(&output.stdout))
+    };
+
+    assert!(run().contains("error[E004]"));
+    assert!(cache_path.exists());
+
+    // doctor the cached message: an unchanged file must be reported from the cache
+    let cache = fs::read_to_string(&cache_path)?;
+    fs::write(
+        &cache_path,
+        cache.replace("Wildcard operators", "CACHED wildcard operators"),
+    )?;
+    assert!(run().contains("CACHED wildcard operators"));
+
+    // a content change invalidates the entry
+    fs::write(&file_path, "*worse")?;
+    let output = run();
+    assert!(output.contains("error[E004]"));
+    assert!(!output.contains("CACHED"));
+
+    Ok(())
+}
+
+#[test]
+fn test_preview_rules_are_opt_in() -> Result<(), Box<dyn std::error::Error>> {
+    let run = |extra: &[&str]| {
+        let output = bwq_cmd()
+            .args(["check", "--query", "apple OR (apple)"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute bwq");
+        strip_ansi_codes(&String::from_utf8_lossy(&output.stdout))
+    };
+
+    assert!(!run(&[]).contains("W034"));
+    assert!(run(&["--preview"]).contains("warning[W034]"));
+    assert!(run(&["--extend-select", "W034"]).contains("warning[W034]"));
+    assert!(!run(&["--extend-select", "W033"]).contains("W034"));
+
+    let output = bwq_cmd()
+        .args(["check", "--query", "apple", "--extend-select", "W999"])
+        .output()?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown diagnostic code 'W999'"));
+
+    Ok(())
+}
+
+#[test]
+fn test_no_recover_stops_at_first_error() -> Result<(), Box<dyn std::error::Error>> {
+    let error_count = |extra: &[&str]| -> Result<usize, Box<dyn std::error::Error>> {
+        let output = bwq_cmd()
+            .args(["check", "--query", "apple ] juice AND AND banana }"])
+            .args(["--output-format", "json"])
+            .args(extra)
+            .output()?;
+        assert!(!output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        Ok(json["errors"].as_array().map_or(0, Vec::len))
+    };
+
+    assert_eq!(error_count(&[])?, 3);
+    assert_eq!(error_count(&["--no-recover"])?, 1);
+    Ok(())
+}
+
+#[test]
+fn test_summary_json() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("bad.bwq"), "apple juice AND rating:6")?;
+    fs::write(temp_dir.path().join("good.bwq"), "apple AND juice")?;
+    let summary_path = temp_dir.path().join("summary.json");
+
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", ".", "--summary-json"])
+        .arg(&summary_path)
+        .output()?;
+
+    // the usual report still goes to stdout
+    assert!(strip_ansi_codes(&String::from_utf8_lossy(&output.stdout)).contains("error[E009]"));
+    let summary: serde_json::Value = serde_json::from_str(&fs::read_to_string(&summary_path)?)?;
+    assert_eq!(
+        summary,
+        serde_json::json!({
+            "total_files": 2,
+            "valid_files": 1,
+            "invalid_files": 1,
+            "error_count": 1,
+            "warning_count": 1,
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_since_only_checks_recent_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("recent.bwq"), "rating:6")?;
+    fs::write(temp_dir.path().join("recent.txt"), "rating:6")?;
+    fs::write(temp_dir.path().join("stale.bwq"), "rating:6")?;
+    let two_days = std::time::Duration::from_secs(2 * 24 * 60 * 60);
+    fs::File::options()
+        .write(true)
+        .open(temp_dir.path().join("stale.bwq"))?
+        .set_modified(std::time::SystemTime::now() - two_days)?;
+
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", ".", "--since", "1h"])
+        .output()?;
+    let stdout = strip_ansi_codes(&String::from_utf8_lossy(&output.stdout));
+    assert!(stdout.contains("recent.bwq"));
+    assert!(!stdout.contains("recent.txt"));
+    assert!(!stdout.contains("stale.bwq"));
+    assert!(stdout.contains("0/1 files valid"));
+
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", ".", "--since", "soon"])
+        .output()?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid --since duration 'soon'"));
+
+    // units are required, whole numbers only, and nothing may separate them
+    for since in ["", "10", "d", "1x", "1h30", "-1d", "1.5h", "1 h", "1month"] {
+        let output = bwq_cmd()
+            .current_dir(temp_dir.path())
+            .args(["check", "."])
+            .arg(format!("--since={since}"))
+            .output()?;
+        assert!(!output.status.success(), "{since}");
+        assert!(
+            String::from_utf8_lossy(&output.stderr)
+                .contains(&format!("Invalid --since duration '{since}'")),
+            "{since}"
+        );
+    }
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", ".", "--since", "1h30m"])
+        .output()?;
+    assert!(String::from_utf8_lossy(&output.stdout).contains("0/1 files valid"));
+
+    // durations past the epoch check everything; ones that overflow are invalid, not a panic
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", ".", "--since", "200000000000000d"])
+        .output()?;
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("0/2 files valid"));
+
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", ".", "--since", "200000000000000d200000000000000d"])
+        .output()?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid --since duration"));
+    Ok(())
+}
+
+#[test]
+fn test_preview_fixes() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let query = "language:ENG apple banana";
+    fs::write(temp_dir.path().join("fixable.bwq"), query)?;
+
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", "fixable.bwq", "--preview-fixes"])
+        .output()?;
+    let stdout = strip_ansi_codes(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(
+        stdout,
+        "fixable.bwq:1:10: W001 \"ENG\" → \"eng\"\n\
+         fixable.bwq:1:13: W001 insert \" AND\"\n\
+         fixable.bwq:1:19: W001 insert \" AND\"\n\
+         3 fixes available\n"
+    );
+    // a dry run: the file is untouched
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("fixable.bwq"))?,
+        query
+    );
+
+    let output = bwq_cmd()
+        .args(["check", "--query", "apple AND juice", "--preview-fixes"])
+        .output()?;
+    assert_eq!(
+        strip_ansi_codes(&String::from_utf8_lossy(&output.stdout)),
+        "No fixes available\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_fix_rewrites_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = |name: &str| temp_dir.path().join(name);
+    fs::write(path("mixed.bwq"), "\nlanguage:EN apple banana OR juice\n")?;
+    fs::write(path("invalid.bwq"), "rating:6 apple")?;
+    fs::write(path("clean.bwq"), "apple AND juice")?;
+
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", ".", "--fix"])
+        .output()?;
+
+    assert_eq!(
+        fs::read_to_string(path("mixed.bwq"))?,
+        "\n(language:en AND apple AND banana) OR juice\n"
+    );
+    assert_eq!(
+        fs::read_to_string(path("invalid.bwq"))?,
+        "rating:6 AND apple"
+    );
+    assert_eq!(fs::read_to_string(path("clean.bwq"))?, "apple AND juice");
+
+    // what can't be fixed is still reported
+    let stdout = strip_ansi_codes(&String::from_utf8_lossy(&output.stdout));
+    assert!(stdout.contains("error[E009]"));
+    assert!(!stdout.contains("W001"));
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Fixed 5 issues in 2 files"));
+    Ok(())
+}
+
+#[test]
+fn test_very_long_token_snippet_is_truncated() -> Result<(), Box<dyn std::error::Error>> {
+    for (ch, width) in [('é', 1), ('漢', 2)] {
+        let term: String = std::iter::repeat_n(ch, 3000).collect();
+        let output = bwq_cmd()
+            .args(["check", "--query", &format!("apple AND language:{term}")])
+            .args(["--color", "never", "--max-line-width", "80"])
+            .output()?;
+        let stdout = strip_ansi_codes(&String::from_utf8_lossy(&output.stdout));
+
+        let snippet = stdout
+            .lines()
+            .find(|line| line.starts_with("1 | "))
+            .expect("a source snippet");
+        let shown = &snippet["1 | ".len()..];
+        assert!(shown.starts_with("…language:"), "{shown}");
+        assert!(shown.ends_with('…'), "{shown}");
+        let display_width: usize = shown.chars().map(|c| if c == ch { width } else { 1 }).sum();
+        assert!(display_width <= 80, "{display_width}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_ast_json_output() {
+    let output = bwq_cmd()
+        .args([
+            "check",
+            "--query",
+            "apple OR [1 TO *]",
+            "--output-format",
+            "ast-json",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let ast: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(ast["expression"]["type"], "BooleanOp");
+    assert_eq!(ast["expression"]["operator"], "OR");
+    assert_eq!(ast["expression"]["left"]["term"]["type"], "Word");
+    assert_eq!(ast["expression"]["right"]["start"], "1");
+    assert!(ast["expression"]["right"]["end"].is_null());
+    assert_eq!(ast["expression"]["span"]["start"]["line"], 1);
+
+    // spans stay compact: chars only, no byte offsets
+    let output = bwq_cmd()
+        .args(["check", "--query", "café", "--output-format", "ast-json"])
+        .output()
+        .expect("Failed to execute command");
+    let ast: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        ast["span"],
+        serde_json::json!({
+            "start": { "line": 1, "column": 1, "offset": 0 },
+            "end": { "line": 1, "column": 5, "offset": 4 },
+        })
+    );
+
+    let output = bwq_cmd()
+        .args([
+            "check",
+            "--query",
+            "apple AND",
+            "--output-format",
+            "ast-json",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    let errors: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(errors["errors"][0]["code"].is_string());
+
+    let output = bwq_cmd()
+        .args(["check", "--output-format", "ast-json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("requires --query"));
+}
+
+#[test]
+fn test_bwqignore_skips_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let drafts = temp_dir.path().join("drafts");
+    fs::create_dir(&drafts)?;
+    fs::write(temp_dir.path().join("kept.bwq"), "apple AND juice")?;
+    fs::write(drafts.join("draft.bwq"), "apple AND")?;
+    fs::write(temp_dir.path().join("scratch.bwq"), "juice OR")?;
+    fs::write(temp_dir.path().join(".bwqignore"), "drafts/\n")?;
+
+    let output = bwq_cmd().arg("check").arg(temp_dir.path()).output()?;
+    let stdout = strip_ansi_codes(&String::from_utf8_lossy(&output.stdout));
+    assert!(!stdout.contains("draft.bwq"), "{stdout}");
+    assert!(stdout.contains("scratch.bwq"), "{stdout}");
+
+    let extra = temp_dir.path().join("extra-ignore");
+    fs::write(&extra, "scratch.bwq\n")?;
+    let output = bwq_cmd()
+        .arg("check")
+        .arg(temp_dir.path())
+        .arg("--ignore-file")
+        .arg(&extra)
+        .output()?;
+    assert!(output.status.success());
+
+    let output = bwq_cmd()
+        .arg("check")
+        .arg(temp_dir.path())
+        .arg("--ignore-file")
+        .arg(temp_dir.path().join("missing"))
+        .output()?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Failed to read ignore file"));
+    Ok(())
+}
+
+#[test]
+fn test_format_command() -> Result<(), Box<dyn std::error::Error>> {
+    let output = bwq_cmd()
+        .args(["format", "--query", "apple   AND (juice  OR soda)"])
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "apple AND (juice OR soda)\n"
+    );
+
+    let temp_dir = TempDir::new()?;
+    let messy = temp_dir.path().join("messy.bwq");
+    let tidy = temp_dir.path().join("tidy.bwq");
+    fs::write(&messy, "apple  OR\n  banana <<<fruit>>>\n")?;
+    fs::write(&tidy, "apple OR banana\n")?;
+
+    let output = bwq_cmd()
+        .arg("format")
+        .arg("--check")
+        .arg(temp_dir.path())
+        .output()?;
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("messy.bwq") && !stdout.contains("tidy.bwq"),
+        "{stdout}"
+    );
+    assert_eq!(
+        fs::read_to_string(&messy)?,
+        "apple  OR\n  banana <<<fruit>>>\n"
+    );
+
+    let output = bwq_cmd().arg("format").arg(temp_dir.path()).output()?;
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("1 file reformatted, 1 file left unchanged")
+    );
+    assert_eq!(fs::read_to_string(&messy)?, "apple OR banana <<<fruit>>>\n");
+    Ok(())
+}
+
+#[test]
+fn test_format_honors_line_comments_config() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("bwq.toml"), "line_comments = true\n")?;
+    let query = temp_dir.path().join("query.bwq");
+    fs::write(&query, "apple  // note\nAND   banana\n")?;
+
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["format", "query.bwq"])
+        .output()?;
+    assert!(output.status.success());
+    // `AND banana` stays on its own line, out of the comment
+    assert_eq!(fs::read_to_string(&query)?, "apple // note\nAND banana\n");
+    Ok(())
+}
+
+#[test]
+fn test_rules_config_and_overrides() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("bwq.toml"),
+        "[rules]\nrating-field = \"off\"\npotential-typo = \"error\"\n",
+    )?;
+    fs::write(
+        temp_dir.path().join("query.bwq"),
+        "apple juice AND rating:6",
+    )?;
+
+    let codes = |args: &[&str]| -> Result<(Vec<String>, Vec<String>), Box<dyn std::error::Error>> {
+        let output = bwq_cmd()
+            .current_dir(temp_dir.path())
+            .args(["check", "query.bwq", "--output-format", "json"])
+            .args(args)
+            .output()?;
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        let codes = |key: &str| {
+            json[key]
+                .as_array()
+                .map(|items| {
+                    items
+                        .iter()
+                        .map(|item| item["code"].as_str().unwrap_or_default().to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Ok((codes("errors"), codes("warnings")))
+    };
+
+    // bwq.toml: E009 for rating:6 is off, W001 is raised to an error
+    assert_eq!(codes(&[])?, (vec!["W001".to_string()], vec![]));
+    // --ignore wins over the file's "error"
+    assert_eq!(codes(&["--ignore", "W001"])?, (vec![], vec![]));
+    // --select reports a code the file turned off
+    assert_eq!(
+        codes(&["--select", "E009"])?,
+        (vec!["E009".to_string()], vec![])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_fix_skips_rules_turned_off() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let query = temp_dir.path().join("query.bwq");
+    let fix = |args: &[&str]| -> Result<String, Box<dyn std::error::Error>> {
+        fs::write(&query, "apple juice OR soda")?;
+        bwq_cmd()
+            .current_dir(temp_dir.path())
+            .args(["check", "query.bwq", "--fix"])
+            .args(args)
+            .output()?;
+        Ok(fs::read_to_string(&query)?)
+    };
+
+    assert_eq!(fix(&["--ignore", "W001,E012"])?, "apple juice OR soda");
+    // E012's parentheses are still added when only W001 is off
+    assert_eq!(fix(&["--ignore", "W001"])?, "(apple juice) OR soda");
+
+    fs::write(
+        temp_dir.path().join("bwq.toml"),
+        "[rules]\nW001 = \"off\"\nE012 = \"off\"\n",
+    )?;
+    assert_eq!(fix(&[])?, "apple juice OR soda");
+    Ok(())
+}
+
+#[test]
+fn test_implicit_and_rule_off() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("bwq.toml"),
+        "[rules]\nimplicit-and = \"off\"\n",
+    )?;
+    let query = temp_dir.path().join("query.bwq");
+    fs::write(&query, "apple juice")?;
+
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", "query.bwq", "--fix"])
+        .output()?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)?.contains("All checks passed!"));
+    assert_eq!(fs::read_to_string(&query)?, "apple juice");
+
+    // the language code check is also W001, and still reported
+    fs::write(&query, "apple juice AND language:english")?;
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", "query.bwq"])
+        .output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("Language codes should be 2-character"));
+    assert!(!stdout.contains("implicitly ANDed"));
+    Ok(())
+}
+
+#[test]
+fn test_fix_skips_suppressed_lines() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let query = temp_dir.path().join("query.bwq");
+    fs::write(
+        &query,
+        "apple juice <<<bwq:ignore W001>>>\nAND banana soda\n",
+    )?;
+
+    bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", "query.bwq", "--fix"])
+        .output()?;
+    assert_eq!(
+        fs::read_to_string(&query)?,
+        "apple juice <<<bwq:ignore W001>>>\nAND banana AND soda\n"
+    );
+
+    // the suppression is still in use, so the next run is clean (no W040)
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", "query.bwq"])
+        .output()?;
+    assert!(
+        strip_ansi_codes(&String::from_utf8_lossy(&output.stdout)).contains("All checks passed!")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_severity_override_affects_exit_status() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("query.bwq"), "apple juice")?;
+
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", "query.bwq"])
+        .output()?;
+    assert!(output.status.success());
+
+    fs::write(
+        temp_dir.path().join("bwq.toml"),
+        "severity_overrides = { W001 = \"error\" }\n",
+    )?;
+    let output = bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", "query.bwq"])
+        .output()?;
+    assert_eq!(output.status.code(), Some(1));
+    Ok(())
+}
+
+#[test]
+fn test_version_output() {
+    let run = |args: &[&str]| {
+        let output = bwq_cmd()
+            .args(args)
+            .output()
+            .expect("Failed to execute bwq");
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    let version = format!("bwq {}", env!("CARGO_PKG_VERSION"));
+    assert_eq!(run(&["--version"]).trim_end(), version);
+
+    let verbose = run(&["--version", "--verbose"]);
+    let lines: Vec<&str> = verbose.lines().collect();
+    assert_eq!(lines.len(), 4, "{verbose}");
+    assert_eq!(lines[0], version);
+    // "unknown" when built outside a git checkout
+    let commit = lines[1].strip_prefix("commit: ").unwrap();
+    assert!(
+        commit == "unknown" || commit.chars().all(|c| c.is_ascii_hexdigit()),
+        "{commit}"
+    );
+    assert!(lines[2].starts_with("features: "));
+    assert!(lines[3].starts_with("rule registry: "));
+    assert!(lines[3].ends_with(" codes)"));
+
+    let output = bwq_cmd().arg("--verbose").output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_watch_stdin_ndjson() -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = bwq_cmd()
+        .args(["check", "--watch-stdin"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"apple AND juice\nrating:6\n")?;
+    let output = child.wait_with_output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(lines.len(), 2, "{stdout}");
+    assert_eq!(lines[0]["query"], "apple AND juice");
+    assert!(lines[0]["errors"].as_array().unwrap().is_empty());
+    assert_eq!(lines[1]["query"], "rating:6");
+    assert_eq!(lines[1]["errors"][0]["code"], "E009");
+
+    Ok(())
+}
+
+#[test]
+fn test_markdown_summary() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("bad.bwq"), "apple\nrating:6")?;
+    fs::write(temp_dir.path().join("good.bwq"), "apple AND juice")?;
+    let summary_path = temp_dir.path().join("summary.md");
+
+    bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", ".", "--no-warnings"])
+        .env("GITHUB_STEP_SUMMARY", &summary_path)
+        .arg("--github-step-summary")
+        .output()?;
+
+    let summary = fs::read_to_string(&summary_path)?;
+    assert!(summary.contains("1/2 files valid"));
+    assert!(summary.contains("| File | Line | Code | Message |"));
+    assert!(summary.contains("| ./bad.bwq | 2 | E009 | Rating must be between 0 and 5 |"));
+    assert!(!summary.contains("good.bwq"));
+
+    // an explicit path works without the environment variable, and appends
+    let explicit_path = temp_dir.path().join("explicit.md");
+    fs::write(&explicit_path, "# earlier step\n")?;
+    bwq_cmd()
+        .current_dir(temp_dir.path())
+        .args(["check", "good.bwq", "--markdown-summary", "explicit.md"])
+        .env_remove("GITHUB_STEP_SUMMARY")
+        .output()?;
+    let explicit = fs::read_to_string(&explicit_path)?;
+    assert!(explicit.starts_with("# earlier step\n"));
+    assert!(explicit.contains("1/1 files valid"));
+    assert!(!explicit.contains("| File |"));
+
+    Ok(())
+}
+
+#[test]
+fn test_emit_pretty() {
+    let output = bwq_cmd()
+        .args([
+            "check",
+            "-q",
+            "(apple OR juice) AND pie",
+            "--emit",
+            "pretty",
+        ])
+        .output()
+        .expect("Failed to execute bwq");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "(\n    apple\n    OR juice\n)\nAND pie\n"
+    );
+
+    let output = bwq_cmd()
+        .args(["check", "-q", "rating:9", "--emit", "pretty"])
+        .output()
+        .expect("Failed to execute bwq");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(strip_ansi_codes(&String::from_utf8_lossy(&output.stdout)).contains("E009"));
+}
+
+#[test]
+fn test_explain_diagnostics() {
+    let output = bwq_cmd()
+        .args([
+            "check",
+            "-q",
+            "apple AND juice OR soda",
+            "--explain-diagnostics",
+        ])
+        .output()
+        .expect("Failed to execute bwq");
+    let stdout = strip_ansi_codes(&String::from_utf8_lossy(&output.stdout));
+
+    let header = stdout.find("error[E012]").expect("E012 should be reported");
+    let explanation = stdout
+        .find("  = operator-mixing: ")
+        .expect("the E012 explanation should be printed");
+    assert!(explanation > header);
+
+    let output = bwq_cmd()
+        .args(["check", "-q", "apple AND juice OR soda"])
+        .output()
+        .expect("Failed to execute bwq");
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("  = "));
+
+    // --verbose belongs to --version, not check
+    let output = bwq_cmd()
+        .args(["check", "-q", "apple", "--verbose"])
+        .output()
+        .expect("Failed to execute bwq");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_zero_width_span_caret() {
+    for (query, expected) in [
+        ("apple AND", "1 | apple AND\n  |          ^\n"),
+        (
+            "juice AND title:",
+            "1 | juice AND title:\n  |                 ^\n",
+        ),
+    ] {
+        let output = bwq_cmd()
+            .args(["check", "--query", query])
+            .output()
+            .expect("Failed to execute bwq");
+        let stdout = strip_ansi_codes(&String::from_utf8_lossy(&output.stdout));
+        assert!(stdout.contains(expected), "{query}:\n{stdout}");
+    }
+}
+
+/// Compares `bwq check` text and JSON output for each query in `resources/test/golden/`
+/// against the committed `<name>.text` / `<name>.json` files next to it.
+///
+/// Run with `BWQ_UPDATE_GOLDEN=1` to rewrite the golden files after an intended change.
+#[test]
+fn test_golden_output() {
+    let golden_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/test/golden");
+    let update = std::env::var_os("BWQ_UPDATE_GOLDEN").is_some();
+    let mut mismatches = Vec::new();
+
+    for name in ["valid", "error", "warning"] {
+        for format in ["text", "json"] {
+            let output = bwq_cmd()
+                .current_dir(&golden_dir)
+                .args(["check", &format!("{name}.bwq"), "--color", "never"])
+                .args(["--output-format", format, "--max-line-width", "80"])
+                .output()
+                .expect("Failed to execute bwq");
+            let actual = String::from_utf8_lossy(&output.stdout);
+
+            let golden_path = golden_dir.join(format!("{name}.{format}"));
+            if update {
+                fs::write(&golden_path, actual.as_bytes()).unwrap();
+                continue;
+            }
+
+            let expected = fs::read_to_string(&golden_path).unwrap_or_default();
+            if actual != expected {
+                mismatches.push(format!(
+                    "{}:\n--- expected\n{expected}\n+++ actual\n{actual}",
+                    golden_path.display()
+                ));
+            }
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "output differs from golden files (rerun with BWQ_UPDATE_GOLDEN=1 if intended):\n{}",
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn test_group_by_ordering() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let file1 = temp_dir.path().join("a.bwq");
+    let file2 = temp_dir.path().join("b.bwq");
+
+    fs::write(&file1, "apple this AND rating:15")?;
+    fs::write(&file2, "*bad")?;
+
+    let headers = |group_by: &str| -> Vec<String> {
+        let output = bwq_cmd()
+            .args([
+                "check",
+                file1.to_str().unwrap(),
+                file2.to_str().unwrap(),
+                "--group-by",
+                group_by,
+            ])
+            .output()
+            .expect("Failed to execute bwq");
+        strip_ansi_codes(&String::from_utf8_lossy(&output.stdout))
+            .lines()
+            .filter(|line| line.starts_with("error[") || line.starts_with("warning["))
+            .map(|line| line.split(']').next().unwrap().to_string() + "]")
+            .collect()
+    };
+
+    assert_eq!(
+        headers("file"),
+        ["error[E009]", "warning[W001]", "error[E004]"]
+    );
+    assert_eq!(
+        headers("code"),
+        ["error[E004]", "error[E009]", "warning[W001]"]
+    );
+    assert_eq!(
+        headers("severity"),
+        ["error[E009]", "error[E004]", "warning[W001]"]
+    );
+
+    Ok(())
+}
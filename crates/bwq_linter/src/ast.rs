@@ -1,51 +1,541 @@
-use crate::error::Span;
+use std::collections::HashSet;
 
-#[derive(Debug, Clone, PartialEq)]
+use serde::{Serialize, Serializer};
+
+use crate::error::{Position, Span};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Query {
     pub expression: Expression,
+    #[serde(serialize_with = "compact_span")]
     pub span: Span,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl Query {
+    /// whether `field` is used anywhere in the query, in any polarity
+    pub fn contains_field(&self, field: FieldType) -> bool {
+        self.expression.contains_field(&field)
+    }
+
+    /// all search terms in the query in source order, including field values
+    pub fn terms(&self) -> Vec<&Term> {
+        let mut terms = Vec::new();
+        self.expression.collect_terms(true, &mut terms);
+        terms
+    }
+
+    /// search terms outside field values, e.g. `apple` but not `en` in `apple language:en`
+    pub fn keyword_terms(&self) -> Vec<&Term> {
+        let mut terms = Vec::new();
+        self.expression.collect_terms(false, &mut terms);
+        terms
+    }
+
+    /// every `entityId:` value in source order, without repeats, e.g. to pre-fetch entities
+    pub fn entity_ids(&self) -> Vec<String> {
+        let mut ids = Vec::new();
+        self.expression.collect_entity_ids(false, &mut ids);
+        ids
+    }
+
+    /// number of boolean and proximity operators, including implicit ANDs
+    pub fn operator_count(&self) -> usize {
+        self.expression.operator_count()
+    }
+
+    /// every kind of operator used, e.g. to count how many queries in a corpus use NEAR
+    pub fn operators(&self) -> HashSet<OperatorKind> {
+        let mut operators = HashSet::new();
+        let mut stack = vec![&self.expression];
+        while let Some(expr) = stack.pop() {
+            match expr {
+                Expression::BooleanOp { operator, .. } => {
+                    operators.insert(match operator {
+                        BooleanOperator::And => OperatorKind::And,
+                        BooleanOperator::Or => OperatorKind::Or,
+                        BooleanOperator::Not => OperatorKind::Not,
+                    });
+                }
+                Expression::Proximity { operator, .. } => {
+                    operators.insert(match operator {
+                        ProximityOperator::Proximity { .. } => OperatorKind::Proximity,
+                        ProximityOperator::Near { .. } => OperatorKind::Near,
+                        ProximityOperator::NearForward { .. } => OperatorKind::NearForward,
+                    });
+                }
+                _ => {}
+            }
+            stack.extend(expr.children());
+        }
+        operators
+    }
+
+    /// deepest nesting of parenthesized groups (0 when there are none)
+    pub fn max_depth(&self) -> usize {
+        self.expression.group_depth()
+    }
+
+    /// equal ignoring spans, so differently spaced copies of a query compare equal
+    pub fn structural_eq(&self, other: &Query) -> bool {
+        self.expression.structural_eq(&other.expression)
+    }
+
+    /// the query as an indented tree of clauses, one operand per line, for reading
+    /// complex boolean logic; each nested group is indented by `indent` spaces
+    ///
+    /// AND and NOT chains share a level (`a AND b NOT c`), and an AND chain inside an
+    /// ungrouped OR is parenthesized, so the layout shows precedence explicitly
+    pub fn pretty(&self, indent: usize) -> String {
+        self.expression.pretty_lines(&" ".repeat(indent)).join("\n")
+    }
+
+    /// innermost expression whose span contains the byte `offset` (end inclusive, so a cursor
+    /// right after a term still resolves to it)
+    pub fn node_at_offset(&self, offset: usize) -> Option<&Expression> {
+        self.nodes_at_offset(offset).pop()
+    }
+
+    /// span and kind of every expression, depth-first with each parent before its children
+    /// (so enclosing regions come before the regions they contain)
+    pub fn depth_first_spans(&self) -> Vec<(Span, NodeKind)> {
+        let mut spans = Vec::new();
+        let mut stack = vec![&self.expression];
+        while let Some(expr) = stack.pop() {
+            spans.push((expr.span().clone(), expr.kind()));
+            let children: Vec<_> = expr.children().collect();
+            stack.extend(children.into_iter().rev());
+        }
+        spans
+    }
+
+    /// every expression containing the byte `offset`, outermost first
+    pub fn nodes_at_offset(&self, offset: usize) -> Vec<&Expression> {
+        let mut nodes = Vec::new();
+        let mut current = Some(&self.expression);
+        while let Some(expr) = current.filter(|expr| expr.contains_offset(offset)) {
+            nodes.push(expr);
+            current = expr.children().find(|child| child.contains_offset(offset));
+        }
+        nodes
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
 pub enum Expression {
     BooleanOp {
         operator: BooleanOperator,
         left: Box<Expression>,
         right: Option<Box<Expression>>,
+        #[serde(serialize_with = "compact_span")]
         span: Span,
     },
 
     Group {
         expression: Box<Expression>,
+        #[serde(serialize_with = "compact_span")]
         span: Span,
     },
 
     Proximity {
         operator: ProximityOperator,
         terms: Vec<Expression>,
+        #[serde(serialize_with = "compact_span")]
         span: Span,
+        /// span of the `~`, `NEAR/x` or `NEAR/xf` token itself
+        #[serde(serialize_with = "compact_span")]
+        operator_span: Span,
     },
 
     Field {
         field: FieldType,
         value: Box<Expression>,
+        #[serde(serialize_with = "compact_span")]
         span: Span,
     },
 
     Range {
         field: Option<FieldType>,
-        start: String,
-        end: String,
+        start: RangeBound,
+        end: RangeBound,
+        #[serde(serialize_with = "compact_span")]
         span: Span,
     },
 
     Term {
         term: Term,
+        #[serde(serialize_with = "compact_span")]
         span: Span,
     },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// which kind of [`Expression`] a node is, without its contents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    BooleanOp,
+    Group,
+    Proximity,
+    Field,
+    Range,
+    Term,
+}
+
+/// an operator in a query, without its operands or distance (see [`Query::operators`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperatorKind {
+    /// explicit or implicit
+    And,
+    Or,
+    Not,
+    /// `NEAR/x`
+    Near,
+    /// `NEAR/xf`
+    NearForward,
+    /// `"apple juice"~5`
+    Proximity,
+}
+
+impl Expression {
+    pub fn kind(&self) -> NodeKind {
+        match self {
+            Expression::BooleanOp { .. } => NodeKind::BooleanOp,
+            Expression::Group { .. } => NodeKind::Group,
+            Expression::Proximity { .. } => NodeKind::Proximity,
+            Expression::Field { .. } => NodeKind::Field,
+            Expression::Range { .. } => NodeKind::Range,
+            Expression::Term { .. } => NodeKind::Term,
+        }
+    }
+
+    /// equal ignoring spans (including proximity operator spans)
+    pub fn structural_eq(&self, other: &Expression) -> bool {
+        match (self, other) {
+            (
+                Expression::BooleanOp {
+                    operator,
+                    left,
+                    right,
+                    ..
+                },
+                Expression::BooleanOp {
+                    operator: other_operator,
+                    left: other_left,
+                    right: other_right,
+                    ..
+                },
+            ) => {
+                operator == other_operator
+                    && left.structural_eq(other_left)
+                    && match (right, other_right) {
+                        (Some(right), Some(other_right)) => right.structural_eq(other_right),
+                        (None, None) => true,
+                        _ => false,
+                    }
+            }
+            (
+                Expression::Group { expression, .. },
+                Expression::Group {
+                    expression: other_expression,
+                    ..
+                },
+            ) => expression.structural_eq(other_expression),
+            (
+                Expression::Proximity {
+                    operator, terms, ..
+                },
+                Expression::Proximity {
+                    operator: other_operator,
+                    terms: other_terms,
+                    ..
+                },
+            ) => {
+                operator == other_operator
+                    && terms.len() == other_terms.len()
+                    && terms
+                        .iter()
+                        .zip(other_terms)
+                        .all(|(term, other_term)| term.structural_eq(other_term))
+            }
+            (
+                Expression::Field { field, value, .. },
+                Expression::Field {
+                    field: other_field,
+                    value: other_value,
+                    ..
+                },
+            ) => field == other_field && value.structural_eq(other_value),
+            (
+                Expression::Range {
+                    field, start, end, ..
+                },
+                Expression::Range {
+                    field: other_field,
+                    start: other_start,
+                    end: other_end,
+                    ..
+                },
+            ) => field == other_field && start == other_start && end == other_end,
+            (
+                Expression::Term { term, .. },
+                Expression::Term {
+                    term: other_term, ..
+                },
+            ) => term == other_term,
+            _ => false,
+        }
+    }
+
+    fn contains_offset(&self, offset: usize) -> bool {
+        let span = self.span();
+        span.start.byte_offset <= offset && offset <= span.end.byte_offset
+    }
+
+    pub(crate) fn children(&self) -> Box<dyn Iterator<Item = &Expression> + '_> {
+        match self {
+            Expression::BooleanOp { left, right, .. } => {
+                Box::new(std::iter::once(left.as_ref()).chain(right.as_deref()))
+            }
+            Expression::Group { expression, .. } => Box::new(std::iter::once(expression.as_ref())),
+            Expression::Proximity { terms, .. } => Box::new(terms.iter()),
+            Expression::Field { value, .. } => Box::new(std::iter::once(value.as_ref())),
+            Expression::Range { .. } | Expression::Term { .. } => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn operator_count(&self) -> usize {
+        let own = usize::from(matches!(
+            self,
+            Expression::BooleanOp { .. } | Expression::Proximity { .. }
+        ));
+        own + self
+            .children()
+            .map(Expression::operator_count)
+            .sum::<usize>()
+    }
+
+    fn group_depth(&self) -> usize {
+        let own = usize::from(matches!(self, Expression::Group { .. }));
+        own + self
+            .children()
+            .map(Expression::group_depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn contains_field(&self, target: &FieldType) -> bool {
+        match self {
+            Expression::BooleanOp { left, right, .. } => {
+                left.contains_field(target)
+                    || right.as_ref().is_some_and(|r| r.contains_field(target))
+            }
+            Expression::Group { expression, .. } => expression.contains_field(target),
+            Expression::Proximity { terms, .. } => {
+                terms.iter().any(|term| term.contains_field(target))
+            }
+            Expression::Field { field, value, .. } => {
+                field == target || value.contains_field(target)
+            }
+            Expression::Range { field, .. } => field.as_ref() == Some(target),
+            Expression::Term { .. } => false,
+        }
+    }
+
+    /// single-line source form, used for the leaves of [`Query::pretty`]
+    pub(crate) fn inline_text(&self) -> String {
+        match self {
+            Expression::BooleanOp {
+                operator,
+                left,
+                right: Some(right),
+                ..
+            } => format!(
+                "{} {} {}",
+                left.inline_text(),
+                operator.as_str(),
+                right.inline_text()
+            ),
+            Expression::BooleanOp { left, .. } => format!("NOT {}", left.inline_text()),
+            Expression::Group { expression, .. } => format!("({})", expression.inline_text()),
+            Expression::Proximity {
+                operator, terms, ..
+            } => {
+                let terms: Vec<_> = terms.iter().map(Expression::inline_text).collect();
+                match operator {
+                    ProximityOperator::Proximity { distance } => format!(
+                        "{}~{}",
+                        terms.join(" "),
+                        distance.map(|d| d.to_string()).unwrap_or_default()
+                    ),
+                    ProximityOperator::Near { distance } => {
+                        terms.join(&format!(" NEAR/{distance} "))
+                    }
+                    ProximityOperator::NearForward { distance } => {
+                        terms.join(&format!(" NEAR/{distance}f "))
+                    }
+                }
+            }
+            Expression::Field { field, value, .. } => {
+                format!("{}:{}", field.as_str(), value.inline_text())
+            }
+            Expression::Range { start, end, .. } => {
+                let bound = |bound: &RangeBound| match bound {
+                    RangeBound::Value(value) => value.clone(),
+                    RangeBound::Unbounded => "*".to_string(),
+                };
+                format!("[{} TO {}]", bound(start), bound(end))
+            }
+            Expression::Term { term, .. } => match term {
+                Term::Word { value } | Term::Wildcard { value } | Term::Emoji { value } => {
+                    value.clone()
+                }
+                // undo the lexer's unescaping so the phrase reads back the same
+                Term::Phrase { value } => {
+                    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+                }
+                Term::CaseSensitive { value } => format!("{{{value}}}"),
+                Term::Hashtag { value } => format!("#{value}"),
+                Term::Mention { value } => format!("@{value}"),
+            },
+        }
+    }
+
+    fn pretty_lines(&self, indent: &str) -> Vec<String> {
+        match self {
+            Expression::BooleanOp {
+                operator,
+                right: Some(_),
+                ..
+            } => {
+                let mut operands = Vec::new();
+                self.flatten_chain(Self::is_or_level(operator), None, &mut operands);
+
+                let mut lines = Vec::new();
+                for (operator, operand) in operands {
+                    let mut operand_lines = match operand {
+                        // a chain of the other level, e.g. `a AND b` under `x OR ...`
+                        Expression::BooleanOp { right: Some(_), .. } => {
+                            Self::block("(", operand, ")", indent)
+                        }
+                        _ => operand.pretty_lines(indent),
+                    };
+                    if let Some(operator) = operator {
+                        operand_lines[0] = format!("{} {}", operator.as_str(), operand_lines[0]);
+                    }
+                    lines.extend(operand_lines);
+                }
+                lines
+            }
+            Expression::BooleanOp { left, .. } => {
+                let mut lines = left.pretty_lines(indent);
+                lines[0] = format!("NOT {}", lines[0]);
+                lines
+            }
+            Expression::Group { expression, .. } => Self::block("(", expression, ")", indent),
+            Expression::Field { field, value, .. } => match value.as_ref() {
+                Expression::Group { expression, .. } => {
+                    Self::block(&format!("{}:(", field.as_str()), expression, ")", indent)
+                }
+                _ => vec![self.inline_text()],
+            },
+            _ => vec![self.inline_text()],
+        }
+    }
+
+    /// `open`, `inner` indented, `close`; on one line when `inner` isn't a chain
+    fn block(open: &str, inner: &Expression, close: &str, indent: &str) -> Vec<String> {
+        let inner_lines = inner.pretty_lines(indent);
+        if inner_lines.len() == 1 {
+            return vec![format!("{open}{}{close}", inner_lines[0])];
+        }
+
+        let mut lines = vec![open.to_string()];
+        lines.extend(
+            inner_lines
+                .into_iter()
+                .map(|line| format!("{indent}{line}")),
+        );
+        lines.push(close.to_string());
+        lines
+    }
+
+    /// OR chains are one level; AND and binary NOT chains are the other
+    fn is_or_level(operator: &BooleanOperator) -> bool {
+        matches!(operator, BooleanOperator::Or)
+    }
+
+    /// the operands of a chain of same-level operators, each with the operator before it
+    fn flatten_chain<'a>(
+        &'a self,
+        or_level: bool,
+        operator: Option<&'a BooleanOperator>,
+        operands: &mut Vec<(Option<&'a BooleanOperator>, &'a Expression)>,
+    ) {
+        match self {
+            Expression::BooleanOp {
+                operator: own,
+                left,
+                right: Some(right),
+                ..
+            } if Self::is_or_level(own) == or_level => {
+                left.flatten_chain(or_level, operator, operands);
+                right.flatten_chain(or_level, Some(own), operands);
+            }
+            _ => operands.push((operator, self)),
+        }
+    }
+
+    fn collect_entity_ids(&self, in_entity_field: bool, ids: &mut Vec<String>) {
+        match self {
+            Expression::Field { field, value, .. } => {
+                value.collect_entity_ids(*field == FieldType::EntityId, ids)
+            }
+            Expression::Term {
+                term: Term::Word { value } | Term::Phrase { value },
+                ..
+            } if in_entity_field => {
+                if !ids.contains(value) {
+                    ids.push(value.clone());
+                }
+            }
+            _ => {
+                for child in self.children() {
+                    child.collect_entity_ids(in_entity_field, ids);
+                }
+            }
+        }
+    }
+
+    fn collect_terms<'a>(&'a self, include_field_values: bool, terms: &mut Vec<&'a Term>) {
+        match self {
+            Expression::BooleanOp { left, right, .. } => {
+                left.collect_terms(include_field_values, terms);
+                if let Some(right) = right {
+                    right.collect_terms(include_field_values, terms);
+                }
+            }
+            Expression::Group { expression, .. } => {
+                expression.collect_terms(include_field_values, terms)
+            }
+            Expression::Proximity {
+                terms: children, ..
+            } => {
+                for child in children {
+                    child.collect_terms(include_field_values, terms);
+                }
+            }
+            Expression::Field { value, .. } => {
+                if include_field_values {
+                    value.collect_terms(include_field_values, terms)
+                }
+            }
+            Expression::Range { .. } => {}
+            Expression::Term { term, .. } => terms.push(term),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
 pub enum Term {
     Word { value: String },
     Phrase { value: String },
@@ -56,7 +546,27 @@ pub enum Term {
     Emoji { value: String },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// one end of a `[start TO end]` range; serializes as the value, or `null` when unbounded
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum RangeBound {
+    Value(String),
+    /// `*`, leaving that side of the range open
+    Unbounded,
+}
+
+impl RangeBound {
+    /// parse a concrete bound; `Ok(None)` when unbounded
+    pub fn parse<T: std::str::FromStr>(&self) -> Result<Option<T>, T::Err> {
+        match self {
+            RangeBound::Value(value) => value.parse().map(Some),
+            RangeBound::Unbounded => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum BooleanOperator {
     And,
     Or,
@@ -82,7 +592,8 @@ impl BooleanOperator {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
 pub enum ProximityOperator {
     Proximity { distance: Option<u32> },
     Near { distance: u32 },
@@ -143,6 +654,56 @@ pub enum FieldType {
 }
 
 impl FieldType {
+    /// every field, in declaration order
+    pub const ALL: &'static [FieldType] = &[
+        Self::Title,
+        Self::Site,
+        Self::Url,
+        Self::Author,
+        Self::Links,
+        Self::Continent,
+        Self::Country,
+        Self::Region,
+        Self::City,
+        Self::Latitude,
+        Self::Longitude,
+        Self::Language,
+        Self::ChannelId,
+        Self::AuthorGender,
+        Self::AuthorVerified,
+        Self::AuthorVerifiedType,
+        Self::AuthorFollowers,
+        Self::BlogName,
+        Self::ParentBlogName,
+        Self::RootBlogName,
+        Self::ParentPostId,
+        Self::RootPostId,
+        Self::Tags,
+        Self::BrandIds,
+        Self::Objects,
+        Self::EngagementType,
+        Self::EngagingWith,
+        Self::EngagingWithGuid,
+        Self::Guid,
+        Self::ImageType,
+        Self::ItemReview,
+        Self::Rating,
+        Self::MinuteOfDay,
+        Self::PubType,
+        Self::PublisherSubType,
+        Self::Publication,
+        Self::RedditAuthorFlair,
+        Self::RedditPostFlair,
+        Self::RedditSpoiler,
+        Self::SensitiveContent,
+        Self::Subreddit,
+        Self::SubredditNSFW,
+        Self::SubredditTopics,
+        Self::TopLevelDomain,
+        Self::WeblogTitle,
+        Self::EntityId,
+    ];
+
     pub fn parse(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "title" => Some(Self::Title),
@@ -195,6 +756,82 @@ impl FieldType {
         }
     }
 
+    /// fields whose values are free text, where a multi-word value needs quoting
+    /// and enum/range checks don't apply
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self,
+            Self::Title
+                | Self::Url
+                | Self::Author
+                | Self::BlogName
+                | Self::ParentBlogName
+                | Self::RootBlogName
+                | Self::WeblogTitle
+                | Self::Publication
+                | Self::RedditAuthorFlair
+                | Self::RedditPostFlair
+        )
+    }
+
+    /// location fields whose values are place names, which can run to several words
+    pub fn is_place_name(&self) -> bool {
+        matches!(self, Self::Continent | Self::Region | Self::City)
+    }
+
+    /// fields limited to a fixed set of values (including true/false), which can't take a range
+    pub fn is_enumerated(&self) -> bool {
+        matches!(
+            self,
+            Self::AuthorGender
+                | Self::EngagementType
+                | Self::AuthorVerifiedType
+                | Self::AuthorVerified
+                | Self::RedditSpoiler
+                | Self::SubredditNSFW
+                | Self::SensitiveContent
+        )
+    }
+
+    /// the values an enumerated field accepts, `None` for every other field
+    pub fn allowed_values(&self) -> Option<&'static [&'static str]> {
+        match self {
+            Self::AuthorGender => Some(&["F", "M"]),
+            Self::EngagementType => Some(&["COMMENT", "REPLY", "RETWEET", "QUOTE"]),
+            Self::AuthorVerifiedType => Some(&["blue", "business", "government"]),
+            Self::AuthorVerified
+            | Self::RedditSpoiler
+            | Self::SubredditNSFW
+            | Self::SensitiveContent => Some(&["true", "false"]),
+            _ => None,
+        }
+    }
+
+    /// numeric fields that take a `[from TO to]` range
+    pub fn is_range(&self) -> bool {
+        matches!(
+            self,
+            Self::AuthorFollowers
+                | Self::MinuteOfDay
+                | Self::Rating
+                | Self::Latitude
+                | Self::Longitude
+        )
+    }
+
+    /// fields that only exist on Reddit content
+    pub fn is_reddit_specific(&self) -> bool {
+        matches!(
+            self,
+            Self::RedditAuthorFlair
+                | Self::RedditPostFlair
+                | Self::RedditSpoiler
+                | Self::Subreddit
+                | Self::SubredditNSFW
+                | Self::SubredditTopics
+        )
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Title => "title",
@@ -246,3 +883,279 @@ impl FieldType {
         }
     }
 }
+
+/// serialized by its query spelling, e.g. `"authorFollowers"`
+impl Serialize for FieldType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// a span in the AST dump: `{line, column, offset}` at each end, where `offset` counts chars
+/// (as in diagnostics' `span`), to keep the tree small
+fn compact_span<S: Serializer>(span: &Span, serializer: S) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct CompactPosition {
+        line: usize,
+        column: usize,
+        offset: usize,
+    }
+
+    #[derive(Serialize)]
+    struct CompactSpan {
+        start: CompactPosition,
+        end: CompactPosition,
+    }
+
+    let compact = |pos: &Position| CompactPosition {
+        line: pos.line,
+        column: pos.column,
+        offset: pos.char_offset,
+    };
+    CompactSpan {
+        start: compact(&span.start),
+        end: compact(&span.end),
+    }
+    .serialize(serializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn parse(query: &str) -> Query {
+        let tokens = Lexer::new(query).tokenize().unwrap();
+        Parser::new(tokens).unwrap().parse().unwrap().query
+    }
+
+    #[test]
+    fn test_pretty() {
+        let query = parse(
+            "(apple OR title:(juice OR \"orange soda\")) AND {Apple} NOT rating:[1 TO *] AND pie NEAR/3f cake",
+        );
+        assert_eq!(
+            query.pretty(2),
+            [
+                "(",
+                "  apple",
+                "  OR title:(",
+                "    juice",
+                "    OR \"orange soda\"",
+                "  )",
+                ")",
+                "AND {Apple}",
+                "NOT rating:[1 TO *]",
+                "AND pie NEAR/3f cake",
+            ]
+            .join("\n")
+        );
+
+        assert_eq!(
+            parse("a OR b AND c").pretty(4),
+            "a\nOR (\n    b\n    AND c\n)"
+        );
+        assert_eq!(parse("apple").pretty(4), "apple");
+
+        let query = parse(r#"title:"say \"hi\" C:\\dir" AND pie"#);
+        let pretty = query.pretty(4);
+        assert_eq!(pretty, "title:\"say \\\"hi\\\" C:\\\\dir\"\nAND pie");
+        assert_eq!(parse(&pretty).pretty(4), pretty);
+    }
+
+    #[test]
+    fn test_contains_field() {
+        let query = parse("(apple OR juice) AND language:en NOT rating:[1 TO 2]");
+        assert!(query.contains_field(FieldType::Language));
+        assert!(query.contains_field(FieldType::Rating));
+        assert!(!query.contains_field(FieldType::Country));
+    }
+
+    #[test]
+    fn test_entity_ids() {
+        let query = parse("entityId:(Q1 OR Q2) AND entityId:Q3 AND title:Q4 NOT entityId:Q1");
+        assert_eq!(query.entity_ids(), vec!["Q1", "Q2", "Q3"]);
+        assert!(parse("apple").entity_ids().is_empty());
+    }
+
+    #[test]
+    fn test_terms() {
+        let query = parse("apple AND \"orange juice\" AND (#fresh NEAR/3 juic*) AND title:drink");
+        let terms = query.terms();
+        assert_eq!(
+            terms,
+            vec![
+                &Term::Word {
+                    value: "apple".to_string()
+                },
+                &Term::Phrase {
+                    value: "orange juice".to_string()
+                },
+                &Term::Hashtag {
+                    value: "fresh".to_string()
+                },
+                &Term::Wildcard {
+                    value: "juic*".to_string()
+                },
+                &Term::Word {
+                    value: "drink".to_string()
+                },
+            ]
+        );
+        assert_eq!(query.keyword_terms().len(), 4);
+        assert!(
+            parse("language:en AND rating:[1 TO 5]")
+                .keyword_terms()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_is_text() {
+        for field in [FieldType::Title, FieldType::Author, FieldType::Url] {
+            assert!(field.is_text(), "{}", field.as_str());
+        }
+        for field in [
+            FieldType::Rating,
+            FieldType::Language,
+            FieldType::AuthorFollowers,
+            FieldType::Site,
+        ] {
+            assert!(!field.is_text(), "{}", field.as_str());
+        }
+    }
+
+    #[test]
+    fn test_structural_eq() {
+        let compact = parse("apple AND (juice OR soda) NEAR/3 fresh AND rating:[1 TO 3]");
+        let spaced = parse("apple   AND\n(  juice OR soda )  NEAR/3   fresh AND rating:[1 TO 3]");
+        assert_ne!(compact, spaced);
+        assert!(compact.structural_eq(&spaced));
+
+        let different = parse("apple AND (juice OR cola) NEAR/3 fresh AND rating:[1 TO 3]");
+        assert!(!compact.structural_eq(&different));
+
+        let reordered = parse("(juice OR soda) NEAR/3 fresh AND apple AND rating:[1 TO 3]");
+        assert!(!compact.structural_eq(&reordered));
+    }
+
+    #[test]
+    fn test_operator_count_and_max_depth() {
+        let query = parse("apple");
+        assert_eq!(query.operator_count(), 0);
+        assert_eq!(query.max_depth(), 0);
+
+        let query = parse("(apple OR (banana NEAR/3 juice)) AND title:(x y) NOT z");
+        assert_eq!(query.operator_count(), 5);
+        assert_eq!(query.max_depth(), 2);
+    }
+
+    #[test]
+    fn test_operators() {
+        let query = parse("(apple juice OR soda) NOT bitter AND fizzy NEAR/3f drink");
+        assert_eq!(
+            query.operators(),
+            HashSet::from([
+                OperatorKind::And,
+                OperatorKind::Or,
+                OperatorKind::Not,
+                OperatorKind::NearForward,
+            ])
+        );
+        assert_eq!(
+            parse("\"apple juice\"~5").operators(),
+            HashSet::from([OperatorKind::Proximity])
+        );
+        assert!(parse("apple").operators().is_empty());
+    }
+
+    #[test]
+    fn test_depth_first_spans() {
+        let query = parse("apple AND (title:juice OR rating:[1 TO 5])");
+        let spans: Vec<_> = query
+            .depth_first_spans()
+            .into_iter()
+            .map(|(span, kind)| (span.start.byte_offset, span.end.byte_offset, kind))
+            .collect();
+
+        assert_eq!(
+            spans,
+            vec![
+                (0, 42, NodeKind::BooleanOp),
+                (0, 5, NodeKind::Term),
+                (10, 42, NodeKind::Group),
+                (11, 41, NodeKind::BooleanOp),
+                (11, 22, NodeKind::Field),
+                (17, 22, NodeKind::Term),
+                (26, 41, NodeKind::Field),
+                (33, 41, NodeKind::Range),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_node_at_offset() {
+        let query = parse("(apple OR banana) AND title:\"x\"");
+
+        let node = query.node_at_offset(3).unwrap();
+        assert!(matches!(
+            node,
+            Expression::Term { term: Term::Word { value }, .. } if value == "apple"
+        ));
+
+        let node = query.node_at_offset(7).unwrap();
+        assert!(matches!(
+            node,
+            Expression::BooleanOp {
+                operator: BooleanOperator::Or,
+                ..
+            }
+        ));
+
+        let node = query.node_at_offset(0).unwrap();
+        assert!(matches!(node, Expression::Group { .. }));
+
+        let node = query.node_at_offset(19).unwrap();
+        assert!(matches!(
+            node,
+            Expression::BooleanOp {
+                operator: BooleanOperator::And,
+                ..
+            }
+        ));
+
+        let node = query.node_at_offset(23).unwrap();
+        assert!(matches!(
+            node,
+            Expression::Field {
+                field: FieldType::Title,
+                ..
+            }
+        ));
+
+        let node = query.node_at_offset(29).unwrap();
+        assert!(matches!(
+            node,
+            Expression::Term { term: Term::Phrase { value }, .. } if value == "x"
+        ));
+
+        assert!(query.node_at_offset(100).is_none());
+    }
+
+    #[test]
+    fn test_nodes_at_offset() {
+        let query = parse("(apple OR banana) AND title:\"x\"");
+        let kinds: Vec<_> = query
+            .nodes_at_offset(12)
+            .iter()
+            .map(|node| match node {
+                Expression::BooleanOp { .. } => "op",
+                Expression::Group { .. } => "group",
+                Expression::Term { .. } => "term",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(kinds, vec!["op", "group", "op", "term"]);
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::ast::FieldType;
+use crate::parser::IMPLICIT_AND_RULE;
+use crate::registry;
+use crate::validation::ValidationEngine;
+
+/// name of the project configuration file, looked up from the working directory upwards
+pub const CONFIG_FILE_NAME: &str = "bwq.toml";
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("invalid configuration: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("unknown field '{0}' in require_fields")]
+    UnknownField(String),
+
+    #[error("unknown diagnostic code '{0}' in extend_select")]
+    UnknownCode(String),
+
+    #[error("unknown rule '{0}' in [rules] or select")]
+    UnknownRule(String),
+}
+
+/// what a `[rules]` entry does with a rule's diagnostics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleSeverity {
+    Off,
+    Warning,
+    Error,
+}
+
+/// Project settings from `bwq.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LinterConfig {
+    /// fields every query must use, e.g. `["language"]`
+    pub require_fields: Vec<String>,
+    /// allow `// comment` to the end of the line alongside `<<< >>>`
+    pub line_comments: bool,
+    /// longest query accepted, in characters (E034 beyond it)
+    pub max_query_length: Option<usize>,
+    /// widest NEAR/x distance accepted without a warning (W041 beyond it)
+    pub max_near_distance: Option<u32>,
+    /// report every preview rule, e.g. W034
+    pub preview: bool,
+    /// preview codes to report without enabling all of them, e.g. `["W034"]`
+    pub extend_select: Vec<String>,
+    /// stop at the first parse error instead of recovering and reporting the rest
+    pub no_recover: bool,
+    /// only report these codes or names, e.g. `["E012", "potential-typo"]` (empty: all)
+    pub select: Vec<String>,
+    /// per-rule overrides keyed by validation rule (`rating-field`), diagnostic name
+    /// (`potential-typo`) or code (`W001`); also read from `severity_overrides`
+    #[serde(alias = "severity_overrides")]
+    pub rules: BTreeMap<String, RuleSeverity>,
+}
+
+impl LinterConfig {
+    pub fn from_toml_str(source: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(source)?;
+        if let Some(unknown) = config
+            .require_fields
+            .iter()
+            .find(|field| FieldType::parse(field).is_none())
+        {
+            return Err(ConfigError::UnknownField(unknown.clone()));
+        }
+        if let Some(unknown) = config
+            .extend_select
+            .iter()
+            .find(|code| registry::lookup(code).is_none())
+        {
+            return Err(ConfigError::UnknownCode(unknown.clone()));
+        }
+        if let Some(unknown) = config
+            .select
+            .iter()
+            .find(|key| registry::resolve(key).is_none())
+        {
+            return Err(ConfigError::UnknownRule(unknown.clone()));
+        }
+        if let Some(unknown) = config.rules.keys().find(|key| !is_rule_key(key)) {
+            return Err(ConfigError::UnknownRule(unknown.clone()));
+        }
+        Ok(config)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let source = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_toml_str(&source)
+    }
+
+    /// find the nearest `bwq.toml` in `start` or one of its ancestors
+    pub fn discover(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|candidate| candidate.is_file())
+    }
+
+    pub fn required_fields(&self) -> Vec<FieldType> {
+        self.require_fields
+            .iter()
+            .filter_map(|field| FieldType::parse(field))
+            .collect()
+    }
+}
+
+/// a validation rule name, diagnostic name or code, or `implicit-and`
+pub fn is_rule_key(key: &str) -> bool {
+    key == IMPLICIT_AND_RULE
+        || registry::resolve(key).is_some()
+        || ValidationEngine::new().rule_names().any(|name| name == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_require_fields() {
+        let config =
+            LinterConfig::from_toml_str(r#"require_fields = ["language", "Country"]"#).unwrap();
+        assert_eq!(
+            config.required_fields(),
+            vec![FieldType::Language, FieldType::Country]
+        );
+
+        assert!(
+            LinterConfig::from_toml_str("")
+                .unwrap()
+                .require_fields
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_parse_line_comments() {
+        assert!(!LinterConfig::from_toml_str("").unwrap().line_comments);
+        assert!(
+            LinterConfig::from_toml_str("line_comments = true")
+                .unwrap()
+                .line_comments
+        );
+    }
+
+    #[test]
+    fn test_parse_max_query_length() {
+        assert_eq!(
+            LinterConfig::from_toml_str("").unwrap().max_query_length,
+            None
+        );
+        assert_eq!(
+            LinterConfig::from_toml_str("max_query_length = 2000")
+                .unwrap()
+                .max_query_length,
+            Some(2000)
+        );
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let config = LinterConfig::from_toml_str(
+            r#"
+            select = ["W001", "operator-mixing"]
+
+            [rules]
+            rating-field = "off"
+            potential-typo = "error"
+            E012 = "warning"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.select, ["W001", "operator-mixing"]);
+        assert_eq!(config.rules["rating-field"], RuleSeverity::Off);
+        assert_eq!(config.rules["potential-typo"], RuleSeverity::Error);
+        assert_eq!(config.rules["E012"], RuleSeverity::Warning);
+
+        let config =
+            LinterConfig::from_toml_str(r#"severity_overrides = { W001 = "error" }"#).unwrap();
+        assert_eq!(config.rules["W001"], RuleSeverity::Error);
+
+        let config = LinterConfig::from_toml_str("[rules]\nimplicit-and = \"off\"").unwrap();
+        assert_eq!(config.rules["implicit-and"], RuleSeverity::Off);
+        assert!(matches!(
+            LinterConfig::from_toml_str("[rules]\nimplicit-andd = \"off\""),
+            Err(ConfigError::UnknownRule(rule)) if rule == "implicit-andd"
+        ));
+        assert!(matches!(
+            LinterConfig::from_toml_str("[rules]\nrating-field = \"silent\""),
+            Err(ConfigError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(matches!(
+            LinterConfig::from_toml_str(r#"require_fields = ["lang"]"#),
+            Err(ConfigError::UnknownField(field)) if field == "lang"
+        ));
+        assert!(matches!(
+            LinterConfig::from_toml_str(r#"extend_select = ["W999"]"#),
+            Err(ConfigError::UnknownCode(code)) if code == "W999"
+        ));
+        assert!(matches!(
+            LinterConfig::from_toml_str("require_feilds = []"),
+            Err(ConfigError::Parse(_))
+        ));
+    }
+}
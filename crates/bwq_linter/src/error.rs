@@ -1,23 +1,42 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Position {
+    /// 1-based line
     pub line: usize,
+    /// 1-based column, in chars
     pub column: usize,
-    pub offset: usize,
+    /// index into the query's chars
+    pub char_offset: usize,
+    /// index into the query's UTF-8 bytes (for slicing `&str`)
+    pub byte_offset: usize,
 }
 
 impl Position {
-    pub fn new(line: usize, column: usize, offset: usize) -> Self {
+    pub fn new(line: usize, column: usize, char_offset: usize, byte_offset: usize) -> Self {
         Self {
             line,
             column,
-            offset,
+            char_offset,
+            byte_offset,
         }
     }
+
+    /// step past `ch`, moving to the start of the next line on a newline
+    pub fn advance(&mut self, ch: char) {
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        self.char_offset += 1;
+        self.byte_offset += ch.len_utf8();
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Span {
     pub start: Position,
     pub end: Position,
@@ -35,15 +54,37 @@ impl Span {
         }
     }
 
+    /// zero-width, e.g. [`Self::single`]: an insertion point such as a missing operand
+    pub fn is_empty(&self) -> bool {
+        self.start.byte_offset == self.end.byte_offset
+    }
+
+    /// span covering the single ASCII character at `pos`
     pub fn single_character(pos: Position) -> Self {
         Self {
             start: pos.clone(),
-            end: Position::new(pos.line, pos.column + 1, pos.offset + 1),
+            end: Position::new(
+                pos.line,
+                pos.column + 1,
+                pos.char_offset + 1,
+                pos.byte_offset + 1,
+            ),
         }
     }
 }
 
-#[derive(Error, Debug, Clone, PartialEq)]
+/// `offset` is kept (as the char offset) for consumers of the original JSON shape
+fn position_json(pos: &Position) -> serde_json::Value {
+    serde_json::json!({
+        "line": pos.line,
+        "column": pos.column,
+        "offset": pos.char_offset,
+        "char_offset": pos.char_offset,
+        "byte_offset": pos.byte_offset,
+    })
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LintError {
     #[error("{message}")]
     LexerError { span: Span, message: String },
@@ -87,6 +128,37 @@ pub enum LintError {
 
     #[error("{message}")]
     PureNegativeQueryError { span: Span, message: String },
+
+    #[error("{message}")]
+    MissingRequiredField { span: Span, message: String },
+
+    #[error("{message}")]
+    ComparisonShorthand { span: Span, message: String },
+
+    #[error("{message}")]
+    Timeout { span: Span, message: String },
+
+    #[error("{message}")]
+    QueryTooLong { span: Span, message: String },
+
+    #[error("{message}")]
+    UnmatchedBracket { span: Span, message: String },
+
+    #[error("{message}")]
+    UnmatchedBrace { span: Span, message: String },
+
+    #[error("{message}")]
+    RepeatedOperator { span: Span, message: String },
+
+    #[error("{message}")]
+    MatchAllQuery { span: Span, message: String },
+
+    #[error("{message}")]
+    NearMissingOperand { span: Span, message: String },
+
+    /// a warning raised to an error by a `[rules]` override; keeps its own code and message
+    #[error("{0}")]
+    Escalated(Box<LintWarning>),
 }
 
 impl LintError {
@@ -104,7 +176,17 @@ impl LintError {
             | LintError::ProximityOperatorError { span, .. }
             | LintError::InvalidFieldRange { span, .. }
             | LintError::OperatorMixingError { span, .. }
-            | LintError::PureNegativeQueryError { span, .. } => span,
+            | LintError::PureNegativeQueryError { span, .. }
+            | LintError::MissingRequiredField { span, .. }
+            | LintError::ComparisonShorthand { span, .. }
+            | LintError::Timeout { span, .. }
+            | LintError::QueryTooLong { span, .. }
+            | LintError::UnmatchedBracket { span, .. }
+            | LintError::UnmatchedBrace { span, .. }
+            | LintError::RepeatedOperator { span, .. }
+            | LintError::MatchAllQuery { span, .. }
+            | LintError::NearMissingOperand { span, .. } => span,
+            LintError::Escalated(warning) => warning.span(),
         }
     }
 
@@ -123,30 +205,140 @@ impl LintError {
             LintError::InvalidFieldRange { .. } => "E011",
             LintError::OperatorMixingError { .. } => "E012",
             LintError::PureNegativeQueryError { .. } => "E013",
+            LintError::MissingRequiredField { .. } => "E031",
+            LintError::ComparisonShorthand { .. } => "E032",
+            LintError::Timeout { .. } => "E033",
+            LintError::QueryTooLong { .. } => "E034",
+            LintError::UnmatchedBracket { .. } => "E035",
+            LintError::UnmatchedBrace { .. } => "E036",
+            LintError::RepeatedOperator { .. } => "E037",
+            LintError::MatchAllQuery { .. } => "E038",
+            LintError::NearMissingOperand { .. } => "E039",
+            LintError::Escalated(warning) => warning.code(),
+        }
+    }
+
+    /// the same diagnostic reported as a warning (undoing [`LintWarning::into_error`])
+    pub fn into_warning(self) -> LintWarning {
+        match self {
+            LintError::Escalated(warning) => *warning,
+            error => LintWarning::Downgraded(Box::new(error)),
         }
     }
 
     pub fn span_json(&self) -> serde_json::Value {
         let span = self.span();
         serde_json::json!({
-            "start": {"line": span.start.line, "column": span.start.column, "offset": span.start.offset},
-            "end": {"line": span.end.line, "column": span.end.column, "offset": span.end.offset}
+            "start": position_json(&span.start),
+            "end": position_json(&span.end)
         })
     }
 
+    /// `byte_offset`/`end_byte_offset` are for plugins that highlight without line/column; the
+    /// `offset` inside `span` stays a char offset
     pub fn to_json(&self) -> serde_json::Value {
         serde_json::json!({
             "code": self.code(),
             "message": format!("{}", self),
-            "span": self.span_json()
+            "span": self.span_json(),
+            "byte_offset": self.span().start.byte_offset,
+            "end_byte_offset": self.span().end.byte_offset
         })
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LintWarning {
-    PotentialTypo { span: Span, message: String },
-    PerformanceWarning { span: Span, message: String },
+    PotentialTypo {
+        span: Span,
+        message: String,
+    },
+    PerformanceWarning {
+        span: Span,
+        message: String,
+    },
+    PlatformConflict {
+        span: Span,
+        message: String,
+    },
+    PrefixOperator {
+        span: Span,
+        message: String,
+    },
+    UnquotedFieldPhrase {
+        span: Span,
+        message: String,
+    },
+    StopwordQuery {
+        span: Span,
+        message: String,
+    },
+    OperatorCase {
+        span: Span,
+        message: String,
+    },
+    RedundantSite {
+        span: Span,
+        message: String,
+    },
+    ZeroProximityDistance {
+        span: Span,
+        message: String,
+    },
+    DoubleNegation {
+        span: Span,
+        message: String,
+    },
+    QuotedQuery {
+        span: Span,
+        message: String,
+    },
+    DuplicateOperand {
+        span: Span,
+        message: String,
+    },
+    InvalidSourceName {
+        span: Span,
+        message: String,
+    },
+    ConflictingLanguage {
+        span: Span,
+        message: String,
+    },
+    OperatorInCaseSensitive {
+        span: Span,
+        message: String,
+    },
+    UnquotedLocation {
+        span: Span,
+        message: String,
+    },
+    AmbiguousNot {
+        span: Span,
+        message: String,
+    },
+    UnusedSuppression {
+        span: Span,
+        message: String,
+    },
+    LargeNearDistance {
+        span: Span,
+        message: String,
+    },
+    FieldsOnlyQuery {
+        span: Span,
+        message: String,
+    },
+    OperatorFieldValue {
+        span: Span,
+        message: String,
+    },
+    ReviewPlatformConflict {
+        span: Span,
+        message: String,
+    },
+    /// an error lowered to a warning by a `[rules]` override; keeps its own code and message
+    Downgraded(Box<LintError>),
 }
 
 impl std::fmt::Display for LintWarning {
@@ -158,6 +350,67 @@ impl std::fmt::Display for LintWarning {
             LintWarning::PerformanceWarning { message, .. } => {
                 write!(f, "Performance warning: {message}")
             }
+            LintWarning::PlatformConflict { message, .. } => {
+                write!(f, "Platform conflict: {message}")
+            }
+            LintWarning::PrefixOperator { message, .. } => {
+                write!(f, "Unsupported prefix operator: {message}")
+            }
+            LintWarning::UnquotedFieldPhrase { message, .. } => {
+                write!(f, "Unquoted field phrase: {message}")
+            }
+            LintWarning::StopwordQuery { message, .. } => {
+                write!(f, "Stopword query: {message}")
+            }
+            LintWarning::OperatorCase { message, .. } => {
+                write!(f, "Possible operator typo: {message}")
+            }
+            LintWarning::RedundantSite { message, .. } => {
+                write!(f, "Redundant site filter: {message}")
+            }
+            LintWarning::ZeroProximityDistance { message, .. } => {
+                write!(f, "Zero proximity distance: {message}")
+            }
+            LintWarning::DoubleNegation { message, .. } => {
+                write!(f, "Double negation: {message}")
+            }
+            LintWarning::QuotedQuery { message, .. } => {
+                write!(f, "Quoted query: {message}")
+            }
+            LintWarning::DuplicateOperand { message, .. } => {
+                write!(f, "Duplicate operand: {message}")
+            }
+            LintWarning::InvalidSourceName { message, .. } => {
+                write!(f, "Invalid source name: {message}")
+            }
+            LintWarning::ConflictingLanguage { message, .. } => {
+                write!(f, "Conflicting languages: {message}")
+            }
+            LintWarning::OperatorInCaseSensitive { message, .. } => {
+                write!(f, "Operator in case-sensitive term: {message}")
+            }
+            LintWarning::UnquotedLocation { message, .. } => {
+                write!(f, "Unquoted location: {message}")
+            }
+            LintWarning::AmbiguousNot { message, .. } => {
+                write!(f, "Ambiguous NOT: {message}")
+            }
+            LintWarning::UnusedSuppression { message, .. } => {
+                write!(f, "Unused suppression: {message}")
+            }
+            LintWarning::LargeNearDistance { message, .. } => {
+                write!(f, "Large NEAR distance: {message}")
+            }
+            LintWarning::FieldsOnlyQuery { message, .. } => {
+                write!(f, "Fields-only query: {message}")
+            }
+            LintWarning::OperatorFieldValue { message, .. } => {
+                write!(f, "Operator as field value: {message}")
+            }
+            LintWarning::ReviewPlatformConflict { message, .. } => {
+                write!(f, "Review field platform conflict: {message}")
+            }
+            LintWarning::Downgraded(error) => write!(f, "{error}"),
         }
     }
 }
@@ -167,13 +420,63 @@ impl LintWarning {
         match self {
             LintWarning::PotentialTypo { .. } => "W001",
             LintWarning::PerformanceWarning { .. } => "W002",
+            LintWarning::PlatformConflict { .. } => "W025",
+            LintWarning::PrefixOperator { .. } => "W026",
+            LintWarning::UnquotedFieldPhrase { .. } => "W027",
+            LintWarning::StopwordQuery { .. } => "W028",
+            LintWarning::OperatorCase { .. } => "W029",
+            LintWarning::RedundantSite { .. } => "W030",
+            LintWarning::ZeroProximityDistance { .. } => "W031",
+            LintWarning::DoubleNegation { .. } => "W032",
+            LintWarning::QuotedQuery { .. } => "W033",
+            LintWarning::DuplicateOperand { .. } => "W034",
+            LintWarning::InvalidSourceName { .. } => "W035",
+            LintWarning::ConflictingLanguage { .. } => "W036",
+            LintWarning::OperatorInCaseSensitive { .. } => "W037",
+            LintWarning::UnquotedLocation { .. } => "W038",
+            LintWarning::AmbiguousNot { .. } => "W039",
+            LintWarning::UnusedSuppression { .. } => "W040",
+            LintWarning::LargeNearDistance { .. } => "W041",
+            LintWarning::FieldsOnlyQuery { .. } => "W042",
+            LintWarning::OperatorFieldValue { .. } => "W043",
+            LintWarning::ReviewPlatformConflict { .. } => "W044",
+            LintWarning::Downgraded(error) => error.code(),
+        }
+    }
+
+    /// the same diagnostic reported as an error (undoing [`LintError::into_warning`])
+    pub fn into_error(self) -> LintError {
+        match self {
+            LintWarning::Downgraded(error) => *error,
+            warning => LintError::Escalated(Box::new(warning)),
         }
     }
 
     pub fn span(&self) -> &Span {
         match self {
             LintWarning::PotentialTypo { span, .. }
-            | LintWarning::PerformanceWarning { span, .. } => span,
+            | LintWarning::PerformanceWarning { span, .. }
+            | LintWarning::PlatformConflict { span, .. }
+            | LintWarning::PrefixOperator { span, .. }
+            | LintWarning::UnquotedFieldPhrase { span, .. }
+            | LintWarning::StopwordQuery { span, .. }
+            | LintWarning::OperatorCase { span, .. }
+            | LintWarning::RedundantSite { span, .. }
+            | LintWarning::ZeroProximityDistance { span, .. }
+            | LintWarning::DoubleNegation { span, .. }
+            | LintWarning::QuotedQuery { span, .. }
+            | LintWarning::DuplicateOperand { span, .. }
+            | LintWarning::InvalidSourceName { span, .. }
+            | LintWarning::ConflictingLanguage { span, .. }
+            | LintWarning::OperatorInCaseSensitive { span, .. }
+            | LintWarning::UnquotedLocation { span, .. }
+            | LintWarning::AmbiguousNot { span, .. }
+            | LintWarning::UnusedSuppression { span, .. }
+            | LintWarning::LargeNearDistance { span, .. }
+            | LintWarning::FieldsOnlyQuery { span, .. }
+            | LintWarning::OperatorFieldValue { span, .. }
+            | LintWarning::ReviewPlatformConflict { span, .. } => span,
+            LintWarning::Downgraded(error) => error.span(),
         }
     }
 
@@ -183,9 +486,11 @@ impl LintWarning {
             "code": self.code(),
             "message": format!("{}", self),
             "span": {
-                "start": {"line": span.start.line, "column": span.start.column, "offset": span.start.offset},
-                "end": {"line": span.end.line, "column": span.end.column, "offset": span.end.offset}
-            }
+                "start": position_json(&span.start),
+                "end": position_json(&span.end)
+            },
+            "byte_offset": span.start.byte_offset,
+            "end_byte_offset": span.end.byte_offset
         })
     }
 }
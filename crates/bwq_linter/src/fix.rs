@@ -0,0 +1,175 @@
+//! Deterministic autofixes: source edits that resolve a diagnostic without changing what the
+//! query matches
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{BooleanOperator, Expression, FieldType, Query, Term};
+use crate::error::Span;
+
+/// replace the text under `span` with `replacement` (an empty span inserts)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fix {
+    /// the diagnostic code this resolves, e.g. `W001`
+    pub code: String,
+    pub span: Span,
+    pub replacement: String,
+}
+
+impl Fix {
+    fn new(code: &str, span: Span, replacement: impl Into<String>) -> Self {
+        Self {
+            code: code.to_string(),
+            span,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// the `apple banana` -> `apple AND banana` insertion, the only W001 fix with an empty span
+    pub fn is_implicit_and(&self) -> bool {
+        self.code == "W001" && self.span.start.byte_offset == self.span.end.byte_offset
+    }
+}
+
+/// every fix available for `query`, which was parsed from `source`, in document order
+pub fn collect(query: &Query, source: &str) -> Vec<Fix> {
+    let mut fixes = Vec::new();
+    collect_from(&query.expression, source, &mut fixes);
+    fixes.sort_by_key(|fix| fix.span.start.byte_offset);
+    fixes
+}
+
+/// apply `fixes` to `source`, skipping any that overlap an earlier one
+pub fn apply(source: &str, fixes: &[Fix]) -> String {
+    apply_with_count(source, fixes).0
+}
+
+/// like [`apply`], also returning how many fixes were applied (the rest overlapped)
+pub fn apply_with_count(source: &str, fixes: &[Fix]) -> (String, usize) {
+    let mut sorted: Vec<_> = fixes.iter().collect();
+    sorted.sort_by_key(|fix| fix.span.start.byte_offset);
+
+    let mut fixed = String::with_capacity(source.len());
+    let mut copied_to = 0;
+    let mut applied = 0;
+    for fix in sorted {
+        let (start, end) = (fix.span.start.byte_offset, fix.span.end.byte_offset);
+        if start < copied_to || end > source.len() {
+            continue;
+        }
+        fixed.push_str(&source[copied_to..start]);
+        fixed.push_str(&fix.replacement);
+        copied_to = end;
+        applied += 1;
+    }
+    fixed.push_str(&source[copied_to..]);
+    (fixed, applied)
+}
+
+fn collect_from(expr: &Expression, source: &str, fixes: &mut Vec<Fix>) {
+    match expr {
+        // `apple banana` -> `apple AND banana`: only when nothing but whitespace separates
+        // the operands, so an explicit AND (or a comment) is left alone
+        Expression::BooleanOp {
+            operator: BooleanOperator::And,
+            left,
+            right: Some(right),
+            ..
+        } => {
+            let gap = source.get(left.span().end.byte_offset..right.span().start.byte_offset);
+            if gap.is_some_and(|gap| !gap.is_empty() && gap.trim().is_empty()) {
+                fixes.push(Fix::new(
+                    "W001",
+                    Span::single(left.span().end.clone()),
+                    " AND",
+                ));
+            }
+        }
+        // `apple AND juice OR soda` -> `(apple AND juice) OR soda`, the grouping the parser
+        // already gave it (E012)
+        Expression::BooleanOp {
+            operator: BooleanOperator::Or,
+            left,
+            right: Some(right),
+            ..
+        } => {
+            for operand in [left, right] {
+                if let Expression::BooleanOp {
+                    operator: BooleanOperator::And,
+                    span,
+                    ..
+                } = operand.as_ref()
+                {
+                    if let Some(text) = source.get(span.start.byte_offset..span.end.byte_offset) {
+                        fixes.push(Fix::new("E012", span.clone(), format!("({text})")));
+                    }
+                }
+            }
+        }
+        // `language:EN` -> `language:en`
+        Expression::Field {
+            field: FieldType::Language,
+            value,
+            ..
+        } => {
+            if let Expression::Term {
+                term: Term::Word { value: code },
+                span,
+            } = value.as_ref()
+            {
+                if code.chars().any(|c| c.is_ascii_uppercase()) {
+                    fixes.push(Fix::new("W001", span.clone(), code.to_ascii_lowercase()));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    for child in expr.children() {
+        collect_from(child, source, fixes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BrandwatchLinter;
+
+    fn fixes_for(source: &str) -> Vec<Fix> {
+        let (_, query) = BrandwatchLinter::new().lint_for_server(source).unwrap();
+        collect(&query, source)
+    }
+
+    #[test]
+    fn test_implicit_and_fix() {
+        let source = "apple  banana AND cherry <<<note>>> date";
+        assert_eq!(
+            apply(source, &fixes_for(source)),
+            "apple AND  banana AND cherry <<<note>>> date"
+        );
+    }
+
+    #[test]
+    fn test_mixed_and_or_fix() {
+        let source = "apple AND juice OR soda OR fresh AND (cold OR hot)";
+        assert_eq!(
+            apply(source, &fixes_for(source)),
+            "(apple AND juice) OR soda OR (fresh AND (cold OR hot))"
+        );
+
+        // the inner implicit AND overlaps the parentheses and waits for another pass
+        let source = "apple juice OR soda";
+        let (fixed, applied) = apply_with_count(source, &fixes_for(source));
+        assert_eq!((fixed.as_str(), applied), ("(apple juice) OR soda", 1));
+    }
+
+    #[test]
+    fn test_overlapping_fixes_are_skipped() {
+        let source = "apple banana";
+        let position = |offset| crate::error::Position::new(1, offset + 1, offset, offset);
+        let fixes = [
+            Fix::new("W001", Span::new(position(0), position(5)), "pear"),
+            Fix::new("W001", Span::new(position(3), position(12)), "kiwi"),
+        ];
+        assert_eq!(apply(source, &fixes), "pear banana");
+    }
+}
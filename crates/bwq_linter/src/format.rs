@@ -0,0 +1,430 @@
+//! Canonical query formatting: single spaces around uppercase operators, no space after field
+//! colons, comments kept after the node they follow (with a line break after each `//`
+//! comment), and queries too long for one line broken into indented groups
+//!
+//! Formatting never changes what a query means or which diagnostics it gets: implicit ANDs stay
+//! implicit and no parentheses are added or removed.
+
+use std::collections::HashMap;
+
+use crate::BrandwatchLinter;
+use crate::ast::{BooleanOperator, Expression, ProximityOperator};
+use crate::config::LinterConfig;
+use crate::error::{LintResult, Span};
+use crate::lexer::{Lexer, TokenType};
+
+/// queries (and groups within them) longer than this many characters are broken across lines
+pub const MAX_WIDTH: usize = 80;
+
+const INDENT: &str = "    ";
+
+/// `query` in canonical form; formatting the result again returns it unchanged
+pub fn format_query(query: &str) -> LintResult<String> {
+    format_query_with(query, &LinterConfig::default())
+}
+
+/// [`format_query`], parsing the way `config` does (e.g. with `line_comments`)
+pub fn format_query_with(query: &str, config: &LinterConfig) -> LintResult<String> {
+    if query.trim().is_empty() {
+        return Ok(String::new());
+    }
+
+    let ast = BrandwatchLinter::new().with_config(config).parse(query)?;
+    let formatter = Formatter::new(query, &ast.expression, config.line_comments)?;
+
+    let mut lines = formatter.lines(&ast.expression, MAX_WIDTH);
+    if !formatter.leading_comments.is_empty() {
+        let leading = join_comments(&formatter.leading_comments);
+        if lines.len() == 1
+            && !leading.contains('\n')
+            && leading.chars().count() + 1 + lines[0].chars().count() <= MAX_WIDTH
+        {
+            lines[0] = format!("{leading} {}", lines[0]);
+        } else {
+            lines.splice(0..0, split_lines(&leading));
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+struct Formatter<'a> {
+    source: &'a str,
+    /// byte offsets of every `AND` written out in the source (the rest are implicit)
+    explicit_ands: Vec<usize>,
+    /// `<<<comments>>>` and `// comments` (ending in a newline), keyed by the end offset of the
+    /// node they follow
+    trailing_comments: HashMap<usize, Vec<String>>,
+    /// comments before the first term
+    leading_comments: Vec<String>,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(source: &'a str, expression: &Expression, line_comments: bool) -> LintResult<Self> {
+        let mut explicit_ands = Vec::new();
+        let mut comments = Vec::new();
+        let mut comment_start = None;
+        let tokens = Lexer::new(source)
+            .with_line_comments(line_comments)
+            .tokenize_full()?;
+        for token in tokens {
+            match token.token_type {
+                TokenType::CommentStart => comment_start = Some((token.span.start, String::new())),
+                TokenType::Comment(text) => match &mut comment_start {
+                    Some((_, body)) => *body = text,
+                    // a `//` comment runs to the end of the line, so the line break stays
+                    None => comments.push((
+                        token.span.start.byte_offset,
+                        format!("{}\n", token.raw.trim_end()),
+                    )),
+                },
+                TokenType::CommentEnd => {
+                    if let Some((start, body)) = comment_start.take() {
+                        comments.push((start.byte_offset, format!("<<<{body}>>>")));
+                    }
+                }
+                TokenType::And if comment_start.is_none() => {
+                    explicit_ands.push(token.span.start.byte_offset)
+                }
+                _ => {}
+            }
+        }
+
+        let mut node_ends = Vec::new();
+        collect_node_ends(expression, &mut node_ends);
+        node_ends.sort_unstable();
+
+        let mut trailing_comments: HashMap<usize, Vec<String>> = HashMap::new();
+        let mut leading_comments = Vec::new();
+        for (start, comment) in comments {
+            match node_ends.iter().rev().find(|&&end| end <= start) {
+                Some(&end) => trailing_comments.entry(end).or_default().push(comment),
+                None => leading_comments.push(comment),
+            }
+        }
+
+        Ok(Self {
+            source,
+            explicit_ands,
+            trailing_comments,
+            leading_comments,
+        })
+    }
+
+    /// `expr` on one line if it fits in `width`, otherwise one operand per line with groups
+    /// indented
+    fn lines(&self, expr: &Expression, width: usize) -> Vec<String> {
+        let flat = self.flat(expr);
+        let flat_lines = split_lines(&flat);
+        if flat_lines.iter().all(|line| line.chars().count() <= width)
+            && !self.has_line_break_in_group(expr)
+        {
+            return flat_lines;
+        }
+
+        match expr {
+            Expression::BooleanOp { right: Some(_), .. } => {
+                let mut operands = Vec::new();
+                self.flatten_chain(expr, "", &mut operands);
+
+                let mut lines = Vec::new();
+                for (prefix, operand) in operands {
+                    let mut operand_lines = self.lines(operand, width.saturating_sub(prefix.len()));
+                    operand_lines[0] = format!("{prefix}{}", operand_lines[0]);
+                    lines.extend(operand_lines);
+                }
+                lines
+            }
+            Expression::BooleanOp { left, .. } => {
+                let mut lines = self.lines(left, width.saturating_sub("NOT ".len()));
+                lines[0] = format!("NOT {}", lines[0]);
+                lines
+            }
+            Expression::Group { expression, span } => self.block("(", expression, span, width),
+            Expression::Field { field, value, .. } => match value.as_ref() {
+                Expression::Group { expression, span } => {
+                    self.block(&format!("{}:(", field.as_str()), expression, span, width)
+                }
+                _ => split_lines(&flat),
+            },
+            _ => split_lines(&flat),
+        }
+    }
+
+    /// `open`, then `inner` indented on its own lines, then `)` and the comments after the group
+    /// spanning `span`
+    fn block(&self, open: &str, inner: &Expression, span: &Span, width: usize) -> Vec<String> {
+        let mut lines = vec![open.to_string()];
+        lines.extend(
+            self.lines(inner, width.saturating_sub(INDENT.len()))
+                .into_iter()
+                .map(|line| format!("{INDENT}{line}")),
+        );
+        lines.extend(split_lines(&format!("){}", self.comments_after(span))));
+        lines
+    }
+
+    /// the operands of a run of binary operators, each with the operator written before it
+    /// (`""` for the first operand and implicit ANDs)
+    fn flatten_chain<'e>(
+        &self,
+        expr: &'e Expression,
+        prefix: &'static str,
+        operands: &mut Vec<(&'static str, &'e Expression)>,
+    ) {
+        match expr {
+            Expression::BooleanOp {
+                operator,
+                left,
+                right: Some(right),
+                ..
+            } => {
+                self.flatten_chain(left, prefix, operands);
+                let operator = match operator {
+                    BooleanOperator::And if self.is_implicit_and(left, right) => "",
+                    BooleanOperator::And => "AND ",
+                    BooleanOperator::Or => "OR ",
+                    BooleanOperator::Not => "NOT ",
+                };
+                self.flatten_chain(right, operator, operands);
+            }
+            _ => operands.push((prefix, expr)),
+        }
+    }
+
+    /// single-line form of `expr`, except for the line break after each `//` comment
+    fn flat(&self, expr: &Expression) -> String {
+        let text = match expr {
+            Expression::BooleanOp { right: Some(_), .. } => {
+                let mut operands = Vec::new();
+                self.flatten_chain(expr, "", &mut operands);
+                operands
+                    .into_iter()
+                    .map(|(prefix, operand)| format!("{prefix}{}", self.flat(operand)))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+            Expression::BooleanOp { left, .. } => format!("NOT {}", self.flat(left)),
+            Expression::Group { expression, .. } => format!("({})", self.flat(expression)),
+            Expression::Proximity {
+                operator, terms, ..
+            } => {
+                let terms: Vec<_> = terms.iter().map(|term| self.flat(term)).collect();
+                match operator {
+                    ProximityOperator::Proximity { distance } => format!(
+                        "{}~{}",
+                        terms.join(" "),
+                        distance.map(|d| d.to_string()).unwrap_or_default()
+                    ),
+                    ProximityOperator::Near { distance } => {
+                        terms.join(&format!(" NEAR/{distance} "))
+                    }
+                    ProximityOperator::NearForward { distance } => {
+                        terms.join(&format!(" NEAR/{distance}f "))
+                    }
+                }
+            }
+            Expression::Field { field, value, .. } => {
+                format!("{}:{}", field.as_str(), self.flat(value))
+            }
+            Expression::Range { .. } => expr.inline_text(),
+            // the source text keeps escapes (`\"`) that the parsed value has dropped
+            Expression::Term { span, .. } => self
+                .source
+                .get(span.start.byte_offset..span.end.byte_offset)
+                .map(str::to_string)
+                .unwrap_or_else(|| expr.inline_text()),
+        };
+        // a node that ends where its last child does leaves the comments to that child
+        let span = expr.span();
+        if expr
+            .children()
+            .any(|child| child.span().end.byte_offset == span.end.byte_offset)
+        {
+            return text;
+        }
+        text + &self.comments_after(span)
+    }
+
+    /// whether a `//` comment ends a line inside the parentheses of a group in `expr`, which
+    /// then has to be written as an indented block
+    fn has_line_break_in_group(&self, expr: &Expression) -> bool {
+        match expr {
+            Expression::Group { expression, .. } if self.flat(expression).contains('\n') => true,
+            _ => expr
+                .children()
+                .into_iter()
+                .any(|child| self.has_line_break_in_group(child)),
+        }
+    }
+
+    /// whether nothing but whitespace and comments separates two ANDed operands
+    fn is_implicit_and(&self, left: &Expression, right: &Expression) -> bool {
+        let (gap_start, gap_end) = (left.span().end.byte_offset, right.span().start.byte_offset);
+        !self
+            .explicit_ands
+            .iter()
+            .any(|&offset| (gap_start..gap_end).contains(&offset))
+    }
+
+    fn comments_after(&self, span: &Span) -> String {
+        self.trailing_comments
+            .get(&span.end.byte_offset)
+            .map(|comments| format!(" {}", join_comments(comments)))
+            .unwrap_or_default()
+    }
+}
+
+/// end offsets of every node, so a comment follows the outermost node that ends before it
+fn collect_node_ends(expr: &Expression, ends: &mut Vec<usize>) {
+    ends.push(expr.span().end.byte_offset);
+    for child in expr.children() {
+        collect_node_ends(child, ends);
+    }
+}
+
+/// comments separated by a space, or by the line break a `//` comment already ends with
+fn join_comments(comments: &[String]) -> String {
+    let mut joined = String::new();
+    for comment in comments {
+        if !joined.is_empty() && !joined.ends_with('\n') {
+            joined.push(' ');
+        }
+        joined.push_str(comment);
+    }
+    joined
+}
+
+/// `text` split at the line breaks `//` comments end with, without the spaces that followed
+fn split_lines(text: &str) -> Vec<String> {
+    text.trim_end_matches('\n')
+        .split('\n')
+        .map(|line| line.trim_start().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic_codes(query: &str) -> Vec<String> {
+        let analysis = BrandwatchLinter::new().analyze(query);
+        let mut codes: Vec<_> = analysis
+            .errors
+            .iter()
+            .map(|error| error.code().to_string())
+            .chain(
+                analysis
+                    .warnings
+                    .iter()
+                    .map(|warning| warning.code().to_string()),
+            )
+            .collect();
+        codes.sort();
+        codes
+    }
+
+    #[test]
+    fn test_format_normalizes_spacing() {
+        assert_eq!(
+            format_query("  apple   AND  (juice  OR\n soda)  NOT title:soda   \"fizzy drink\"~3 ")
+                .unwrap(),
+            "apple AND (juice OR soda) NOT title:soda \"fizzy drink\"~3"
+        );
+        assert_eq!(
+            format_query("apple   NEAR/3f juice  rating:[1   TO  *]").unwrap(),
+            "apple NEAR/3f juice rating:[1 TO *]"
+        );
+        assert_eq!(format_query("   ").unwrap(), "");
+        assert!(format_query("apple AND (juice").is_err());
+    }
+
+    #[test]
+    fn test_format_keeps_implicit_and_and_comments() {
+        assert_eq!(
+            format_query("<<<brand>>>  apple  juice <<<drinks>>> AND   \"say \\\"hi\\\"\"")
+                .unwrap(),
+            "<<<brand>>> apple juice <<<drinks>>> AND \"say \\\"hi\\\"\""
+        );
+    }
+
+    #[test]
+    fn test_format_keeps_comments_in_place() {
+        let line_comments = LinterConfig {
+            line_comments: true,
+            ..LinterConfig::default()
+        };
+        let cases = [
+            ("apple // x\nAND b", "apple // x\nAND b"),
+            ("// brand\n  apple   juice", "// brand\napple juice"),
+            ("(a OR b) // x\n<<<y>>> c", "(a OR b) // x\n<<<y>>> c"),
+            ("title:apple // x", "title:apple // x"),
+            ("(a // x\nOR b) c", "(\n    a // x\n    OR b\n)\nc"),
+        ];
+        for (query, expected) in cases {
+            let formatted = format_query_with(query, &line_comments).unwrap();
+            assert_eq!(formatted, expected, "{query}");
+            assert_eq!(
+                format_query_with(&formatted, &line_comments).unwrap(),
+                formatted
+            );
+        }
+
+        // a comment after a group stays outside it
+        assert_eq!(
+            format_query("(a  OR b)  <<<x>>>  c").unwrap(),
+            "(a OR b) <<<x>>> c"
+        );
+        assert_eq!(
+            format_query("title:(a OR b) <<<x>>>").unwrap(),
+            "title:(a OR b) <<<x>>>"
+        );
+        let query = "(apple OR banana OR cherry OR \"dragon fruit\" OR elderberry OR fig OR grape) \
+                     <<<fruit>>> AND juice";
+        assert_eq!(
+            format_query(query).unwrap(),
+            "(\n    apple OR banana OR cherry OR \"dragon fruit\" OR elderberry OR fig OR grape\n) \
+             <<<fruit>>>\nAND juice"
+        );
+    }
+
+    #[test]
+    fn test_format_breaks_long_queries() {
+        let query = "(apple OR banana OR cherry OR \"dragon fruit\" OR elderberry OR fig OR grape \
+                     OR honeydew) AND (juice OR smoothie OR soda) AND NOT site:example.com";
+        let expected = "\
+(
+    apple
+    OR banana
+    OR cherry
+    OR \"dragon fruit\"
+    OR elderberry
+    OR fig
+    OR grape
+    OR honeydew
+)
+AND (juice OR smoothie OR soda)
+AND NOT site:example.com";
+        assert_eq!(format_query(query).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_is_idempotent_and_keeps_diagnostics() {
+        let queries = [
+            "apple juice",
+            "apple AND juice OR soda",
+            "title:apple juice <<<note>>> NOT (soda OR (fizz AND pop))",
+            "language:EN AND ((apple OR banana OR cherry OR \"dragon fruit\" OR elderberry) AND \
+             (juice OR smoothie OR soda OR lemonade OR \"sparkling water\" OR cordial))",
+            "{BrandName} NEAR/5 (launch OR release) AND authorFollowers:[1000 TO *]",
+        ];
+        for query in queries {
+            let formatted = format_query(query).unwrap();
+            assert_eq!(format_query(&formatted).unwrap(), formatted, "{query}");
+            assert_eq!(
+                diagnostic_codes(&formatted),
+                diagnostic_codes(query),
+                "{query}"
+            );
+        }
+    }
+}
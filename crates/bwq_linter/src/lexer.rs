@@ -28,6 +28,8 @@ pub enum TokenType {
 
     CommentStart,
     CommentEnd,
+    /// text between `<<<` and `>>>`, or after `//` when line comments are enabled
+    Comment(String),
 
     Field(String),
 
@@ -63,6 +65,7 @@ impl fmt::Display for TokenType {
             TokenType::NearForward(n) => write!(f, "NEAR/{n}f"),
             TokenType::CommentStart => write!(f, "<<<"),
             TokenType::CommentEnd => write!(f, ">>>"),
+            TokenType::Comment(c) => write!(f, "comment '{c}'"),
             TokenType::Field(f_name) => write!(f, "field '{f_name}'"),
             TokenType::Hashtag(h) => write!(f, "hashtag '{h}'"),
             TokenType::Mention(m) => write!(f, "mention '{m}'"),
@@ -93,10 +96,12 @@ impl Token {
 /// lexer for tokenizing  queries
 pub struct Lexer {
     input: Vec<char>,
-    position: usize,
-    line: usize,
-    column: usize,
+    /// position of the next char to read; only `advance` moves it
+    cursor: Position,
+    /// opener of the first quoted string that ran onto another line
+    multiline_quote: Option<Position>,
     inside_comment: bool,
+    line_comments: bool,
 }
 
 impl Lexer {
@@ -139,9 +144,7 @@ impl Lexer {
                 self.inside_comment = false;
                 return self.read_comment_end();
             } else {
-                // Skip any character inside comments
-                self.advance_with_position_tracking(ch);
-                return self.next_token();
+                return self.read_comment_body();
             }
         } else if ch == '<' && self.peek_ahead(2) == "<<" {
             return self.read_comment_start();
@@ -152,47 +155,50 @@ impl Lexer {
         Ok(None)
     }
 
-    fn advance_with_position_tracking(&mut self, ch: char) {
-        self.advance();
-        if ch == '\n' {
-            self.line += 1;
-            self.column = 1;
-        } else {
-            self.column += 1;
-        }
-    }
     pub fn new(input: &str) -> Self {
         Self {
             input: input.chars().collect(),
-            position: 0,
-            line: 1,
-            column: 1,
+            cursor: Position::new(1, 1, 0, 0),
+            multiline_quote: None,
             inside_comment: false,
+            line_comments: false,
         }
     }
 
+    /// also treat `//` at the start of a line or after whitespace as a comment running to the
+    /// end of the line, so `http://` in a url is never a comment
+    pub fn with_line_comments(mut self, enabled: bool) -> Self {
+        self.line_comments = enabled;
+        self
+    }
+
+    /// tokens for parsing, without whitespace or comment text
     pub fn tokenize(&mut self) -> LintResult<Vec<Token>> {
-        let mut tokens = Vec::new();
+        self.tokenize_impl(false)
+    }
 
-        while !self.is_at_end() {
-            match self.next_token()? {
-                Some(token) => {
-                    if !matches!(token.token_type, TokenType::Whitespace) {
-                        tokens.push(token);
-                    }
-                }
-                None => break,
-            }
-        }
+    /// lossless token stream that keeps whitespace and comment text (for highlighters and formatters)
+    pub fn tokenize_full(&mut self) -> LintResult<Vec<Token>> {
+        self.tokenize_impl(true)
+    }
 
-        let eof_pos = self.current_position();
-        tokens.push(Token::new(
-            TokenType::Eof,
-            Span::single(eof_pos),
-            String::new(),
-        ));
+    /// pull tokens one at a time, e.g. to highlight only the visible part of a document.
+    /// Yields what [`Self::tokenize`] returns, ending with `Eof`, or stops after the first error
+    pub fn tokens(&mut self) -> Tokens<'_> {
+        Tokens {
+            lexer: self,
+            keep_trivia: false,
+            done: false,
+        }
+    }
 
-        Ok(tokens)
+    fn tokenize_impl(&mut self, keep_trivia: bool) -> LintResult<Vec<Token>> {
+        Tokens {
+            lexer: self,
+            keep_trivia,
+            done: false,
+        }
+        .collect()
     }
 
     fn next_token(&mut self) -> LintResult<Option<Token>> {
@@ -212,15 +218,18 @@ impl Lexer {
             return self.next_token();
         }
 
+        if self.line_comments
+            && ch == '/'
+            && self.peek_ahead(1) == "/"
+            && (self.cursor.char_offset == 0
+                || self.input[self.cursor.char_offset - 1].is_whitespace())
+        {
+            return self.read_line_comment();
+        }
+
         match ch {
             ' ' | '\t' | '\r' | '\n' => {
                 self.advance();
-                if ch == '\n' {
-                    self.line += 1;
-                    self.column = 1;
-                } else {
-                    self.column += 1;
-                }
                 let end_pos = self.current_position();
                 Ok(Some(Token::new(
                     TokenType::Whitespace,
@@ -233,7 +242,6 @@ impl Lexer {
 
             '(' => {
                 self.advance();
-                self.column += 1;
                 Ok(Some(Token::new(
                     TokenType::LeftParen,
                     Span::new(start_pos, self.current_position()),
@@ -242,7 +250,6 @@ impl Lexer {
             }
             ')' => {
                 self.advance();
-                self.column += 1;
                 Ok(Some(Token::new(
                     TokenType::RightParen,
                     Span::new(start_pos, self.current_position()),
@@ -251,7 +258,6 @@ impl Lexer {
             }
             '[' => {
                 self.advance();
-                self.column += 1;
                 Ok(Some(Token::new(
                     TokenType::LeftBracket,
                     Span::new(start_pos, self.current_position()),
@@ -260,7 +266,6 @@ impl Lexer {
             }
             ']' => {
                 self.advance();
-                self.column += 1;
                 Ok(Some(Token::new(
                     TokenType::RightBracket,
                     Span::new(start_pos, self.current_position()),
@@ -269,7 +274,6 @@ impl Lexer {
             }
             '{' => {
                 self.advance();
-                self.column += 1;
                 Ok(Some(Token::new(
                     TokenType::LeftBrace,
                     Span::new(start_pos, self.current_position()),
@@ -278,7 +282,6 @@ impl Lexer {
             }
             '}' => {
                 self.advance();
-                self.column += 1;
                 Ok(Some(Token::new(
                     TokenType::RightBrace,
                     Span::new(start_pos, self.current_position()),
@@ -288,14 +291,12 @@ impl Lexer {
 
             '~' => {
                 self.advance();
-                self.column += 1;
 
                 // check if tilde is followed by a number and then invalid characters
                 let tilde_end = self.current_position();
                 if !self.is_at_end() && self.current_char().is_ascii_digit() {
                     while !self.is_at_end() && self.current_char().is_ascii_digit() {
                         self.advance();
-                        self.column += 1;
                     }
 
                     if !self.is_at_end()
@@ -312,8 +313,7 @@ impl Lexer {
                         });
                     }
 
-                    self.position -= self.current_position().offset - tilde_end.offset;
-                    self.column = tilde_end.column;
+                    self.cursor = tilde_end.clone();
                 }
 
                 Ok(Some(Token::new(
@@ -324,7 +324,9 @@ impl Lexer {
             }
             ':' => {
                 // Check for a space before colon - always fail if there's a space before
-                if self.position > 0 && self.input[self.position - 1].is_whitespace() {
+                if self.cursor.char_offset > 0
+                    && self.input[self.cursor.char_offset - 1].is_whitespace()
+                {
                     return Err(LintError::LexerError {
                         span: Span::single_character(start_pos),
                         message: "Field operator colon must be directly attached to the field name. If the colon is a search term, you'll need to put it in quote marks".to_string(),
@@ -332,7 +334,6 @@ impl Lexer {
                 }
 
                 self.advance();
-                self.column += 1;
                 Ok(Some(Token::new(
                     TokenType::Colon,
                     Span::new(start_pos, self.current_position()),
@@ -354,11 +355,17 @@ impl Lexer {
             }
             _ if self.is_word_char(ch) => self.read_word_or_operator(),
 
+            '>' | '<'
+                if self.cursor.char_offset > 0
+                    && self.input[self.cursor.char_offset - 1] == ':' =>
+            {
+                self.read_comparison_shorthand()
+            }
+
             _ => {
                 self.advance();
-                self.column += 1;
                 Err(LintError::LexerError {
-                    span: Span::single_character(start_pos),
+                    span: Span::new(start_pos, self.current_position()),
                     message: format!("Unexpected character '{ch}'"),
                 })
             }
@@ -372,33 +379,32 @@ impl Lexer {
 
         raw.push(self.current_char());
         self.advance();
-        self.column += 1;
 
         while !self.is_at_end() && self.current_char() != '"' {
-            let ch = self.current_char();
-            value.push(ch);
-            raw.push(ch);
+            let mut ch = self.current_char();
 
-            if ch == '\n' {
-                self.line += 1;
-                self.column = 1;
-            } else {
-                self.column += 1;
+            // `\"` and `\\` are a literal quote/backslash; raw keeps both chars for spans
+            if ch == '\\' && matches!(self.peek_ahead(1).as_str(), "\"" | "\\") {
+                raw.push(ch);
+                self.advance();
+                ch = self.current_char();
             }
 
+            value.push(ch);
+            raw.push(ch);
             self.advance();
         }
 
         if self.is_at_end() {
-            return Err(LintError::LexerError {
-                span: Span::single_character(start_pos),
-                message: "Unterminated quoted string".to_string(),
-            });
+            return Err(self.unterminated_quote(start_pos, value.contains('\n')));
+        }
+
+        if value.contains('\n') && self.multiline_quote.is_none() {
+            self.multiline_quote = Some(start_pos.clone());
         }
 
         raw.push(self.current_char());
         self.advance();
-        self.column += 1;
 
         let end_pos = self.current_position();
         Ok(Some(Token::new(
@@ -408,6 +414,27 @@ impl Lexer {
         )))
     }
 
+    /// phrases rarely span lines, so an earlier quoted string that ran onto the next line is
+    /// the likelier culprit: its closer pairs with a later opener and shifts every quote after it
+    fn unterminated_quote(&self, last_opener: Position, crosses_lines: bool) -> LintError {
+        let (opener, hint) = match &self.multiline_quote {
+            Some(opener) => (opener.clone(), format!("the end of line {}", opener.line)),
+            None if crosses_lines => (
+                last_opener.clone(),
+                format!("the end of line {}", last_opener.line),
+            ),
+            None => (last_opener, "the end of the query".to_string()),
+        };
+
+        LintError::LexerError {
+            message: format!(
+                "Unterminated quoted string opened at line {}, column {}; a closing quote may be missing at {hint}",
+                opener.line, opener.column
+            ),
+            span: Span::single_character(opener),
+        }
+    }
+
     fn read_word_or_operator(&mut self) -> LintResult<Option<Token>> {
         let start_pos = self.current_position();
         let mut value = String::new();
@@ -415,7 +442,6 @@ impl Lexer {
         while !self.is_at_end() && self.is_word_char(self.current_char()) {
             value.push(self.current_char());
             self.advance();
-            self.column += 1;
         }
 
         let end_pos = self.current_position();
@@ -460,7 +486,6 @@ impl Lexer {
         if self.current_char() == '-' {
             value.push(self.current_char());
             self.advance();
-            self.column += 1;
         }
 
         while !self.is_at_end()
@@ -471,7 +496,6 @@ impl Lexer {
         {
             value.push(self.current_char());
             self.advance();
-            self.column += 1;
         }
 
         let end_pos = self.current_position();
@@ -482,17 +506,46 @@ impl Lexer {
         )))
     }
 
+    /// `authorFollowers:>1000` isn't supported; point at the equivalent range instead
+    fn read_comparison_shorthand(&mut self) -> LintResult<Option<Token>> {
+        let start_pos = self.current_position();
+        let operator = self.current_char();
+        self.advance();
+
+        if self.current_char() == '=' {
+            self.advance();
+        }
+
+        let mut bound = String::new();
+        while !self.is_at_end()
+            && (self.current_char().is_ascii_digit() || self.current_char() == '.')
+        {
+            bound.push(self.current_char());
+            self.advance();
+        }
+
+        // only rewrite the user's own bound; `>abc` gets no made-up number
+        let range = match operator {
+            _ if bound.parse::<f64>().is_err() => "a [from TO to] range".to_string(),
+            '>' => format!("a range like [{bound} TO *]"),
+            _ => format!("a range like [* TO {bound}]"),
+        };
+
+        Err(LintError::ComparisonShorthand {
+            span: Span::new(start_pos, self.current_position()),
+            message: format!("Comparison operators are not supported, use {range} instead of >/<"),
+        })
+    }
+
     fn read_hashtag(&mut self) -> LintResult<Option<Token>> {
         let start_pos = self.current_position();
         let mut value = String::new();
 
         self.advance();
-        self.column += 1;
 
         while !self.is_at_end() && self.is_word_char(self.current_char()) {
             value.push(self.current_char());
             self.advance();
-            self.column += 1;
         }
 
         let end_pos = self.current_position();
@@ -508,12 +561,10 @@ impl Lexer {
         let mut value = String::new();
 
         self.advance();
-        self.column += 1;
 
         while !self.is_at_end() && self.is_word_char(self.current_char()) {
             value.push(self.current_char());
             self.advance();
-            self.column += 1;
         }
 
         let end_pos = self.current_position();
@@ -530,7 +581,6 @@ impl Lexer {
         self.advance();
         self.advance();
         self.advance();
-        self.column += 3;
 
         self.inside_comment = true;
 
@@ -542,13 +592,47 @@ impl Lexer {
         )))
     }
 
+    fn read_comment_body(&mut self) -> LintResult<Option<Token>> {
+        let start_pos = self.current_position();
+        let mut value = String::new();
+
+        while !self.is_at_end() && !self.is_at_comment_end() {
+            let ch = self.current_char();
+            value.push(ch);
+            self.advance();
+        }
+
+        let end_pos = self.current_position();
+        Ok(Some(Token::new(
+            TokenType::Comment(value.clone()),
+            Span::new(start_pos, end_pos),
+            value,
+        )))
+    }
+
+    fn read_line_comment(&mut self) -> LintResult<Option<Token>> {
+        let start_pos = self.current_position();
+        let mut raw = String::new();
+
+        while !self.is_at_end() && self.current_char() != '\n' {
+            raw.push(self.current_char());
+            self.advance();
+        }
+
+        let text = raw[2..].trim().to_string();
+        Ok(Some(Token::new(
+            TokenType::Comment(text),
+            Span::new(start_pos, self.current_position()),
+            raw,
+        )))
+    }
+
     fn read_comment_end(&mut self) -> LintResult<Option<Token>> {
         let start_pos = self.current_position();
 
         self.advance();
         self.advance();
         self.advance();
-        self.column += 3;
 
         let end_pos = self.current_position();
         Ok(Some(Token::new(
@@ -562,29 +646,33 @@ impl Lexer {
         if self.is_at_end() {
             '\0'
         } else {
-            self.input[self.position]
+            self.input[self.cursor.char_offset]
         }
     }
 
     fn advance(&mut self) {
         if !self.is_at_end() {
-            self.position += 1;
+            self.cursor.advance(self.input[self.cursor.char_offset]);
         }
     }
 
     fn is_at_end(&self) -> bool {
-        self.position >= self.input.len()
+        self.cursor.char_offset >= self.input.len()
+    }
+
+    fn is_at_comment_end(&self) -> bool {
+        self.current_char() == '>' && self.peek_ahead(2) == ">>"
     }
 
     fn current_position(&self) -> Position {
-        Position::new(self.line, self.column, self.position)
+        self.cursor.clone()
     }
 
     fn peek_ahead(&self, n: usize) -> String {
         let mut result = String::new();
         for i in 0..n {
-            if self.position + i + 1 < self.input.len() {
-                result.push(self.input[self.position + i + 1]);
+            if self.cursor.char_offset + i + 1 < self.input.len() {
+                result.push(self.input[self.cursor.char_offset + i + 1]);
             } else {
                 break;
             }
@@ -593,7 +681,7 @@ impl Lexer {
     }
 
     fn has_word_chars_ahead(&self) -> bool {
-        let mut pos = self.position;
+        let mut pos = self.cursor.char_offset;
 
         // Skip initial minus sign if present
         if pos < self.input.len() && self.input[pos] == '-' {
@@ -627,6 +715,51 @@ impl Lexer {
     }
 }
 
+/// lazy token stream from [`Lexer::tokens`]
+pub struct Tokens<'a> {
+    lexer: &'a mut Lexer,
+    keep_trivia: bool,
+    done: bool,
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = LintResult<Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        while !self.lexer.is_at_end() {
+            match self.lexer.next_token() {
+                Ok(Some(token)) => {
+                    if self.keep_trivia
+                        || !matches!(
+                            token.token_type,
+                            TokenType::Whitespace | TokenType::Comment(_)
+                        )
+                    {
+                        return Some(Ok(token));
+                    }
+                }
+                Ok(None) => break,
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+
+        self.done = true;
+        let eof_pos = self.lexer.current_position();
+        Some(Ok(Token::new(
+            TokenType::Eof,
+            Span::single(eof_pos),
+            String::new(),
+        )))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -643,6 +776,29 @@ mod tests {
         assert!(matches!(tokens[3].token_type, TokenType::Eof));
     }
 
+    #[test]
+    fn test_char_and_byte_offsets() {
+        let query = "café AND 日本 OR 🍎";
+        let tokens = Lexer::new(query).tokenize().unwrap();
+
+        let offsets: Vec<_> = tokens
+            .iter()
+            .map(|t| (t.span.start.char_offset, t.span.start.byte_offset))
+            .collect();
+        assert_eq!(
+            offsets,
+            vec![(0, 0), (5, 6), (9, 10), (12, 17), (15, 20), (16, 24)]
+        );
+
+        // byte offsets slice the original text
+        let nihon = &tokens[2].span;
+        assert_eq!(
+            &query[nihon.start.byte_offset..nihon.end.byte_offset],
+            "日本"
+        );
+        assert_eq!(nihon.end.char_offset - nihon.start.char_offset, 2);
+    }
+
     #[test]
     fn test_quoted_string() {
         let mut lexer = Lexer::new("\"apple juice\" \" phrase with spaces \"");
@@ -657,6 +813,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unterminated_quote_points_at_opener() {
+        let err = Lexer::new("apple AND\n\"orange juice\nOR banana")
+            .tokenize()
+            .unwrap_err();
+        assert_eq!(err.span().start.line, 2);
+        assert_eq!(err.span().start.column, 1);
+        assert_eq!(
+            err.to_string(),
+            "Unterminated quoted string opened at line 2, column 1; a closing quote may be missing at the end of line 2"
+        );
+
+        // the missing closer on line 1 pairs the next opener with the wrong quote
+        let err = Lexer::new("title:\"apple pie\nOR \"banana split\"")
+            .tokenize()
+            .unwrap_err();
+        assert_eq!((err.span().start.line, err.span().start.column), (1, 7));
+        assert!(err.to_string().ends_with("missing at the end of line 1"));
+
+        let err = Lexer::new("apple \"pie").tokenize().unwrap_err();
+        assert!(err.to_string().ends_with("missing at the end of the query"));
+    }
+
+    #[test]
+    fn test_escaped_quoted_string() {
+        let mut lexer = Lexer::new(r#""say \"hi\"" AND "back\\slash" AND "C:\path""#);
+        let tokens = lexer.tokenize().unwrap();
+
+        assert!(
+            matches!(tokens[0].token_type, TokenType::QuotedString(ref s) if s == r#"say "hi""#)
+        );
+        assert_eq!(tokens[0].raw, r#""say \"hi\"""#);
+        assert_eq!(tokens[0].span.end.column, 13);
+        assert_eq!(tokens[1].span.start.column, 14);
+
+        assert!(
+            matches!(tokens[2].token_type, TokenType::QuotedString(ref s) if s == r"back\slash")
+        );
+        // a backslash before any other character is kept as-is
+        assert!(matches!(tokens[4].token_type, TokenType::QuotedString(ref s) if s == r"C:\path"));
+
+        let mut lexer = Lexer::new(r#""unterminated \""#);
+        assert!(lexer.tokenize().is_err());
+    }
+
     #[test]
     fn test_proximity_operators() {
         let mut lexer = Lexer::new("NEAR/5 NEAR/3f");
@@ -667,6 +868,57 @@ mod tests {
         assert!(matches!(tokens[1].token_type, TokenType::NearForward(3)));
     }
 
+    #[test]
+    fn test_tokens_iterator_matches_tokenize() {
+        let input = "apple AND <<<note>>> title:\"juice\" NEAR/3 {Brand}";
+        let streamed: Vec<_> = Lexer::new(input)
+            .tokens()
+            .collect::<LintResult<_>>()
+            .unwrap();
+        assert_eq!(streamed, Lexer::new(input).tokenize().unwrap());
+
+        let mut lexer = Lexer::new("apple \"juice");
+        let mut tokens = lexer.tokens();
+        assert!(matches!(tokens.next(), Some(Ok(_))));
+        assert!(matches!(tokens.next(), Some(Err(_))));
+        assert!(tokens.next().is_none());
+    }
+
+    #[test]
+    fn test_tokenize_full_keeps_whitespace_and_comments() {
+        let query = "apple <<<note>>> AND\njuice";
+
+        let tokens = Lexer::new(query).tokenize().unwrap();
+        assert_eq!(tokens.len(), 6); // apple <<< >>> AND juice EOF
+
+        let tokens = Lexer::new(query).tokenize_full().unwrap();
+        let types: Vec<_> = tokens.iter().map(|t| t.token_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                TokenType::Word("apple".to_string()),
+                TokenType::Whitespace,
+                TokenType::CommentStart,
+                TokenType::Comment("note".to_string()),
+                TokenType::CommentEnd,
+                TokenType::Whitespace,
+                TokenType::And,
+                TokenType::Whitespace,
+                TokenType::Word("juice".to_string()),
+                TokenType::Eof,
+            ]
+        );
+
+        // concatenated raw text reproduces the input
+        let raw: String = tokens.iter().map(|t| t.raw.as_str()).collect();
+        assert_eq!(raw, query);
+
+        assert_eq!(tokens[3].span.start.char_offset, 9);
+        assert_eq!(tokens[3].span.end.char_offset, 13);
+        assert_eq!(tokens[8].span.start.line, 2);
+        assert_eq!(tokens[8].span.start.column, 1);
+    }
+
     #[test]
     fn test_numbers_vs_words_and_special_chars() {
         let mut lexer = Lexer::new(
@@ -743,6 +995,115 @@ mod tests {
         assert!(matches!(tokens[1].token_type, TokenType::Eof));
     }
 
+    #[test]
+    fn test_line_comments() {
+        let tokens = Lexer::new("apple // note\nAND juice")
+            .with_line_comments(true)
+            .tokenize()
+            .unwrap();
+        assert_eq!(tokens.len(), 4);
+        assert!(matches!(tokens[1].token_type, TokenType::And));
+        assert_eq!(tokens[1].span.start.line, 2);
+        assert_eq!(tokens[1].span.start.column, 1);
+
+        let tokens = Lexer::new("apple // note\nAND juice")
+            .with_line_comments(true)
+            .tokenize_full()
+            .unwrap();
+        assert!(
+            tokens
+                .iter()
+                .any(|t| t.token_type == TokenType::Comment("note".to_string())
+                    && t.raw == "// note"
+                    && t.span.end.column == 14)
+        );
+
+        // off by default, and never inside a word
+        let tokens = Lexer::new("url:http://example.com")
+            .with_line_comments(true)
+            .tokenize()
+            .unwrap();
+        assert!(matches!(&tokens[4].token_type, TokenType::Word(w) if w == "//example.com"));
+        let tokens = Lexer::new("apple // note").tokenize().unwrap();
+        assert!(matches!(&tokens[1].token_type, TokenType::Word(w) if w == "//"));
+    }
+
+    #[test]
+    fn test_comparison_shorthand() {
+        let err = Lexer::new("authorFollowers:>1000").tokenize().unwrap_err();
+        assert_eq!(err.code(), "E032");
+        assert_eq!(
+            err.to_string(),
+            "Comparison operators are not supported, use a range like [1000 TO *] instead of >/<"
+        );
+        assert_eq!(err.span().start.column, 17);
+        assert_eq!(err.span().end.column, 22);
+
+        let err = Lexer::new("authorFollowers:<=500").tokenize().unwrap_err();
+        assert_eq!(err.code(), "E032");
+        assert!(err.to_string().contains("[* TO 500]"));
+
+        for query in [
+            "authorFollowers:>abc",
+            "authorFollowers:>",
+            "authorFollowers:<.",
+        ] {
+            let err = Lexer::new(query).tokenize().unwrap_err();
+            assert_eq!(err.code(), "E032");
+            assert_eq!(
+                err.to_string(),
+                "Comparison operators are not supported, use a [from TO to] range instead of >/<",
+                "{query}"
+            );
+        }
+
+        // not after a field, still just an unexpected character
+        let err = Lexer::new("apple >1000").tokenize().unwrap_err();
+        assert_eq!(err.code(), "E001");
+    }
+
+    #[test]
+    fn test_multiline_positions() {
+        let query = "title:\"a\nb\" AND\n  <<<é\n>>> (x~2 OR #tag)\n@me";
+        let tokens = Lexer::new(query).tokenize_full().unwrap();
+
+        let starts: Vec<_> = tokens
+            .iter()
+            .filter(|t| !matches!(t.token_type, TokenType::Whitespace))
+            .map(|t| (t.raw.as_str(), t.span.start.line, t.span.start.column))
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                ("title", 1, 1),
+                (":", 1, 6),
+                ("\"a\nb\"", 1, 7),
+                ("AND", 2, 4),
+                ("<<<", 3, 3),
+                ("é\n", 3, 6),
+                (">>>", 4, 1),
+                ("(", 4, 5),
+                ("x", 4, 6),
+                ("~", 4, 7),
+                ("2", 4, 8),
+                ("OR", 4, 10),
+                ("#tag", 4, 13),
+                (")", 4, 17),
+                ("@me", 5, 1),
+                ("", 5, 4),
+            ]
+        );
+
+        // every span's byte offsets still slice back to the raw text
+        for token in &tokens {
+            let span = &token.span;
+            assert_eq!(
+                &query[span.start.byte_offset..span.end.byte_offset],
+                token.raw
+            );
+        }
+    }
+
     #[test]
     fn test_colon_in_terms() {
         let mut lexer = Lexer::new("test:test");
@@ -1,67 +1,340 @@
 pub mod ast;
+pub mod config;
 pub mod error;
+pub mod fix;
+pub mod format;
 pub mod lexer;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 pub mod parser;
+pub mod registry;
+pub mod suppression;
 pub mod validation;
 pub mod validator;
 
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
 use ast::Query;
-use error::{LintError, LintReport, LintResult};
+use config::{LinterConfig, RuleSeverity};
+use error::{LintError, LintReport, LintResult, LintWarning, Position, Span};
+use fix::Fix;
 use lexer::Lexer;
-use parser::Parser;
+use parser::{IMPLICIT_AND_RULE, ParseResult, Parser};
+use suppression::Suppression;
 use validator::Validator;
 
+/// cargo features this build of bwq_linter was compiled with, e.g. `["lsp"]`
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "lsp") {
+        features.push("lsp");
+    }
+    features
+}
+
+/// Brandwatch's limit on the length of a single query, in characters
+pub const DEFAULT_MAX_QUERY_LENGTH: usize = 4096;
+
+/// widest NEAR/x distance accepted without a W041 warning
+pub const DEFAULT_MAX_NEAR_DISTANCE: u32 = 100;
+
 pub struct BrandwatchLinter {
     validator: Validator,
+    coalesce_implicit_and: bool,
+    max_errors: usize,
+    time_limit: Option<Duration>,
+    line_comments: bool,
+    max_query_length: usize,
+    preview: bool,
+    extend_select: Vec<String>,
+    select: Vec<String>,
+    severities: BTreeMap<String, RuleSeverity>,
+    recover: bool,
+    expensive_rules: bool,
 }
 
 impl BrandwatchLinter {
     pub fn new() -> Self {
         Self {
             validator: Validator::new(),
+            coalesce_implicit_and: false,
+            max_errors: parser::DEFAULT_MAX_ERRORS,
+            time_limit: None,
+            line_comments: false,
+            max_query_length: DEFAULT_MAX_QUERY_LENGTH,
+            preview: false,
+            extend_select: Vec::new(),
+            select: Vec::new(),
+            severities: BTreeMap::new(),
+            recover: true,
+            expensive_rules: true,
+        }
+    }
+
+    /// apply project settings loaded from `bwq.toml`
+    pub fn with_config(mut self, config: &LinterConfig) -> Self {
+        self.validator.require_fields(config.required_fields());
+        self.line_comments = config.line_comments;
+        if let Some(max_query_length) = config.max_query_length {
+            self.max_query_length = max_query_length;
+        }
+        if let Some(max_near_distance) = config.max_near_distance {
+            self.validator.set_max_near_distance(max_near_distance);
         }
+        self.preview = config.preview;
+        self.extend_select = config.extend_select.clone();
+        self.recover = !config.no_recover;
+        self.select = config.select.clone();
+        self.set_rule_severities(config.rules.clone());
+        self
     }
 
+    /// accept `// comment` lines in addition to `<<< >>>`
+    pub fn with_line_comments(mut self, enabled: bool) -> Self {
+        self.line_comments = enabled;
+        self
+    }
+
+    /// report each run of implicitly ANDed terms as a single W001 warning
+    pub fn with_coalesced_implicit_and(mut self, coalesce: bool) -> Self {
+        self.coalesce_implicit_and = coalesce;
+        self
+    }
+
+    /// cap on errors collected during parser recovery (default 100)
+    pub fn with_max_errors(mut self, max_errors: usize) -> Self {
+        self.max_errors = max_errors;
+        self
+    }
+
+    /// with `false`, stop at the first parse error (see [`Parser::with_recovery`])
+    pub fn with_recovery(mut self, recover: bool) -> Self {
+        self.recover = recover;
+        self
+    }
+
+    /// longest query accepted, in characters of the trimmed text (default 4096)
+    pub fn with_max_query_length(mut self, max_query_length: usize) -> Self {
+        self.max_query_length = max_query_length;
+        self
+    }
+
+    /// widest NEAR/x or NEAR/xf distance before W041 (default 100)
+    pub fn with_max_near_distance(mut self, max_near_distance: u32) -> Self {
+        self.validator.set_max_near_distance(max_near_distance);
+        self
+    }
+
+    /// run the whole-query rules (on by default); see [`Validator::set_expensive_rules`]
+    pub fn with_expensive_rules(mut self, enabled: bool) -> Self {
+        self.validator.set_expensive_rules(enabled);
+        self.expensive_rules = enabled;
+        self
+    }
+
+    /// report every preview rule (see [`registry::Stability`])
+    pub fn with_preview(mut self, preview: bool) -> Self {
+        self.preview = preview;
+        self
+    }
+
+    /// report these preview codes (e.g. `W034`) without enabling every preview rule
+    pub fn with_extend_select(mut self, codes: Vec<String>) -> Self {
+        self.extend_select = codes;
+        self
+    }
+
+    /// only report these codes or names, e.g. `E012` or `potential-typo` (empty reports all)
+    pub fn with_select(mut self, codes: Vec<String>) -> Self {
+        self.select = codes;
+        self
+    }
+
+    /// per-rule overrides, keyed by validation rule name, diagnostic name or code: `Off` drops
+    /// a rule's diagnostics (and skips running it), the others move them to that severity
+    pub fn with_rule_severities(mut self, severities: BTreeMap<String, RuleSeverity>) -> Self {
+        self.set_rule_severities(severities);
+        self
+    }
+
+    fn set_rule_severities(&mut self, severities: BTreeMap<String, RuleSeverity>) {
+        self.validator.set_rule_severities(severities.clone());
+        self.severities = severities;
+    }
+
+    /// wall-clock budget for validating one query; past it, linting fails with E033
+    pub fn with_time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit = Some(limit);
+        self
+    }
+
+    /// Lint a query, failing on the first unrecoverable lexer/parser error.
+    ///
+    /// Empty or whitespace-only input is a parse error (E007); use [`Self::analyze`]
+    /// when empty documents should be accepted.
     pub fn lint(&mut self, query: &str) -> LintResult<LintReport> {
-        let mut lexer = Lexer::new(query);
-        let tokens = lexer.tokenize()?;
+        self.lint_for_server(query).map(|(report, _)| report)
+    }
 
-        let mut parser = Parser::new(tokens)?;
-        let parse_result = parser.parse()?;
+    /// parse `query` into its AST without running any validation rules
+    pub fn parse(&mut self, query: &str) -> LintResult<Query> {
+        let parse_result = self.parse_query(query)?;
+        match parse_result.errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(parse_result.query),
+        }
+    }
 
-        let mut report = self.validator.validate(&parse_result.query);
-        report.warnings.extend(parse_result.warnings);
+    fn parse_query(&self, query: &str) -> LintResult<ParseResult> {
+        let mut lexer = Lexer::new(query).with_line_comments(self.line_comments);
+        let tokens = lexer.tokenize_full()?;
 
-        Ok(report)
+        Parser::new(tokens)?
+            .with_coalesced_implicit_and(self.coalesce_implicit_and)
+            .with_max_errors(self.max_errors)
+            .with_recovery(self.recover)
+            .parse()
     }
 
     pub fn lint_for_server(&mut self, query: &str) -> LintResult<(LintReport, Query)> {
-        let mut lexer = Lexer::new(query);
-        let tokens = lexer.tokenize()?;
+        self.lint_with_suppressions(query)
+            .map(|(report, query, _)| (report, query))
+    }
+
+    /// [`Self::lint_for_server`], plus the `bwq:ignore` comments already applied to the report
+    fn lint_with_suppressions(
+        &mut self,
+        query: &str,
+    ) -> LintResult<(LintReport, Query, Vec<Suppression>)> {
+        let deadline = self.time_limit.map(|limit| Instant::now() + limit);
+        let parse_result = self.parse_query(query)?;
+
+        let mut report = self
+            .validator
+            .validate_until(&parse_result.query, deadline)?;
+        report.errors.extend(parse_result.errors);
+        // the parser's W001 is always the implicit AND, which `implicit-and` controls on its own
+        for warning in parse_result.warnings {
+            let implicit_and = matches!(warning, LintWarning::PotentialTypo { .. });
+            match self.implicit_and_severity().filter(|_| implicit_and) {
+                Some(RuleSeverity::Off) => {}
+                Some(RuleSeverity::Error) => report.errors.push(warning.into_error()),
+                _ => report.warnings.push(warning),
+            }
+        }
+        if let Some(error) = self.check_length(query) {
+            report.errors.push(error);
+        }
+        // without the whole-query rules, a suppression of one of their codes only looks unused
+        suppression::apply(
+            &mut report,
+            &parse_result.suppressions,
+            self.expensive_rules,
+        );
+        report.errors.retain(|error| self.is_selected(error.code()));
+        report
+            .warnings
+            .retain(|warning| self.is_selected(warning.code()));
+        self.apply_severities(&mut report);
+
+        Ok((report, parse_result.query, parse_result.suppressions))
+    }
+
+    /// stable codes run unless `select` leaves them out; preview ones need `preview`, or an
+    /// `extend_select` or `select` entry
+    fn is_selected(&self, code: &str) -> bool {
+        let listed = |keys: &[String]| {
+            keys.iter()
+                .any(|key| registry::resolve(key).is_some_and(|info| info.code == code))
+        };
+        if !self.select.is_empty() && !listed(&self.select) {
+            return false;
+        }
+        self.preview
+            || !registry::is_preview(code)
+            || listed(&self.extend_select)
+            || listed(&self.select)
+    }
+
+    /// the `[rules]` override for `code`, by code or diagnostic name (the code wins)
+    fn code_severity(&self, code: &str) -> Option<RuleSeverity> {
+        let info = registry::lookup(code)?;
+        self.severities
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(code))
+            .or_else(|| self.severities.iter().find(|(key, _)| *key == info.name))
+            .map(|(_, severity)| *severity)
+    }
+
+    /// the `[rules]` override for the parser's implicit-AND warning
+    fn implicit_and_severity(&self) -> Option<RuleSeverity> {
+        self.severities.get(IMPLICIT_AND_RULE).copied()
+    }
+
+    /// drop or move diagnostics whose code or name has a `[rules]` override
+    fn apply_severities(&self, report: &mut LintReport) {
+        if self.severities.is_empty() {
+            return;
+        }
 
-        let mut parser = Parser::new(tokens)?;
-        let parse_result = parser.parse()?;
+        let errors = std::mem::take(&mut report.errors);
+        let warnings = std::mem::take(&mut report.warnings);
+        for error in errors {
+            match self.code_severity(error.code()) {
+                Some(RuleSeverity::Off) => {}
+                Some(RuleSeverity::Warning) => report.warnings.push(error.into_warning()),
+                _ => report.errors.push(error),
+            }
+        }
+        for warning in warnings {
+            match self.code_severity(warning.code()) {
+                Some(RuleSeverity::Off) => {}
+                Some(RuleSeverity::Error) => report.errors.push(warning.into_error()),
+                _ => report.warnings.push(warning),
+            }
+        }
+    }
 
-        let mut report = self.validator.validate(&parse_result.query);
-        report.warnings.extend(parse_result.warnings);
+    /// E034 spanning the characters past the limit, if the trimmed query is too long
+    fn check_length(&self, query: &str) -> Option<LintError> {
+        let trimmed = query.trim();
+        if trimmed.chars().count() <= self.max_query_length {
+            return None;
+        }
 
-        Ok((report, parse_result.query))
+        let first_over =
+            query.chars().count() - query.trim_start().chars().count() + self.max_query_length;
+        let mut position = Position::new(1, 1, 0, 0);
+        let mut start = position.clone();
+        for (index, ch) in query.trim_end().chars().enumerate() {
+            if index == first_over {
+                start = position.clone();
+            }
+            position.advance(ch);
+        }
+
+        Some(LintError::QueryTooLong {
+            span: Span::new(start, position),
+            message: format!(
+                "Query exceeds maximum length of {} characters",
+                self.max_query_length
+            ),
+        })
     }
 
+    /// Analyze a query, collecting any lint error into the result.
+    ///
+    /// Empty or whitespace-only input is valid with no diagnostics
+    /// (see [`AnalysisResult::is_empty_query`]).
     pub fn analyze(&mut self, query: &str) -> AnalysisResult {
-        match self.lint(query) {
-            Ok(report) => AnalysisResult {
-                is_valid: !report.has_errors(),
-                errors: report.errors,
-                warnings: report.warnings,
-                query: query.to_string(),
-            },
-            Err(error) => AnalysisResult {
-                is_valid: false,
-                errors: vec![error],
-                warnings: vec![],
-                query: query.to_string(),
-            },
+        let analysis = self.analyze_for_server(query);
+        AnalysisResult {
+            is_valid: analysis.is_valid,
+            errors: analysis.errors,
+            warnings: analysis.warnings,
+            fixes: analysis.fixes,
+            query: analysis.query,
         }
     }
 
@@ -71,23 +344,40 @@ impl BrandwatchLinter {
                 is_valid: true,
                 errors: Vec::new(),
                 warnings: Vec::new(),
+                fixes: Vec::new(),
                 query: query.to_string(),
                 ast: None,
             };
         }
 
-        match self.lint_for_server(query) {
-            Ok((report, ast)) => AnalysisResultWithAst {
-                is_valid: !report.has_errors(),
-                errors: report.errors,
-                warnings: report.warnings,
-                query: query.to_string(),
-                ast: Some(ast),
-            },
+        match self.lint_with_suppressions(query) {
+            Ok((report, ast, suppressions)) => {
+                let mut fixes = fix::collect(&ast, query);
+                // only fix what is reported: `[rules]`, `--ignore` and `bwq:ignore` comments can
+                // all hide a diagnostic
+                fixes.retain(|fix| {
+                    self.is_selected(&fix.code)
+                        && self.code_severity(&fix.code) != Some(RuleSeverity::Off)
+                        && !(fix.is_implicit_and()
+                            && self.implicit_and_severity() == Some(RuleSeverity::Off))
+                        && !suppressions
+                            .iter()
+                            .any(|suppression| suppression.covers(&fix.code, &fix.span))
+                });
+                AnalysisResultWithAst {
+                    is_valid: !report.has_errors(),
+                    errors: report.errors,
+                    warnings: report.warnings,
+                    fixes,
+                    query: query.to_string(),
+                    ast: Some(ast),
+                }
+            }
             Err(error) => AnalysisResultWithAst {
                 is_valid: false,
                 errors: vec![error],
                 warnings: vec![],
+                fixes: vec![],
                 query: query.to_string(),
                 ast: None,
             },
@@ -106,6 +396,8 @@ pub struct AnalysisResult {
     pub is_valid: bool,
     pub errors: Vec<LintError>,
     pub warnings: Vec<error::LintWarning>,
+    /// autofixes for the diagnostics above, see [`Self::apply_fixes`]
+    pub fixes: Vec<Fix>,
     pub query: String,
 }
 
@@ -114,10 +406,23 @@ pub struct AnalysisResultWithAst {
     pub is_valid: bool,
     pub errors: Vec<LintError>,
     pub warnings: Vec<error::LintWarning>,
+    pub fixes: Vec<Fix>,
     pub query: String,
     pub ast: Option<Query>,
 }
 
+impl AnalysisResult {
+    /// whether the analyzed query was empty or whitespace-only
+    pub fn is_empty_query(&self) -> bool {
+        self.query.trim().is_empty()
+    }
+
+    /// `source` (the analyzed query) with every autofix applied
+    pub fn apply_fixes(&self, source: &str) -> String {
+        fix::apply(source, &self.fixes)
+    }
+}
+
 pub fn analyze_query(query: &str) -> AnalysisResult {
     let mut linter = BrandwatchLinter::new();
     linter.analyze(query)
@@ -141,6 +446,36 @@ mod tests {
         assert!(report.has_errors());
     }
 
+    #[test]
+    fn test_warning_display_is_a_sentence() {
+        let mut linter = BrandwatchLinter::new();
+        let report = linter.lint("apple juice").unwrap();
+        let warning = report.warnings[0].to_string();
+        assert_eq!(
+            warning,
+            "Potential typo: Two or more terms without an operator between them are implicitly ANDed. Consider using explicit 'AND' operator for clarity"
+        );
+        assert!(!warning.contains("span") && !warning.contains('{'));
+    }
+
+    #[test]
+    fn test_empty_query_handling() {
+        let mut linter = BrandwatchLinter::new();
+
+        for query in ["", "   ", "\n\t"] {
+            let analysis = linter.analyze(query);
+            assert!(analysis.is_valid);
+            assert!(analysis.is_empty_query());
+            assert!(analysis.errors.is_empty());
+            assert!(analysis.warnings.is_empty());
+
+            let error = linter.lint(query).unwrap_err();
+            assert_eq!(error.code(), "E007");
+        }
+
+        assert!(!linter.analyze("apple").is_empty_query());
+    }
+
     #[test]
     fn test_complex_query() {
         let query = r#"(apple OR orange) AND "fruit juice" NOT bitter"#;
@@ -169,4 +504,282 @@ mod tests {
         let report2 = linter.lint(query2).unwrap();
         assert!(!report2.has_errors());
     }
+
+    #[test]
+    fn test_recovered_query_has_no_spurious_diagnostics() {
+        let mut linter = BrandwatchLinter::new();
+        for query in [
+            "(apple AND) OR banana",
+            "(NOT apple AND) OR banana",
+            "title:(apple OR)",
+            "(apple NEAR/3 juice AND) OR banana",
+        ] {
+            let report = linter.lint(query).unwrap();
+            let codes: Vec<_> = report.errors.iter().map(|e| e.code()).collect();
+            assert_eq!(codes, vec!["E002"], "{query}");
+        }
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let mut linter = BrandwatchLinter::new().with_time_limit(Duration::ZERO);
+        let err = linter.lint("apple AND juice").unwrap_err();
+        assert_eq!(err.code(), "E033");
+
+        let mut linter = BrandwatchLinter::new().with_time_limit(Duration::from_secs(60));
+        assert!(linter.lint("apple AND juice").unwrap().is_clean());
+    }
+
+    #[test]
+    fn test_line_comments() {
+        let query = "apple // note\nAND juice";
+        let mut linter = BrandwatchLinter::new().with_line_comments(true);
+        assert!(linter.lint(query).unwrap().is_clean());
+
+        let config = LinterConfig::from_toml_str("line_comments = true").unwrap();
+        let mut linter = BrandwatchLinter::new().with_config(&config);
+        assert!(linter.lint(query).unwrap().is_clean());
+    }
+
+    #[test]
+    fn test_enum_field_suggestions() {
+        let mut linter = BrandwatchLinter::new();
+        let report = linter.lint("engagementType:RETWET").unwrap();
+        assert_eq!(
+            report.errors[0].to_string(),
+            "engagementType must be 'COMMENT', 'REPLY', 'RETWEET', or 'QUOTE'; did you mean 'RETWEET'?"
+        );
+
+        let report = linter.lint("authorVerifiedType:bussiness").unwrap();
+        assert!(
+            report.errors[0]
+                .to_string()
+                .ends_with("did you mean 'business'?")
+        );
+
+        let report = linter.lint("engagementType:LIKE").unwrap();
+        assert!(!report.errors[0].to_string().contains("did you mean"));
+    }
+
+    #[test]
+    fn test_grouped_field_values() {
+        let report = BrandwatchLinter::new()
+            .lint("country:(gbr OR xxx)")
+            .unwrap();
+
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].to_string().contains("'xxx'"));
+        assert_eq!(report.warnings[0].span().start.column, 17);
+
+        // rejected once for the whole group, not again for each value
+        let report = BrandwatchLinter::new()
+            .lint("minuteOfDay:(720 OR 780)")
+            .unwrap();
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].span().start.column, 1);
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_apply_fixes() {
+        let query = "language:ENG apple banana";
+        let analysis = BrandwatchLinter::new().analyze(query);
+        assert_eq!(
+            analysis.apply_fixes(query),
+            "language:eng AND apple AND banana"
+        );
+
+        let clean = "language:en AND apple";
+        assert_eq!(
+            BrandwatchLinter::new().analyze(clean).apply_fixes(clean),
+            clean
+        );
+    }
+
+    #[test]
+    fn test_parse_returns_serializable_ast() {
+        let mut linter = BrandwatchLinter::new();
+        let query = linter.parse("title:apple NEAR/3 juice").unwrap();
+        let json = serde_json::to_value(&query).unwrap();
+        assert_eq!(json["expression"]["type"], "Proximity");
+        assert_eq!(json["expression"]["operator"]["type"], "Near");
+        assert_eq!(json["expression"]["terms"][0]["field"], "title");
+        assert_eq!(
+            json["expression"]["span"]["end"],
+            serde_json::json!({ "line": 1, "column": 25, "offset": 24 })
+        );
+
+        // validation doesn't run, but parse errors still do
+        assert!(linter.parse("rating:9").is_ok());
+        assert!(linter.parse("apple AND (juice").is_err());
+    }
+
+    #[test]
+    fn test_rule_severities() {
+        let severities = |entries: &[(&str, RuleSeverity)]| {
+            entries
+                .iter()
+                .map(|(key, severity)| (key.to_string(), *severity))
+                .collect()
+        };
+
+        let report = BrandwatchLinter::new()
+            .with_rule_severities(severities(&[("rating-field", RuleSeverity::Off)]))
+            .lint("apple AND rating:6")
+            .unwrap();
+        assert!(report.is_clean());
+
+        let report = BrandwatchLinter::new()
+            .with_rule_severities(severities(&[
+                ("potential-typo", RuleSeverity::Error),
+                ("E012", RuleSeverity::Warning),
+            ]))
+            .lint("apple juice OR soda")
+            .unwrap();
+        assert_eq!(
+            report
+                .errors
+                .iter()
+                .map(LintError::code)
+                .collect::<Vec<_>>(),
+            ["W001"]
+        );
+        assert_eq!(
+            report
+                .warnings
+                .iter()
+                .map(error::LintWarning::code)
+                .collect::<Vec<_>>(),
+            ["E012"]
+        );
+
+        // `implicit-and` leaves the other W001 checks alone
+        let report = BrandwatchLinter::new()
+            .with_rule_severities(severities(&[("implicit-and", RuleSeverity::Off)]))
+            .lint("apple juice AND language:EN")
+            .unwrap();
+        assert_eq!(
+            report
+                .warnings
+                .iter()
+                .map(|warning| warning.span().start.char_offset)
+                .collect::<Vec<_>>(),
+            [16]
+        );
+        let report = BrandwatchLinter::new()
+            .with_rule_severities(severities(&[("implicit-and", RuleSeverity::Error)]))
+            .lint("apple juice")
+            .unwrap();
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.warnings.is_empty());
+
+        let report = BrandwatchLinter::new()
+            .with_select(vec!["operator-mixing".to_string()])
+            .lint("apple juice OR soda")
+            .unwrap();
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_suppression_comments() {
+        let codes = |query: &str| {
+            let report = BrandwatchLinter::new().lint(query).unwrap();
+            let mut codes: Vec<_> = report
+                .errors
+                .iter()
+                .map(LintError::code)
+                .chain(report.warnings.iter().map(error::LintWarning::code))
+                .collect();
+            codes.sort_unstable();
+            codes
+        };
+
+        assert_eq!(
+            codes("apple juice <<<bwq:ignore W001>>>"),
+            Vec::<&str>::new()
+        );
+        assert_eq!(
+            codes("<<<bwq:ignore-next-line E009>>>\nrating:6\nAND rating:7"),
+            ["E009"]
+        );
+        assert_eq!(
+            codes("apple juice AND rating:6 <<<bwq:ignore>>>"),
+            Vec::<&str>::new()
+        );
+        // a different code on the line isn't silenced, and the stale suppression is reported
+        assert_eq!(codes("apple juice <<<bwq:ignore E009>>>"), ["W001", "W040"]);
+        assert_eq!(
+            codes("apple AND juice\n<<<bwq:ignore-next-line>>>"),
+            ["W040"]
+        );
+    }
+
+    #[test]
+    fn test_max_query_length() {
+        let mut linter = BrandwatchLinter::new().with_max_query_length(15);
+        assert!(linter.lint("  apple AND juice \n").unwrap().is_clean());
+
+        let report = linter.lint(" apple AND juices").unwrap();
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].code(), "E034");
+        assert_eq!(
+            report.errors[0].to_string(),
+            "Query exceeds maximum length of 15 characters"
+        );
+        let span = report.errors[0].span();
+        assert_eq!((span.start.char_offset, span.end.char_offset), (16, 17));
+
+        let config = LinterConfig::from_toml_str("max_query_length = 5").unwrap();
+        let mut linter = BrandwatchLinter::new().with_config(&config);
+        assert_eq!(linter.lint("apple").unwrap().errors.len(), 0);
+        assert_eq!(linter.lint("apples").unwrap().errors[0].code(), "E034");
+    }
+
+    #[test]
+    fn test_fields_only_query() {
+        let mut linter = BrandwatchLinter::new().with_extend_select(vec!["W042".to_string()]);
+        let report = linter
+            .lint("language:en AND authorVerified:true AND rating:[3 TO 5]")
+            .unwrap();
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].code(), "W042");
+
+        assert!(
+            linter
+                .lint("apple AND language:en AND authorVerified:true")
+                .unwrap()
+                .is_clean()
+        );
+        // preview: off unless selected
+        assert!(
+            BrandwatchLinter::new()
+                .lint("language:en")
+                .unwrap()
+                .is_clean()
+        );
+    }
+
+    #[test]
+    fn test_max_near_distance() {
+        let mut linter = BrandwatchLinter::new();
+        assert!(linter.lint("apple NEAR/100 juice").unwrap().is_clean());
+
+        let report = linter.lint("apple NEAR/101f juice").unwrap();
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].code(), "W041");
+        let span = report.warnings[0].span();
+        assert_eq!((span.start.char_offset, span.end.char_offset), (6, 15));
+
+        let config = LinterConfig::from_toml_str("max_near_distance = 200").unwrap();
+        let mut linter = BrandwatchLinter::new().with_config(&config);
+        assert!(linter.lint("apple NEAR/150 juice").unwrap().is_clean());
+        assert!(
+            BrandwatchLinter::new()
+                .with_max_near_distance(5)
+                .lint("apple NEAR/6 juice")
+                .unwrap()
+                .has_warnings()
+        );
+    }
 }
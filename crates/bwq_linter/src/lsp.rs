@@ -0,0 +1,123 @@
+//! Conversions to Language Server Protocol types (behind the `lsp` feature)
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position as LspPosition, Range};
+
+use crate::error::{LintError, LintReport, LintWarning, Position, Span};
+
+/// 1-based line/column to the 0-based LSP position
+pub fn position_to_lsp(pos: &Position) -> LspPosition {
+    LspPosition {
+        line: pos.line.saturating_sub(1) as u32,
+        character: pos.column.saturating_sub(1) as u32,
+    }
+}
+
+pub fn span_to_range(span: &Span) -> Range {
+    Range {
+        start: position_to_lsp(&span.start),
+        end: position_to_lsp(&span.end),
+    }
+}
+
+fn diagnostic(
+    span: &Span,
+    severity: DiagnosticSeverity,
+    code: &str,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        range: span_to_range(span),
+        severity: Some(severity),
+        code: Some(NumberOrString::String(code.to_string())),
+        code_description: None,
+        source: Some("bwq".to_string()),
+        message,
+        related_information: None,
+        tags: None,
+        data: None,
+    }
+}
+
+impl LintError {
+    pub fn to_lsp_diagnostic(&self) -> Diagnostic {
+        diagnostic(
+            self.span(),
+            DiagnosticSeverity::ERROR,
+            self.code(),
+            self.to_string(),
+        )
+    }
+}
+
+impl LintWarning {
+    pub fn to_lsp_diagnostic(&self) -> Diagnostic {
+        diagnostic(
+            self.span(),
+            DiagnosticSeverity::WARNING,
+            self.code(),
+            self.to_string(),
+        )
+    }
+}
+
+impl LintReport {
+    /// errors, then warnings, as LSP diagnostics with `source: "bwq"`
+    pub fn to_lsp_diagnostics(&self) -> Vec<Diagnostic> {
+        self.errors
+            .iter()
+            .map(LintError::to_lsp_diagnostic)
+            .chain(self.warnings.iter().map(LintWarning::to_lsp_diagnostic))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BrandwatchLinter;
+
+    #[test]
+    fn test_position_conversion() {
+        let pos = Position::new(1, 1, 0, 0);
+        let lsp_pos = position_to_lsp(&pos);
+        assert_eq!(lsp_pos.line, 0);
+        assert_eq!(lsp_pos.character, 0)
+    }
+
+    #[test]
+    fn test_span_conversion() {
+        let span = Span::new(Position::new(1, 1, 0, 0), Position::new(1, 5, 4, 4));
+        let range = span_to_range(&span);
+        assert_eq!(range.start.line, 0);
+        assert_eq!(range.start.character, 0);
+        assert_eq!(range.end.line, 0);
+        assert_eq!(range.end.character, 4);
+    }
+
+    #[test]
+    fn test_report_to_lsp_diagnostics() {
+        let report = BrandwatchLinter::new()
+            .lint("apple juice AND rating:6")
+            .unwrap();
+        let diagnostics = report.to_lsp_diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+
+        let error = &diagnostics[0];
+        assert_eq!(error.severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(error.code, Some(NumberOrString::String("E009".to_string())));
+        assert_eq!(error.source.as_deref(), Some("bwq"));
+        assert_eq!(error.message, report.errors[0].to_string());
+        assert_eq!(
+            (error.range.start.character, error.range.end.character),
+            (16, 24)
+        );
+
+        let warning = &diagnostics[1];
+        assert_eq!(warning.severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(
+            warning.code,
+            Some(NumberOrString::String("W001".to_string()))
+        );
+        assert_eq!(warning.range.start.line, 0);
+    }
+}
@@ -1,37 +1,79 @@
 use crate::ast::*;
 use crate::error::{LintError, LintResult, LintWarning, Span};
 use crate::lexer::{Token, TokenType};
+use crate::suppression::Suppression;
+
+/// default cap on errors collected while recovering, see [`Parser::with_max_errors`]
+pub const DEFAULT_MAX_ERRORS: usize = 100;
 
 /// result type with parsed query and any parser warnings
 pub struct ParseResult {
     pub query: Query,
     pub warnings: Vec<LintWarning>,
+    /// errors the parser recovered from (the query is still usable)
+    pub errors: Vec<LintError>,
+    /// `<<<bwq:ignore ...>>>` comments, found when the tokens include comment text
+    pub suppressions: Vec<Suppression>,
 }
 
+/// `[rules]` key for the parser's implicit-AND warning (W001), so it can be turned off or
+/// raised without touching the other W001 checks
+pub const IMPLICIT_AND_RULE: &str = "implicit-and";
+
 /// recursive descent parser for queries
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
     implicit_and_spans: Vec<Span>,
+    recovered_errors: Vec<LintError>,
+    /// `title:apple juice`: text field, its first word, and the implicitly ANDed words after it
+    unquoted_field_phrases: Vec<UnquotedFieldPhrase>,
+    /// `author:AND`: field values that are an operator word, as (field:value span, field, word)
+    operator_field_values: Vec<(Span, String, String)>,
+    coalesce_implicit_and: bool,
+    max_errors: usize,
+    recover: bool,
+    suppressions: Vec<Suppression>,
+}
+
+struct UnquotedFieldPhrase {
+    span: Span,
+    field: FieldType,
+    words: Vec<String>,
 }
 
 impl Parser {
+    /// `tokens` from [`Lexer::tokenize`](crate::lexer::Lexer::tokenize), or from
+    /// [`Lexer::tokenize_full`](crate::lexer::Lexer::tokenize_full) to also pick up
+    /// `bwq:ignore` comments
     pub fn new(tokens: Vec<Token>) -> Result<Self, LintError> {
         // filter out all comment-related tokens including content between comment markers
         let mut filtered_tokens: Vec<Token> = Vec::new();
         let mut inside_comment = false;
         let mut comment_start_span: Option<Span> = None;
+        let mut comment_text = String::new();
+        let mut suppressions = Vec::new();
 
         for token in tokens {
             match &token.token_type {
                 TokenType::CommentStart => {
                     inside_comment = true;
                     comment_start_span = Some(token.span.clone());
+                    comment_text.clear();
+                }
+                TokenType::Comment(text) if inside_comment => comment_text = text.clone(),
+                // a `//` line comment
+                TokenType::Comment(text) => {
+                    suppressions.extend(Suppression::parse(text, &token.span));
                 }
                 TokenType::CommentEnd => {
+                    if let Some(start) = comment_start_span.take() {
+                        let span = Span::new(start.start, token.span.end.clone());
+                        suppressions.extend(Suppression::parse(&comment_text, &span));
+                    }
                     inside_comment = false;
-                    comment_start_span = None;
                 }
+                TokenType::Whitespace => {}
                 TokenType::Eof if inside_comment => {
                     return Err(LintError::ParserError {
                         span: comment_start_span.unwrap(),
@@ -49,12 +91,54 @@ impl Parser {
             tokens: filtered_tokens,
             current: 0,
             implicit_and_spans: Vec::new(),
+            recovered_errors: Vec::new(),
+            unquoted_field_phrases: Vec::new(),
+            operator_field_values: Vec::new(),
+            coalesce_implicit_and: false,
+            max_errors: DEFAULT_MAX_ERRORS,
+            recover: true,
+            suppressions,
         })
     }
 
+    /// report a run of implicitly ANDed terms (`a b c d`) as one warning instead of one per gap
+    pub fn with_coalesced_implicit_and(mut self, coalesce: bool) -> Self {
+        self.coalesce_implicit_and = coalesce;
+        self
+    }
+
+    /// stop collecting recovered errors after `max_errors`, ending with a "too many errors" note
+    pub fn with_max_errors(mut self, max_errors: usize) -> Self {
+        self.max_errors = max_errors;
+        self
+    }
+
+    /// with `false`, fail on the first error instead of recording it and parsing on
+    pub fn with_recovery(mut self, recover: bool) -> Self {
+        self.recover = recover;
+        self
+    }
+
     /// parse the tokens into a queryAST
     pub fn parse(&mut self) -> LintResult<ParseResult> {
-        let expression = self.parse_expression()?;
+        let mut expression = self.parse_expression()?;
+        // `apple ] juice`: report the stray closer, skip it and AND on whatever follows
+        while let Some(error) = self.stray_closer_error() {
+            self.record_error(error)?;
+            self.advance();
+            if self.is_at_end() || self.stray_closer_error().is_some() {
+                continue;
+            }
+
+            let rest = self.parse_expression()?;
+            let span = Span::new(expression.span().start.clone(), rest.span().end.clone());
+            expression = Expression::BooleanOp {
+                operator: BooleanOperator::And,
+                left: Box::new(expression),
+                right: Some(Box::new(rest)),
+                span,
+            };
+        }
         let span = expression.span().clone();
 
         // ensure we've consumed all tokens except EOF
@@ -80,10 +164,43 @@ impl Parser {
                 message: "Two or more terms without an operator between them are implicitly ANDed. Consider using explicit 'AND' operator for clarity".to_string(),
             });
         }
+        for phrase in &self.unquoted_field_phrases {
+            let rest = phrase.words[1..].join(" ");
+            let field = phrase.field.as_str();
+            let quoted = phrase.words.join(" ");
+            if phrase.field.is_place_name() {
+                warnings.push(LintWarning::UnquotedLocation {
+                    span: phrase.span.clone(),
+                    message: format!(
+                        "'{rest}' is not part of the {field}: value, so this matches {field}:{first} AND {rest}. Quote place names with spaces: {field}:\"{quoted}\"",
+                        first = phrase.words[0],
+                    ),
+                });
+                continue;
+            }
+            warnings.push(LintWarning::UnquotedFieldPhrase {
+                span: phrase.span.clone(),
+                message: format!(
+                    "only '{first}' is restricted to {field}:, '{rest}' is searched anywhere. Use {field}:\"{quoted}\" to search for the phrase",
+                    first = phrase.words[0],
+                ),
+            });
+        }
+
+        for (span, field, word) in &self.operator_field_values {
+            warnings.push(LintWarning::OperatorFieldValue {
+                span: span.clone(),
+                message: format!(
+                    "'{word}' is the value of {field}: here, not an operator. Quote it ({field}:\"{word}\") if you mean the word, otherwise add the missing value"
+                ),
+            });
+        }
 
         Ok(ParseResult {
             query: Query { expression, span },
             warnings,
+            errors: std::mem::take(&mut self.recovered_errors),
+            suppressions: std::mem::take(&mut self.suppressions),
         })
     }
 
@@ -91,8 +208,11 @@ impl Parser {
         // check for leading operators
         if matches!(
             self.peek().token_type,
-            TokenType::And | TokenType::Or | TokenType::Near(_) | TokenType::NearForward(_)
+            TokenType::Near(_) | TokenType::NearForward(_)
         ) {
+            return Err(Self::near_missing_operand(self.peek()));
+        }
+        if matches!(self.peek().token_type, TokenType::And | TokenType::Or) {
             return Err(LintError::ParserError {
                 span: self.peek().span.clone(),
                 message: format!(
@@ -106,7 +226,11 @@ impl Parser {
 
         while self.match_token(&TokenType::Or) {
             let operator = BooleanOperator::Or;
-            let _operator_span = self.previous().span.clone();
+            let operator_span = self.previous().span.clone();
+            self.skip_repeated_operators(&operator, &operator_span)?;
+            if self.recover_dangling_operator(&operator, operator_span)? {
+                break;
+            }
             let right = self.parse_and_expression()?;
 
             let span = Span::new(left.span().start.clone(), right.span().end.clone());
@@ -124,15 +248,25 @@ impl Parser {
     fn parse_and_expression(&mut self) -> LintResult<Expression> {
         let mut left = self.parse_not_expression()?;
         let mut last_right_span: Option<Span> = None;
+        // index of the warning for the current run of implicit ANDs, when coalescing
+        let mut implicit_run: Option<usize> = None;
+        // index of the W027 phrase the previous implicitly ANDed words were added to
+        let mut field_phrase: Option<usize> = None;
 
         loop {
             if self.match_token(&TokenType::And) {
                 let operator = BooleanOperator::And;
-                let _operator_span = self.previous().span.clone();
+                let operator_span = self.previous().span.clone();
+                self.skip_repeated_operators(&operator, &operator_span)?;
+                if self.recover_dangling_operator(&operator, operator_span)? {
+                    break;
+                }
                 let right = self.parse_not_expression()?;
 
                 let span = Span::new(left.span().start.clone(), right.span().end.clone());
                 last_right_span = Some(right.span().clone());
+                implicit_run = None;
+                field_phrase = None;
                 left = Expression::BooleanOp {
                     operator,
                     left: Box::new(left),
@@ -143,6 +277,12 @@ impl Parser {
                 // warn on implicit AND (space-separated terms)
                 let right = self.parse_not_expression()?;
 
+                field_phrase = self.track_unquoted_field_phrase(
+                    Self::last_operand(&left, last_right_span.is_some()),
+                    &right,
+                    field_phrase,
+                );
+
                 // create warning span: if this is the first implicit AND, use left + right
                 // if there was a previous right operand, use previous_right + current_right
                 let warning_span = if let Some(prev_right_span) = &last_right_span {
@@ -162,7 +302,15 @@ impl Parser {
                     span: full_span,
                 };
 
-                self.implicit_and_spans.push(warning_span);
+                match implicit_run {
+                    Some(index) if self.coalesce_implicit_and => {
+                        self.implicit_and_spans[index].end = warning_span.end;
+                    }
+                    _ => {
+                        implicit_run = Some(self.implicit_and_spans.len());
+                        self.implicit_and_spans.push(warning_span);
+                    }
+                }
             } else {
                 break;
             }
@@ -171,27 +319,161 @@ impl Parser {
         Ok(left)
     }
 
+    /// the operand just before the next AND: `left` itself, or the right side of the
+    /// AND built on the previous iteration
+    fn last_operand(left: &Expression, has_previous_right: bool) -> &Expression {
+        match left {
+            Expression::BooleanOp {
+                right: Some(right), ..
+            } if has_previous_right => right,
+            _ => left,
+        }
+    }
+
+    /// `title:apple juice` parses as `title:apple AND juice`; remember it (and any further
+    /// bare words) for a W027 suggesting `title:"apple juice"` (W038 for `city:new york`)
+    fn track_unquoted_field_phrase(
+        &mut self,
+        previous: &Expression,
+        right: &Expression,
+        current: Option<usize>,
+    ) -> Option<usize> {
+        let Expression::Term {
+            term: Term::Word { value: word },
+            span: word_span,
+        } = right
+        else {
+            return None;
+        };
+
+        if let Some(index) = current {
+            let phrase = &mut self.unquoted_field_phrases[index];
+            phrase.span.end = word_span.end.clone();
+            phrase.words.push(word.clone());
+            return Some(index);
+        }
+
+        match previous {
+            Expression::Field { field, value, span }
+                if field.is_text() || field.is_place_name() =>
+            {
+                let Expression::Term {
+                    term: Term::Word { value: first },
+                    ..
+                } = value.as_ref()
+                else {
+                    return None;
+                };
+                self.unquoted_field_phrases.push(UnquotedFieldPhrase {
+                    span: Span::new(span.start.clone(), word_span.end.clone()),
+                    field: field.clone(),
+                    words: vec![first.clone(), word.clone()],
+                });
+                Some(self.unquoted_field_phrases.len() - 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// `apple AND AND juice`: report each AND/OR straight after `operator` and skip it, so
+    /// the query parses as if it were written once
+    fn skip_repeated_operators(
+        &mut self,
+        operator: &BooleanOperator,
+        operator_span: &Span,
+    ) -> LintResult<()> {
+        while matches!(self.peek().token_type, TokenType::And | TokenType::Or) {
+            let repeated = self.advance().clone();
+            let message = if repeated.token_type.to_string() == operator.as_str() {
+                format!(
+                    "Repeated operator '{0} {0}'; remove one of them",
+                    operator.as_str()
+                )
+            } else {
+                format!(
+                    "'{}' is followed directly by '{}'; remove one of them",
+                    operator.as_str(),
+                    repeated.token_type
+                )
+            };
+            self.record_error(LintError::RepeatedOperator {
+                span: Span::new(operator_span.start.clone(), repeated.span.end.clone()),
+                message,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// the error for a `]` or `}` that nothing opened, if that is the next token
+    fn stray_closer_error(&self) -> Option<LintError> {
+        let span = self.peek().span.clone();
+        match self.peek().token_type {
+            TokenType::RightBracket => Some(LintError::UnmatchedBracket {
+                span,
+                message: "Unmatched closing bracket ']'".to_string(),
+            }),
+            TokenType::RightBrace => Some(LintError::UnmatchedBrace {
+                span,
+                message: "Unmatched closing brace '}'".to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// `(apple AND) OR banana`: report the operator missing its right operand and
+    /// drop it, so parsing can continue with the enclosing clause. No placeholder
+    /// operand is built, so validation only ever sees nodes from the source
+    fn recover_dangling_operator(
+        &mut self,
+        operator: &BooleanOperator,
+        operator_span: Span,
+    ) -> LintResult<bool> {
+        if !matches!(
+            self.peek().token_type,
+            TokenType::RightParen | TokenType::RightBracket | TokenType::RightBrace
+        ) {
+            return Ok(false);
+        }
+
+        self.record_error(LintError::ParserError {
+            span: operator_span,
+            message: format!("'{}' operator requires a term after it", operator.as_str()),
+        })?;
+        Ok(true)
+    }
+
+    /// keep a recovered error unless the cap is hit; the first error over the cap becomes the note.
+    /// Without recovery the error is returned instead
+    fn record_error(&mut self, error: LintError) -> LintResult<()> {
+        if !self.recover {
+            return Err(error);
+        }
+
+        match self.recovered_errors.len().cmp(&self.max_errors) {
+            std::cmp::Ordering::Less => self.recovered_errors.push(error),
+            std::cmp::Ordering::Equal => self.recovered_errors.push(LintError::ParserError {
+                span: error.span().clone(),
+                message: format!(
+                    "Too many errors, stopped reporting after {}",
+                    self.max_errors
+                ),
+            }),
+            std::cmp::Ordering::Greater => {}
+        }
+        Ok(())
+    }
+
     fn parse_not_expression(&mut self) -> LintResult<Expression> {
         let mut left = if self.match_token(&TokenType::Not) {
             // handle leading NOT operator
-            let operator_span = self.previous().span.clone();
-            let right = self.parse_proximity_expression()?;
-
-            let span = Span::new(operator_span.start.clone(), right.span().end.clone());
-            Expression::BooleanOp {
-                operator: BooleanOperator::Not,
-                left: Box::new(right),
-                right: None,
-                span,
-            }
+            self.parse_unary_not()?
         } else {
             self.parse_proximity_expression()?
         };
 
         while self.match_token(&TokenType::Not) {
             let operator = BooleanOperator::Not;
-            let _operator_span = self.previous().span.clone();
-            let right = self.parse_proximity_expression()?;
+            let right = self.parse_not_operand()?;
 
             let span = Span::new(left.span().start.clone(), right.span().end.clone());
             left = Expression::BooleanOp {
@@ -205,6 +487,29 @@ impl Parser {
         Ok(left)
     }
 
+    /// the NOT has just been consumed
+    fn parse_unary_not(&mut self) -> LintResult<Expression> {
+        let operator_span = self.previous().span.clone();
+        let operand = self.parse_not_operand()?;
+
+        let span = Span::new(operator_span.start.clone(), operand.span().end.clone());
+        Ok(Expression::BooleanOp {
+            operator: BooleanOperator::Not,
+            left: Box::new(operand),
+            right: None,
+            span,
+        })
+    }
+
+    /// what a NOT negates; `NOT NOT x` nests so validation can flag the double negation (W032)
+    fn parse_not_operand(&mut self) -> LintResult<Expression> {
+        if self.match_token(&TokenType::Not) {
+            self.parse_unary_not()
+        } else {
+            self.parse_proximity_expression()
+        }
+    }
+
     fn parse_proximity_expression(&mut self) -> LintResult<Expression> {
         let mut current_expr = self.parse_primary()?;
 
@@ -213,7 +518,7 @@ impl Parser {
             let tilde_span = self.previous().span.clone();
             let distance;
 
-            if current_expr.span().end.offset != tilde_span.start.offset {
+            if current_expr.span().end.char_offset != tilde_span.start.char_offset {
                 return Err(LintError::ParserError {
                     span: tilde_span,
                     message: "The ~ operator must be immediately attached to the preceding term (e.g., apple~5, not apple ~5).".to_string(),
@@ -223,7 +528,7 @@ impl Parser {
             // require distance number immediately after tilde (no spaces)
             if let TokenType::Number(num_str) = &self.peek().token_type {
                 let number_token = self.peek();
-                if tilde_span.end.offset == number_token.span.start.offset {
+                if tilde_span.end.char_offset == number_token.span.start.char_offset {
                     distance = num_str.parse::<u32>().ok();
                     self.advance();
                     if distance.is_none() {
@@ -270,6 +575,7 @@ impl Parser {
                 },
                 terms,
                 span,
+                operator_span: tilde_span,
             });
         }
 
@@ -278,26 +584,30 @@ impl Parser {
             if let TokenType::Near(distance) = &self.peek().token_type {
                 let distance = *distance;
                 self.advance();
-                let _operator_span = self.previous().span.clone();
-                let right = self.parse_primary()?;
+                let operator_token = self.previous().clone();
+                let right = self.parse_near_operand(&operator_token)?;
+                let operator_span = operator_token.span;
 
                 let span = Span::new(current_expr.span().start.clone(), right.span().end.clone());
                 current_expr = Expression::Proximity {
                     operator: ProximityOperator::Near { distance },
                     terms: vec![current_expr, right],
                     span,
+                    operator_span,
                 };
             } else if let TokenType::NearForward(distance) = &self.peek().token_type {
                 let distance = *distance;
                 self.advance();
-                let _operator_span = self.previous().span.clone();
-                let right = self.parse_primary()?;
+                let operator_token = self.previous().clone();
+                let right = self.parse_near_operand(&operator_token)?;
+                let operator_span = operator_token.span;
 
                 let span = Span::new(current_expr.span().start.clone(), right.span().end.clone());
                 current_expr = Expression::Proximity {
                     operator: ProximityOperator::NearForward { distance },
                     terms: vec![current_expr, right],
                     span,
+                    operator_span,
                 };
             } else {
                 break;
@@ -307,6 +617,21 @@ impl Parser {
         Ok(current_expr)
     }
 
+    /// the term after a NEAR operator; E039 at the operator when there isn't one
+    fn parse_near_operand(&mut self, operator: &Token) -> LintResult<Expression> {
+        match self.parse_primary() {
+            Err(LintError::UnexpectedToken { .. }) => Err(Self::near_missing_operand(operator)),
+            result => result,
+        }
+    }
+
+    fn near_missing_operand(operator: &Token) -> LintError {
+        LintError::NearMissingOperand {
+            span: operator.span.clone(),
+            message: format!("{} requires a term on both sides", operator.token_type),
+        }
+    }
+
     fn parse_primary(&mut self) -> LintResult<Expression> {
         // Dispatch to specialized parsing methods based on token type
         match &self.peek().token_type {
@@ -323,6 +648,10 @@ impl Parser {
                     self.parse_term()
                 }
             }
+            // `apple AND NEAR/3 juice`: the NEAR has nothing on its left
+            TokenType::Near(_) | TokenType::NearForward(_) => {
+                Err(Self::near_missing_operand(self.peek()))
+            }
             _ => self.parse_term(),
         }
     }
@@ -384,8 +713,15 @@ impl Parser {
         let end_span = self.advance().span.clone();
         let span = Span::new(start_span.start, end_span.end);
 
+        // whitespace tokens are dropped before parsing, so put back a space wherever the
+        // source had a gap between two tokens
         let mut value = String::new();
+        let mut previous_end = None;
         for token in content_tokens.iter() {
+            if previous_end.is_some_and(|end| token.span.start.byte_offset > end) {
+                value.push(' ');
+            }
+            previous_end = Some(token.span.end.byte_offset);
             match &token.token_type {
                 TokenType::Word(word) => {
                     value.push_str(word);
@@ -439,7 +775,25 @@ impl Parser {
             });
         }
 
-        let value = Box::new(self.parse_primary()?);
+        // `author:AND` would otherwise fail as an operator with nothing before it
+        let value = if matches!(
+            self.peek().token_type,
+            TokenType::And | TokenType::Or | TokenType::Not | TokenType::To
+        ) {
+            let token = self.advance().clone();
+            let operator = token.token_type.to_string();
+            self.operator_field_values.push((
+                Span::new(word_span.start.clone(), token.span.end.clone()),
+                word.clone(),
+                operator.clone(),
+            ));
+            Box::new(Expression::Term {
+                term: Term::Word { value: operator },
+                span: token.span,
+            })
+        } else {
+            Box::new(self.parse_primary()?)
+        };
 
         // Handle special case where field value is a range
         let value = if let Expression::Range {
@@ -498,20 +852,7 @@ impl Parser {
     fn parse_range(&mut self) -> LintResult<Expression> {
         let start_span = self.previous().span.clone();
 
-        let start_value = match &self.peek().token_type {
-            TokenType::Word(w) | TokenType::Number(w) => {
-                let val = w.clone();
-                self.advance();
-                val
-            }
-            _ => {
-                return Err(LintError::ExpectedToken {
-                    span: self.peek().span.clone(),
-                    expected: "number or word".to_string(),
-                    found: self.peek().token_type.to_string(),
-                });
-            }
-        };
+        let start_value = self.parse_range_bound()?;
 
         if !self.match_token(&TokenType::To) {
             return Err(LintError::ExpectedToken {
@@ -521,20 +862,7 @@ impl Parser {
             });
         }
 
-        let end_value = match &self.peek().token_type {
-            TokenType::Word(w) | TokenType::Number(w) => {
-                let val = w.clone();
-                self.advance();
-                val
-            }
-            _ => {
-                return Err(LintError::ExpectedToken {
-                    span: self.peek().span.clone(),
-                    expected: "number or word".to_string(),
-                    found: self.peek().token_type.to_string(),
-                });
-            }
-        };
+        let end_value = self.parse_range_bound()?;
 
         if !self.match_token(&TokenType::RightBracket) {
             return Err(LintError::ExpectedToken {
@@ -555,6 +883,22 @@ impl Parser {
         })
     }
 
+    fn parse_range_bound(&mut self) -> LintResult<RangeBound> {
+        let bound = match &self.peek().token_type {
+            TokenType::Word(w) if w == "*" => RangeBound::Unbounded,
+            TokenType::Word(w) | TokenType::Number(w) => RangeBound::Value(w.clone()),
+            _ => {
+                return Err(LintError::ExpectedToken {
+                    span: self.peek().span.clone(),
+                    expected: "number or word".to_string(),
+                    found: self.peek().token_type.to_string(),
+                });
+            }
+        };
+        self.advance();
+        Ok(bound)
+    }
+
     // parse_comment function removed - comments are now filtered out during parser construction
 
     fn parse_term(&mut self) -> LintResult<Expression> {
@@ -778,6 +1122,229 @@ mod tests {
         assert!(!result.warnings.is_empty());
     }
 
+    #[test]
+    fn test_near_missing_operand() {
+        for (query, column) in [
+            ("apple NEAR/3", 7),
+            ("NEAR/3 apple", 1),
+            ("a AND NEAR/2f b", 7),
+        ] {
+            let tokens = Lexer::new(query).tokenize().unwrap();
+            let Err(err) = Parser::new(tokens).unwrap().parse() else {
+                panic!("expected a parse error for {query}");
+            };
+            assert_eq!(err.code(), "E039", "{query}");
+            assert_eq!(err.span().start.column, column, "{query}");
+        }
+    }
+
+    #[test]
+    fn test_repeated_operator() {
+        let mut lexer = Lexer::new("apple AND AND juice OR OR banana");
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens).unwrap();
+        let result = parser.parse().unwrap();
+
+        assert_eq!(result.errors.len(), 2);
+        assert_eq!(
+            result.errors[0].to_string(),
+            "Repeated operator 'AND AND'; remove one of them"
+        );
+        assert_eq!(result.errors[0].span().start.column, 7);
+        assert_eq!(result.errors[0].span().end.column, 14);
+        assert_eq!(result.errors[1].code(), "E037");
+    }
+
+    #[test]
+    fn test_stray_closers_are_skipped() {
+        let mut lexer = Lexer::new("apple ] } juice ] title:*bad");
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens).unwrap();
+        let result = parser.parse().unwrap();
+
+        let codes: Vec<_> = result.errors.iter().map(|error| error.code()).collect();
+        assert_eq!(codes, vec!["E035", "E036", "E035"]);
+        assert_eq!(
+            result.errors[0].to_string(),
+            "Unmatched closing bracket ']'"
+        );
+        assert_eq!(result.errors[1].span().start.column, 9);
+        assert_eq!(result.query.span.end.column, 29);
+    }
+
+    #[test]
+    fn test_leading_operator_inside_group() {
+        let mut lexer = Lexer::new("banana AND (OR apple)");
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens).unwrap();
+        let Err(err) = parser.parse() else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!(err.to_string(), "'OR' operator requires a term before it");
+        assert_eq!(err.span().start.column, 13);
+    }
+
+    #[test]
+    fn test_dangling_and_before_closing_paren() {
+        let mut lexer = Lexer::new("(apple AND) OR banana");
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens).unwrap();
+        let result = parser.parse().unwrap();
+
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(
+            result.errors[0].to_string(),
+            "'AND' operator requires a term after it"
+        );
+        assert_eq!(result.errors[0].span().start.column, 8);
+        assert_eq!(result.errors[0].span().end.column, 11);
+
+        // the OR clause is still parsed
+        match result.query.expression {
+            Expression::BooleanOp {
+                operator, right, ..
+            } => {
+                assert_eq!(operator, BooleanOperator::Or);
+                assert!(matches!(
+                    right.as_deref(),
+                    Some(Expression::Term {
+                        term: Term::Word { value },
+                        ..
+                    }) if value == "banana"
+                ));
+            }
+            _ => panic!("Expected BooleanOp with OR"),
+        }
+    }
+
+    #[test]
+    fn test_unquoted_field_phrase_warning() {
+        let codes = |query: &str| {
+            let tokens = Lexer::new(query).tokenize().unwrap();
+            let result = Parser::new(tokens).unwrap().parse().unwrap();
+            result
+                .warnings
+                .iter()
+                .map(|w| (w.code(), w.span().start.column, w.span().end.column))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(codes("title:apple juice").contains(&("W027", 1, 18)));
+        assert!(codes("title:new york city AND nyc").contains(&("W027", 1, 20)));
+        assert!(
+            !codes("language:en juice")
+                .iter()
+                .any(|(code, ..)| *code == "W027")
+        );
+        assert!(
+            !codes("title:apple (juice OR soda)")
+                .iter()
+                .any(|(code, ..)| *code == "W027")
+        );
+    }
+
+    #[test]
+    fn test_coalesced_implicit_and_warnings() {
+        let tokens = Lexer::new("a b c").tokenize().unwrap();
+        let result = Parser::new(tokens).unwrap().parse().unwrap();
+        assert_eq!(result.warnings.len(), 2);
+
+        let tokens = Lexer::new("a b c").tokenize().unwrap();
+        let result = Parser::new(tokens)
+            .unwrap()
+            .with_coalesced_implicit_and(true)
+            .parse()
+            .unwrap();
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].span().start.column, 1);
+        assert_eq!(result.warnings[0].span().end.column, 6);
+
+        // an explicit AND ends the run; nested groups keep their own warning
+        let tokens = Lexer::new("a b AND c d (e f)").tokenize().unwrap();
+        let result = Parser::new(tokens)
+            .unwrap()
+            .with_coalesced_implicit_and(true)
+            .parse()
+            .unwrap();
+        let columns: Vec<_> = result
+            .warnings
+            .iter()
+            .map(|w| (w.span().start.column, w.span().end.column))
+            .collect();
+        assert_eq!(columns, vec![(1, 4), (9, 18), (14, 17)]);
+    }
+
+    #[test]
+    fn test_open_ended_range() {
+        let tokens = Lexer::new("authorFollowers:[1000 TO *]")
+            .tokenize()
+            .unwrap();
+        let result = Parser::new(tokens).unwrap().parse().unwrap();
+        match result.query.expression {
+            Expression::Field { value, .. } => {
+                assert_eq!(
+                    *value,
+                    Expression::Range {
+                        field: Some(FieldType::AuthorFollowers),
+                        start: RangeBound::Value("1000".to_string()),
+                        end: RangeBound::Unbounded,
+                        span: value.span().clone(),
+                    }
+                );
+            }
+            other => panic!("expected field, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_recovery_builds_no_placeholder_nodes() {
+        let parse = |query: &str| {
+            let tokens = Lexer::new(query).tokenize().unwrap();
+            Parser::new(tokens).unwrap().parse().unwrap()
+        };
+
+        let recovered = parse("(apple AND) OR banana");
+        assert_eq!(recovered.errors.len(), 1);
+        let terms: Vec<_> = recovered.query.terms().into_iter().cloned().collect();
+        assert_eq!(
+            terms,
+            vec![
+                Term::Word {
+                    value: "apple".to_string()
+                },
+                Term::Word {
+                    value: "banana".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recovered_error_limit() {
+        let query = vec!["(apple AND)"; 150].join(" OR ");
+        let tokens = Lexer::new(&query).tokenize().unwrap();
+        let result = Parser::new(tokens).unwrap().parse().unwrap();
+        assert_eq!(result.errors.len(), DEFAULT_MAX_ERRORS + 1);
+        assert!(
+            result.errors[DEFAULT_MAX_ERRORS]
+                .to_string()
+                .contains("Too many errors")
+        );
+
+        let tokens = Lexer::new(&query).tokenize().unwrap();
+        let result = Parser::new(tokens)
+            .unwrap()
+            .with_max_errors(3)
+            .parse()
+            .unwrap();
+        assert_eq!(result.errors.len(), 4);
+        assert_eq!(
+            result.errors[3].to_string(),
+            "Too many errors, stopped reporting after 3"
+        );
+    }
+
     #[test]
     fn test_colon_in_field_vs_non_field_terms() {
         // valid field operations are parsed as fields
@@ -0,0 +1,508 @@
+//! Metadata for every diagnostic code, used by `bwq explain` and editor tooltips
+
+/// version of the rule set, which ships with (and changes only in releases of) bwq_linter
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        }
+    }
+}
+
+/// preview rules are opt-in (`--preview` or `--extend-select`) while they stabilize
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stability {
+    Stable,
+    Preview,
+}
+
+impl Stability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Preview => "preview",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeInfo {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub severity: Severity,
+    pub stability: Stability,
+    pub summary: &'static str,
+    pub example_before: &'static str,
+    pub example_after: &'static str,
+}
+
+impl CodeInfo {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "code": self.code,
+            "name": self.name,
+            "severity": self.severity.as_str(),
+            "stability": self.stability.as_str(),
+            "summary": self.summary,
+            "example_before": self.example_before,
+            "example_after": self.example_after,
+        })
+    }
+}
+
+/// look up a code such as `E004` or `w001` (case-insensitive)
+pub fn lookup(code: &str) -> Option<&'static CodeInfo> {
+    CODES
+        .iter()
+        .find(|info| info.code.eq_ignore_ascii_case(code))
+}
+
+/// look up a code (case-insensitive) or a name such as `potential-typo`
+pub fn resolve(key: &str) -> Option<&'static CodeInfo> {
+    lookup(key).or_else(|| CODES.iter().find(|info| info.name == key))
+}
+
+/// whether `code` only runs when preview rules are enabled
+pub fn is_preview(code: &str) -> bool {
+    lookup(code).is_some_and(|info| info.stability == Stability::Preview)
+}
+
+pub fn all() -> &'static [CodeInfo] {
+    CODES
+}
+
+const CODES: &[CodeInfo] = &[
+    CodeInfo {
+        code: "E001",
+        name: "lexer-error",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "The query contains characters or syntax that can't be tokenized, such as an unterminated quote.",
+        example_before: "\"apple juice",
+        example_after: "\"apple juice\"",
+    },
+    CodeInfo {
+        code: "E002",
+        name: "parser-error",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "The query structure is invalid, e.g. an operator or field is missing its operand.",
+        example_before: "title: AND apple",
+        example_after: "title:apple",
+    },
+    CodeInfo {
+        code: "E003",
+        name: "validation-error",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "A term or operator is used in a way Brandwatch doesn't accept.",
+        example_before: "apple AND \"\"",
+        example_after: "apple AND \"apple juice\"",
+    },
+    CodeInfo {
+        code: "E004",
+        name: "invalid-wildcard-placement",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "Wildcards can't start a term and need at least two characters before a trailing *.",
+        example_before: "*juice OR a*",
+        example_after: "juic* OR ap*",
+    },
+    CodeInfo {
+        code: "E005",
+        name: "invalid-proximity-operator",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "Reserved for invalid proximity operator (~, NEAR/x, NEAR/xf) syntax; currently reported as E001 or E002.",
+        example_before: "apple NEAR/x juice",
+        example_after: "apple NEAR/3 juice",
+    },
+    CodeInfo {
+        code: "E006",
+        name: "invalid-field-operator",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "Reserved for invalid field operator syntax; currently reported as E001 or E002.",
+        example_before: "title : apple",
+        example_after: "title:apple",
+    },
+    CodeInfo {
+        code: "E007",
+        name: "unexpected-token",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "A token appears where it isn't allowed, or the query is empty.",
+        example_before: "apple ]",
+        example_after: "apple",
+    },
+    CodeInfo {
+        code: "E008",
+        name: "expected-token",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "A required token is missing, most often a closing parenthesis.",
+        example_before: "(apple OR juice",
+        example_after: "(apple OR juice)",
+    },
+    CodeInfo {
+        code: "E009",
+        name: "field-validation",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "A field value is outside what the field accepts.",
+        example_before: "rating:6",
+        example_after: "rating:5",
+    },
+    CodeInfo {
+        code: "E010",
+        name: "proximity-operator",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "NEAR operators must be parenthesized when combined with AND or other NEAR operators.",
+        example_before: "apple NEAR/5 juice AND fresh",
+        example_after: "(apple NEAR/5 juice) AND fresh",
+    },
+    CodeInfo {
+        code: "E011",
+        name: "invalid-field-range",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "A field range is malformed or its bounds are out of order.",
+        example_before: "authorFollowers:[5000 TO 100]",
+        example_after: "authorFollowers:[100 TO 5000]",
+    },
+    CodeInfo {
+        code: "E012",
+        name: "operator-mixing",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "AND and OR can't be mixed in the same sub-query without parentheses.",
+        example_before: "apple AND juice OR soda",
+        example_after: "apple AND (juice OR soda)",
+    },
+    CodeInfo {
+        code: "E013",
+        name: "pure-negative-query",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "A query must contain at least one term that isn't excluded with NOT.",
+        example_before: "NOT apple",
+        example_after: "juice NOT apple",
+    },
+    CodeInfo {
+        code: "E031",
+        name: "missing-required-field",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "The query doesn't use a field that the project configuration requires (require_fields in bwq.toml).",
+        example_before: "apple AND juice",
+        example_after: "apple AND juice AND language:en",
+    },
+    CodeInfo {
+        code: "E032",
+        name: "comparison-shorthand",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "Fields don't support > or < comparisons; use an open-ended range instead.",
+        example_before: "authorFollowers:>1000",
+        example_after: "authorFollowers:[1000 TO *]",
+    },
+    CodeInfo {
+        code: "E033",
+        name: "timeout",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "Validation was stopped because it ran past the linter's configured time limit.",
+        example_before: "(a very large generated query)",
+        example_after: "(split the query, or raise the time limit)",
+    },
+    CodeInfo {
+        code: "E034",
+        name: "query-too-long",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "The query is longer than Brandwatch accepts (4096 characters unless `max_query_length` is set in bwq.toml).",
+        example_before: "(a query of more than 4096 characters)",
+        example_after: "(split it into several queries)",
+    },
+    CodeInfo {
+        code: "E035",
+        name: "unmatched-bracket",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "A closing ] has no opening [, which only appears in ranges like rating:[1 TO 3].",
+        example_before: "apple ] juice",
+        example_after: "apple juice",
+    },
+    CodeInfo {
+        code: "E036",
+        name: "unmatched-brace",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "A closing } has no opening {, which only appears around case-sensitive terms like {Apple}.",
+        example_before: "apple } juice",
+        example_after: "apple juice",
+    },
+    CodeInfo {
+        code: "E037",
+        name: "repeated-operator",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "Two AND/OR operators appear in a row, leaving the second without a term before it.",
+        example_before: "apple AND AND juice",
+        example_after: "apple AND juice",
+    },
+    CodeInfo {
+        code: "E038",
+        name: "match-all-query",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "The whole query is a bare * wildcard, which would match everything and is rejected by Brandwatch.",
+        example_before: "*",
+        example_after: "appl*",
+    },
+    CodeInfo {
+        code: "E039",
+        name: "near-missing-operand",
+        severity: Severity::Error,
+        stability: Stability::Stable,
+        summary: "A NEAR/x or NEAR/xf operator is missing the term on one of its sides.",
+        example_before: "apple NEAR/3",
+        example_after: "apple NEAR/3 juice",
+    },
+    CodeInfo {
+        code: "W001",
+        name: "potential-typo",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "Something that is valid but probably not what was intended, such as terms implicitly ANDed without an operator.",
+        example_before: "apple juice",
+        example_after: "apple AND juice",
+    },
+    CodeInfo {
+        code: "W002",
+        name: "performance",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "A construct that may match far more than intended or slow the query down.",
+        example_before: "#*apple",
+        example_after: "#apple*",
+    },
+    CodeInfo {
+        code: "W025",
+        name: "platform-conflict",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "A Reddit-only field is combined with a site: filter for a different platform, so it can never match.",
+        example_before: "subredditNSFW:true AND site:twitter.com",
+        example_after: "subredditNSFW:true AND site:reddit.com",
+    },
+    CodeInfo {
+        code: "W026",
+        name: "prefix-operator",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "A leading + or - is searched literally; Brandwatch uses AND and NOT instead.",
+        example_before: "+apple -juice",
+        example_after: "apple NOT juice",
+    },
+    CodeInfo {
+        code: "W027",
+        name: "unquoted-field-phrase",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "Only the first word after a text field is restricted to that field; quote the value to search for a phrase.",
+        example_before: "title:apple juice",
+        example_after: "title:\"apple juice\"",
+    },
+    CodeInfo {
+        code: "W028",
+        name: "stopword-query",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "The whole query is a single common word such as 'the', which matches almost everything.",
+        example_before: "the",
+        example_after: "\"the apple juice company\"",
+    },
+    CodeInfo {
+        code: "W029",
+        name: "operator-case",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "A word like ANd or Or looks like a boolean operator but isn't uppercase, so it is searched as a term.",
+        example_before: "apple ANd juice",
+        example_after: "apple AND juice",
+    },
+    CodeInfo {
+        code: "W030",
+        name: "redundant-site",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "A site: filter is ANDed with a url: on the same domain, which already limits results to that site.",
+        example_before: "site:example.com AND url:example.com/news",
+        example_after: "url:example.com/news",
+    },
+    CodeInfo {
+        code: "W031",
+        name: "zero-proximity-distance",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "A NEAR/0 or ~0 distance only matches terms in the same position, which is rarely intended.",
+        example_before: "apple NEAR/0 juice",
+        example_after: "\"apple juice\" OR apple NEAR/1 juice",
+    },
+    CodeInfo {
+        code: "W032",
+        name: "double-negation",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "Two NOTs negate the same operand, which cancels out and is almost always a typo.",
+        example_before: "apple NOT NOT bitter",
+        example_after: "apple NOT bitter",
+    },
+    CodeInfo {
+        code: "W033",
+        name: "quoted-query",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "The whole query is one quoted phrase containing boolean operators, usually left over from shell quoting.",
+        example_before: "\"apple AND juice\"",
+        example_after: "apple AND juice",
+    },
+    CodeInfo {
+        code: "W034",
+        name: "duplicate-operand",
+        severity: Severity::Warning,
+        stability: Stability::Preview,
+        summary: "The same term is on both sides of an AND or OR, so one copy is redundant.",
+        example_before: "apple OR apple",
+        example_after: "apple",
+    },
+    CodeInfo {
+        code: "W035",
+        name: "invalid-source-name",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "A subreddit: or blogName: value has an r/ prefix, spaces or other characters those names can't contain.",
+        example_before: "subreddit:\"r/nba\"",
+        example_after: "subreddit:nba",
+    },
+    CodeInfo {
+        code: "W036",
+        name: "conflicting-language",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "Two different language: values are ANDed together, so no single-language document can match.",
+        example_before: "apple AND language:en AND language:es",
+        example_after: "apple AND language:(en OR es)",
+    },
+    CodeInfo {
+        code: "W037",
+        name: "case-sensitive-operator",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "An AND, OR, NOT or NEAR inside {...} is part of the case-sensitive value, not an operator.",
+        example_before: "{Apple AND Juice}",
+        example_after: "{Apple} AND {Juice}",
+    },
+    CodeInfo {
+        code: "W038",
+        name: "unquoted-location",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "A continent:, region: or city: place name with spaces isn't quoted, so only its first word is the location.",
+        example_before: "city:new york",
+        example_after: "city:\"new york\"",
+    },
+    CodeInfo {
+        code: "W039",
+        name: "ambiguous-not",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "A group starting with NOT before an OR negates only the first operand, not the whole OR.",
+        example_before: "juice AND (NOT apple OR banana)",
+        example_after: "juice AND (NOT (apple OR banana))",
+    },
+    CodeInfo {
+        code: "W040",
+        name: "unused-suppression",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "A <<<bwq:ignore>>> comment doesn't silence anything on its line, so it can be removed.",
+        example_before: "apple AND juice <<<bwq:ignore W001>>>",
+        example_after: "apple AND juice",
+    },
+    CodeInfo {
+        code: "W041",
+        name: "large-near-distance",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "A NEAR/x or NEAR/xf distance above 100 (or `max_near_distance` in bwq.toml) matches unrelated mentions and slows the search down.",
+        example_before: "apple NEAR/150 juice",
+        example_after: "apple NEAR/10 juice",
+    },
+    CodeInfo {
+        code: "W042",
+        name: "fields-only-query",
+        severity: Severity::Warning,
+        stability: Stability::Preview,
+        summary: "The query only filters on fields and ranges, with no keyword, so it matches every mention that passes the filters.",
+        example_before: "language:en AND authorVerified:true",
+        example_after: "apple AND language:en AND authorVerified:true",
+    },
+    CodeInfo {
+        code: "W043",
+        name: "operator-field-value",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "A field's value is an operator word (AND, OR, NOT or TO), which is usually a missing value rather than the literal word.",
+        example_before: "author:AND",
+        example_after: "author:\"AND\"",
+    },
+    CodeInfo {
+        code: "W044",
+        name: "review-platform-conflict",
+        severity: Severity::Warning,
+        stability: Stability::Stable,
+        summary: "A review-only field (rating:) is combined with a site: filter for a platform without reviews, so it can never match.",
+        example_before: "rating:4 AND site:twitter.com",
+        example_after: "rating:4 AND site:trustpilot.com",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_are_unique_and_populated() {
+        let mut codes: Vec<_> = all().iter().map(|info| info.code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), all().len());
+
+        for info in all() {
+            let expected = if info.code.starts_with('E') {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+            assert_eq!(info.severity, expected, "{}", info.code);
+            assert!(!info.summary.is_empty() && !info.example_before.is_empty());
+        }
+
+        assert_eq!(lookup("w027").unwrap().name, "unquoted-field-phrase");
+        assert!(is_preview("W034"));
+        assert!(!is_preview("W033"));
+        assert!(lookup("E999").is_none());
+    }
+}
@@ -0,0 +1,123 @@
+//! `<<<bwq:ignore W001>>>` and `<<<bwq:ignore-next-line E009>>>` comments, which silence
+//! diagnostics on one line of a query
+
+use crate::error::{LintReport, LintWarning, Span};
+
+/// a `bwq:ignore` or `bwq:ignore-next-line` comment
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suppression {
+    /// the whole comment, where an unused suppression is reported
+    pub span: Span,
+    /// the 1-based line it silences
+    pub line: usize,
+    /// codes it silences, uppercased; empty silences everything on the line
+    pub codes: Vec<String>,
+}
+
+impl Suppression {
+    /// the directive in a comment's text, if it is one; `span` covers the whole comment
+    pub fn parse(text: &str, span: &Span) -> Option<Self> {
+        let text = text.trim();
+        let (codes, line) = if let Some(codes) = text.strip_prefix("bwq:ignore-next-line") {
+            (codes, span.end.line + 1)
+        } else if let Some(codes) = text.strip_prefix("bwq:ignore") {
+            (codes, span.start.line)
+        } else {
+            return None;
+        };
+        // `bwq:ignored` isn't a directive
+        if codes.starts_with(|c: char| !c.is_whitespace()) {
+            return None;
+        }
+
+        Some(Self {
+            span: span.clone(),
+            line,
+            codes: codes
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|code| !code.is_empty())
+                .map(str::to_ascii_uppercase)
+                .collect(),
+        })
+    }
+
+    /// whether this silences a `code` diagnostic starting in `span`
+    pub fn covers(&self, code: &str, span: &Span) -> bool {
+        span.start.line == self.line
+            && (self.codes.is_empty() || self.codes.iter().any(|c| c == code))
+    }
+
+    fn unused_warning(&self) -> LintWarning {
+        let directive = if self.codes.is_empty() {
+            "bwq:ignore".to_string()
+        } else {
+            format!("bwq:ignore {}", self.codes.join(", "))
+        };
+        LintWarning::UnusedSuppression {
+            span: self.span.clone(),
+            message: format!(
+                "{directive} doesn't match any diagnostic on line {}. Remove it",
+                self.line
+            ),
+        }
+    }
+}
+
+/// drop the diagnostics `suppressions` cover; with `report_unused`, add W040 for each
+/// suppression that covered nothing
+pub fn apply(report: &mut LintReport, suppressions: &[Suppression], report_unused: bool) {
+    if suppressions.is_empty() {
+        return;
+    }
+
+    let mut used = vec![false; suppressions.len()];
+    let mut suppressed = |code: &str, span: &Span| {
+        let mut covered = false;
+        for (suppression, used) in suppressions.iter().zip(used.iter_mut()) {
+            if suppression.covers(code, span) {
+                *used = true;
+                covered = true;
+            }
+        }
+        covered
+    };
+    report
+        .errors
+        .retain(|error| !suppressed(error.code(), error.span()));
+    report
+        .warnings
+        .retain(|warning| !suppressed(warning.code(), warning.span()));
+
+    if report_unused {
+        for (suppression, used) in suppressions.iter().zip(used) {
+            if !used {
+                report.warnings.push(suppression.unused_warning());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Position;
+
+    fn span_on(line: usize) -> Span {
+        Span::new(Position::new(line, 1, 0, 0), Position::new(line, 5, 4, 4))
+    }
+
+    #[test]
+    fn test_parse_directives() {
+        let suppression = Suppression::parse(" bwq:ignore w001, E009 ", &span_on(2)).unwrap();
+        assert_eq!(
+            (suppression.line, suppression.codes),
+            (2, vec!["W001".to_string(), "E009".to_string()])
+        );
+
+        let suppression = Suppression::parse("bwq:ignore-next-line", &span_on(2)).unwrap();
+        assert_eq!((suppression.line, suppression.codes), (3, vec![]));
+
+        assert_eq!(Suppression::parse("bwq:ignored", &span_on(1)), None);
+        assert_eq!(Suppression::parse("brand terms", &span_on(1)), None);
+    }
+}
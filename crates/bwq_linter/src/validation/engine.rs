@@ -1,11 +1,18 @@
 use super::rules::*;
 use super::{ValidationContext, ValidationRule};
+use std::collections::BTreeMap;
+use std::time::Instant;
+
 use crate::ast::*;
-use crate::error::{LintError, LintReport, LintWarning};
+use crate::config::RuleSeverity;
+use crate::error::{LintError, LintReport, LintResult, LintWarning};
 
 /// per-expression validation engine
 pub struct ValidationEngine {
     rules: Vec<Box<dyn ValidationRule>>,
+    /// `[rules]` overrides by rule name: `Off` rules are skipped, the rest have their
+    /// diagnostics moved to that severity
+    severities: BTreeMap<String, RuleSeverity>,
 }
 
 impl ValidationEngine {
@@ -16,11 +23,15 @@ impl ValidationEngine {
                 Box::new(RatingFieldRule),
                 Box::new(CoordinateFieldRule),
                 Box::new(LanguageFieldRule),
+                Box::new(CountryFieldRule),
+                Box::new(SourceNameFieldRule),
+                Box::new(ConflictingLanguageRule),
                 Box::new(AuthorGenderFieldRule),
                 Box::new(BooleanFieldRule),
                 Box::new(EngagementTypeFieldRule),
                 Box::new(VerifiedTypeFieldRule),
                 Box::new(MinuteOfDayFieldRule),
+                Box::new(EnumeratedRangeFieldRule),
                 Box::new(RangeFieldRule),
                 Box::new(FollowerCountFieldRule),
                 Box::new(GuidFieldRule),
@@ -31,41 +42,141 @@ impl ValidationEngine {
                 Box::new(PureNegativeRule),
                 Box::new(BinaryOperatorRule),
                 Box::new(TildeUsageRule),
+                Box::new(ZeroProximityDistanceRule),
+                Box::new(DoubleNegationRule),
+                Box::new(AmbiguousNotRule),
+                Box::new(DuplicateOperandRule),
+                Box::new(CaseSensitiveOperatorRule),
                 Box::new(WildcardPlacementRule),
+                Box::new(PrefixOperatorRule),
+                Box::new(OperatorCaseRule),
                 // performance validation rules
                 Box::new(ShortTermRule),
+                Box::new(LargeNearDistanceRule::default()),
             ],
+            severities: BTreeMap::new(),
+        }
+    }
+
+    /// warn (W041) on NEAR/x and NEAR/xf distances above `max_distance` instead of the default
+    pub fn set_max_near_distance(&mut self, max_distance: u32) {
+        let rule = LargeNearDistanceRule::new(max_distance);
+        if let Some(slot) = self
+            .rules
+            .iter_mut()
+            .find(|slot| slot.name() == rule.name())
+        {
+            *slot = Box::new(rule);
         }
     }
 
+    /// add a rule that runs after the built-in rules
+    pub fn register_rule(&mut self, rule: Box<dyn ValidationRule>) {
+        self.rules.push(rule);
+    }
+
+    /// names of every registered rule, e.g. `rating-field`
+    pub fn rule_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.rules.iter().map(|rule| rule.name())
+    }
+
+    /// turn rules off or change the severity of what they report, keyed by rule name (other
+    /// keys are ignored)
+    pub fn set_rule_severities(&mut self, severities: BTreeMap<String, RuleSeverity>) {
+        self.severities = severities;
+    }
+
+    /// run every enabled rule that applies to `expr`, except those named in `skip`, remapping
+    /// severities as configured; returns the names of the rules that reported something
+    fn apply_rules(
+        &self,
+        expr: &Expression,
+        ctx: &ValidationContext,
+        skip: &[&'static str],
+        deadline: Option<Instant>,
+        errors: &mut Vec<LintError>,
+        warnings: &mut Vec<LintWarning>,
+    ) -> Result<Vec<&'static str>, DeadlineExceeded> {
+        let mut reported = Vec::new();
+        for rule in &self.rules {
+            let severity = self.severities.get(rule.name()).copied();
+            if severity == Some(RuleSeverity::Off) || skip.contains(&rule.name()) {
+                continue;
+            }
+            check_deadline(deadline)?;
+            if rule.can_validate(expr) {
+                let result = rule.validate(expr, ctx);
+                if !result.errors.is_empty() || !result.warnings.is_empty() {
+                    reported.push(rule.name());
+                }
+                match severity {
+                    Some(RuleSeverity::Error) => {
+                        errors.extend(result.errors);
+                        errors.extend(result.warnings.into_iter().map(LintWarning::into_error));
+                    }
+                    Some(RuleSeverity::Warning) => {
+                        warnings.extend(result.errors.into_iter().map(LintError::into_warning));
+                        warnings.extend(result.warnings);
+                    }
+                    _ => {
+                        errors.extend(result.errors);
+                        warnings.extend(result.warnings);
+                    }
+                }
+            }
+        }
+        Ok(reported)
+    }
+
     pub fn validate(&self, query: &Query) -> LintReport {
+        self.validate_until(query, None)
+            .expect("validation without a deadline can't time out")
+    }
+
+    /// like [`Self::validate`], but gives up with E033 if `deadline` passes before a rule runs
+    pub fn validate_until(
+        &self,
+        query: &Query,
+        deadline: Option<Instant>,
+    ) -> LintResult<LintReport> {
+        self.validate_from(query, &ValidationContext::default(), deadline)
+    }
+
+    /// walk `query` starting from `ctx` rather than the default root context
+    pub(crate) fn validate_from(
+        &self,
+        query: &Query,
+        ctx: &ValidationContext,
+        deadline: Option<Instant>,
+    ) -> LintResult<LintReport> {
         let mut all_errors = Vec::new();
         let mut all_warnings = Vec::new();
 
-        let ctx = ValidationContext::default();
-        self.walk_expression(&query.expression, &ctx, &mut all_errors, &mut all_warnings);
+        self.walk_expression(
+            &query.expression,
+            ctx,
+            deadline,
+            &mut all_errors,
+            &mut all_warnings,
+        )
+        .map_err(|DeadlineExceeded| timeout_error(query))?;
 
-        LintReport {
+        Ok(LintReport {
             errors: all_errors,
             warnings: all_warnings,
-        }
+        })
     }
 
     fn walk_expression(
         &self,
         expr: &Expression,
         ctx: &ValidationContext,
+        deadline: Option<Instant>,
         errors: &mut Vec<LintError>,
         warnings: &mut Vec<LintWarning>,
-    ) {
+    ) -> Result<(), DeadlineExceeded> {
         // apply all relevant rules to this expression
-        for rule in &self.rules {
-            if rule.can_validate(expr) {
-                let result = rule.validate(expr, ctx);
-                errors.extend(result.errors);
-                warnings.extend(result.warnings);
-            }
-        }
+        let reported = self.apply_rules(expr, ctx, &[], deadline, errors, warnings)?;
 
         // recursively validate child expressions with updated context
         match expr {
@@ -78,30 +189,93 @@ impl ValidationEngine {
                 let mut child_ctx = ctx.clone();
                 child_ctx.parent_operator = Some(operator.clone());
 
-                self.walk_expression(left, &child_ctx, errors, warnings);
+                self.walk_expression(left, &child_ctx, deadline, errors, warnings)?;
                 if let Some(right_expr) = right {
-                    self.walk_expression(right_expr, &child_ctx, errors, warnings);
+                    self.walk_expression(right_expr, &child_ctx, deadline, errors, warnings)?;
                 }
             }
             Expression::Group { expression, .. } => {
                 let mut group_ctx = ctx.clone();
                 group_ctx.inside_group = true;
-                self.walk_expression(expression, &group_ctx, errors, warnings);
+                self.walk_expression(expression, &group_ctx, deadline, errors, warnings)?;
             }
             Expression::Proximity { terms, .. } => {
                 for term in terms {
-                    self.walk_expression(term, ctx, errors, warnings);
+                    self.walk_expression(term, ctx, deadline, errors, warnings)?;
                 }
             }
             Expression::Field { field, value, .. } => {
                 let mut field_ctx = ctx.clone();
                 field_ctx.field_context = Some(field.clone());
-                self.walk_expression(value, &field_ctx, errors, warnings);
+
+                // `country:(gbr OR xxx)`: field rules only look at a single term, so
+                // validate each grouped alternative as if it were `country:xxx`. A rule that
+                // already rejected the whole group (`minuteOfDay:(720 OR 780)` isn't a range)
+                // isn't run again on each alternative
+                if !matches!(value.as_ref(), Expression::Term { .. }) {
+                    let mut leaves = Vec::new();
+                    collect_field_values(value, &mut leaves);
+                    for leaf in leaves {
+                        // a range parses as `Range { field }` outside a group, not as a `Field`
+                        let single = match leaf {
+                            Expression::Range {
+                                start, end, span, ..
+                            } => Expression::Range {
+                                field: Some(field.clone()),
+                                start: start.clone(),
+                                end: end.clone(),
+                                span: span.clone(),
+                            },
+                            _ => Expression::Field {
+                                field: field.clone(),
+                                value: Box::new(leaf.clone()),
+                                span: leaf.span().clone(),
+                            },
+                        };
+                        self.apply_rules(&single, ctx, &reported, deadline, errors, warnings)?;
+                    }
+                }
+
+                self.walk_expression(value, &field_ctx, deadline, errors, warnings)?;
             }
             Expression::Range { .. } | Expression::Term { .. } => {
                 // terminal nodes - no recursion needed
             }
         }
+
+        Ok(())
+    }
+}
+
+/// the terms and ranges of a grouped field value, through any parentheses and boolean operators
+fn collect_field_values<'a>(expr: &'a Expression, leaves: &mut Vec<&'a Expression>) {
+    match expr {
+        Expression::Term { .. } | Expression::Range { .. } => leaves.push(expr),
+        Expression::Group { expression, .. } => collect_field_values(expression, leaves),
+        Expression::BooleanOp { left, right, .. } => {
+            collect_field_values(left, leaves);
+            if let Some(right) = right {
+                collect_field_values(right, leaves);
+            }
+        }
+        Expression::Proximity { .. } | Expression::Field { .. } => {}
+    }
+}
+
+/// the validation deadline passed; checked cooperatively between rules
+pub(crate) struct DeadlineExceeded;
+
+pub(crate) fn check_deadline(deadline: Option<Instant>) -> Result<(), DeadlineExceeded> {
+    match deadline {
+        Some(deadline) if Instant::now() >= deadline => Err(DeadlineExceeded),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn timeout_error(query: &Query) -> LintError {
+    LintError::Timeout {
+        span: query.span.clone(),
+        message: "Validation stopped because it exceeded the configured time limit".to_string(),
     }
 }
 
@@ -3,6 +3,7 @@ use crate::error::{LintError, LintWarning};
 
 pub mod engine;
 pub mod rules;
+pub mod suggest;
 
 pub use engine::ValidationEngine;
 
@@ -1,5 +1,6 @@
 use crate::ast::*;
-use crate::error::{LintError, LintWarning};
+use crate::error::{LintError, LintWarning, Span};
+use crate::validation::suggest::with_suggestion;
 use crate::validation::{ValidationContext, ValidationResult, ValidationRule};
 
 pub struct RatingFieldRule;
@@ -39,7 +40,11 @@ impl ValidationRule for RatingFieldRule {
                 span,
             } => match (start.parse::<i32>(), end.parse::<i32>()) {
                 (Ok(start_num), Ok(end_num)) => {
-                    if !(0..=5).contains(&start_num) || !(0..=5).contains(&end_num) {
+                    if [start_num, end_num]
+                        .into_iter()
+                        .flatten()
+                        .any(|rating| !(0..=5).contains(&rating))
+                    {
                         return ValidationResult::with_error(LintError::FieldValidationError {
                             span: span.clone(),
                             message: "Rating values must be between 0 and 5".to_string(),
@@ -122,11 +127,10 @@ impl ValidationRule for CoordinateFieldRule {
                 span,
             } => match (start.parse::<f64>(), end.parse::<f64>()) {
                 (Ok(start_num), Ok(end_num)) => {
+                    let mut bounds = [start_num, end_num].into_iter().flatten();
                     match field {
                         FieldType::Latitude => {
-                            if !(-90.0..=90.0).contains(&start_num)
-                                || !(-90.0..=90.0).contains(&end_num)
-                            {
+                            if bounds.any(|coord| !(-90.0..=90.0).contains(&coord)) {
                                 return ValidationResult::with_error(
                                     LintError::FieldValidationError {
                                         span: span.clone(),
@@ -137,9 +141,7 @@ impl ValidationRule for CoordinateFieldRule {
                             }
                         }
                         FieldType::Longitude => {
-                            if !(-180.0..=180.0).contains(&start_num)
-                                || !(-180.0..=180.0).contains(&end_num)
-                            {
+                            if bounds.any(|coord| !(-180.0..=180.0).contains(&coord)) {
                                 return ValidationResult::with_error(
                                     LintError::FieldValidationError {
                                         span: span.clone(),
@@ -224,6 +226,204 @@ impl ValidationRule for LanguageFieldRule {
     }
 }
 
+/// ISO 3166-1 alpha-3 codes, which Brandwatch uses for `country:`
+const COUNTRY_CODES: &[&str] = &[
+    "abw", "afg", "ago", "aia", "ala", "alb", "and", "are", "arg", "arm", "asm", "ata", "atf",
+    "atg", "aus", "aut", "aze", "bdi", "bel", "ben", "bes", "bfa", "bgd", "bgr", "bhr", "bhs",
+    "bih", "blm", "blr", "blz", "bmu", "bol", "bra", "brb", "brn", "btn", "bvt", "bwa", "caf",
+    "can", "cck", "che", "chl", "chn", "civ", "cmr", "cod", "cog", "cok", "col", "com", "cpv",
+    "cri", "cub", "cuw", "cxr", "cym", "cyp", "cze", "deu", "dji", "dma", "dnk", "dom", "dza",
+    "ecu", "egy", "eri", "esh", "esp", "est", "eth", "fin", "fji", "flk", "fra", "fro", "fsm",
+    "gab", "gbr", "geo", "ggy", "gha", "gib", "gin", "glp", "gmb", "gnb", "gnq", "grc", "grd",
+    "grl", "gtm", "guf", "gum", "guy", "hkg", "hmd", "hnd", "hrv", "hti", "hun", "idn", "imn",
+    "ind", "iot", "irl", "irn", "irq", "isl", "isr", "ita", "jam", "jey", "jor", "jpn", "kaz",
+    "ken", "kgz", "khm", "kir", "kna", "kor", "kwt", "lao", "lbn", "lbr", "lby", "lca", "lie",
+    "lka", "lso", "ltu", "lux", "lva", "mac", "maf", "mar", "mco", "mda", "mdg", "mdv", "mex",
+    "mhl", "mkd", "mli", "mlt", "mmr", "mne", "mng", "mnp", "moz", "mrt", "msr", "mtq", "mus",
+    "mwi", "mys", "myt", "nam", "ncl", "ner", "nfk", "nga", "nic", "niu", "nld", "nor", "npl",
+    "nru", "nzl", "omn", "pak", "pan", "pcn", "per", "phl", "plw", "png", "pol", "pri", "prk",
+    "prt", "pry", "pse", "pyf", "qat", "reu", "rou", "rus", "rwa", "sau", "sdn", "sen", "sgp",
+    "sgs", "shn", "sjm", "slb", "sle", "slv", "smr", "som", "spm", "srb", "ssd", "stp", "sur",
+    "svk", "svn", "swe", "swz", "sxm", "syc", "syr", "tca", "tcd", "tgo", "tha", "tjk", "tkl",
+    "tkm", "tls", "ton", "tto", "tun", "tur", "tuv", "twn", "tza", "uga", "ukr", "umi", "ury",
+    "usa", "uzb", "vat", "vct", "ven", "vgb", "vir", "vnm", "vut", "wlf", "wsm", "yem", "zaf",
+    "zmb", "zwe",
+];
+
+pub struct CountryFieldRule;
+
+impl ValidationRule for CountryFieldRule {
+    fn name(&self) -> &'static str {
+        "country-field"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        if let Expression::Field {
+            field: FieldType::Country,
+            value,
+            span,
+        } = expr
+        {
+            if let Expression::Term {
+                term: Term::Word { value: country },
+                ..
+            } = value.as_ref()
+            {
+                if !COUNTRY_CODES.contains(&country.to_ascii_lowercase().as_str()) {
+                    return ValidationResult::with_warning(LintWarning::PotentialTypo {
+                        span: span.clone(),
+                        message: format!(
+                            "Unknown country code '{country}'. Country codes are 3-letter ISO 3166-1 codes (e.g., 'usa', 'gbr')"
+                        ),
+                    });
+                }
+            }
+        }
+        ValidationResult::new()
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(
+            expr,
+            Expression::Field {
+                field: FieldType::Country,
+                ..
+            }
+        )
+    }
+}
+
+/// `subreddit:` and `blogName:` take bare names: letters, digits and `_` for subreddits,
+/// letters, digits and `-` for blogs, without an `r/` prefix
+pub struct SourceNameFieldRule;
+
+impl ValidationRule for SourceNameFieldRule {
+    fn name(&self) -> &'static str {
+        "source-name-field"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        if let Expression::Field { field, value, span } = expr {
+            if let Expression::Term {
+                term: Term::Word { value: name } | Term::Phrase { value: name },
+                ..
+            } = value.as_ref()
+            {
+                let (kind, separator) = match field {
+                    FieldType::Subreddit => ("subreddit", '_'),
+                    _ => ("blog", '-'),
+                };
+                let valid = |c: char| c.is_ascii_alphanumeric() || c == separator;
+
+                if !name.chars().all(valid) {
+                    let bare: String = name
+                        .trim_start_matches('/')
+                        .trim_start_matches("r/")
+                        .chars()
+                        .filter(|&c| valid(c))
+                        .collect();
+                    let mut message = format!("'{name}' is not a valid {kind} name");
+                    if !bare.is_empty() {
+                        message.push_str(&format!("; did you mean {}:{bare}?", field.as_str()));
+                    }
+
+                    return ValidationResult::with_warning(LintWarning::InvalidSourceName {
+                        span: span.clone(),
+                        message,
+                    });
+                }
+            }
+        }
+        ValidationResult::new()
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(
+            expr,
+            Expression::Field {
+                field: FieldType::Subreddit | FieldType::BlogName,
+                ..
+            }
+        )
+    }
+}
+
+/// `language:en AND language:es`: a document has one language, so two different ones in the
+/// same AND chain (parentheses included) can never both match. Checked once per chain, at its
+/// outermost AND.
+pub struct ConflictingLanguageRule;
+
+impl ValidationRule for ConflictingLanguageRule {
+    fn name(&self) -> &'static str {
+        "conflicting-language"
+    }
+
+    fn validate(&self, expr: &Expression, ctx: &ValidationContext) -> ValidationResult {
+        if ctx.parent_operator == Some(BooleanOperator::And) {
+            return ValidationResult::new();
+        }
+
+        let mut languages = Vec::new();
+        collect_anded_languages(expr, &mut languages);
+        let (Some(first), Some(second)) = (languages.first(), languages.get(1)) else {
+            return ValidationResult::new();
+        };
+
+        ValidationResult::with_warning(LintWarning::ConflictingLanguage {
+            span: expr.span().clone(),
+            message: format!(
+                "language:{first} AND language:{second} can never both match; did you mean language:({first} OR {second})?"
+            ),
+        })
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(
+            expr,
+            Expression::BooleanOp {
+                operator: BooleanOperator::And,
+                right: Some(_),
+                ..
+            }
+        )
+    }
+}
+
+/// distinct `language:` codes that every match of `expr` must satisfy, in source order
+fn collect_anded_languages(expr: &Expression, languages: &mut Vec<String>) {
+    match expr {
+        Expression::BooleanOp {
+            operator: BooleanOperator::And,
+            left,
+            right,
+            ..
+        } => {
+            collect_anded_languages(left, languages);
+            if let Some(right) = right {
+                collect_anded_languages(right, languages);
+            }
+        }
+        Expression::Group { expression, .. } => collect_anded_languages(expression, languages),
+        Expression::Field {
+            field: FieldType::Language,
+            value,
+            ..
+        } => {
+            if let Expression::Term {
+                term: Term::Word { value: code },
+                ..
+            } = value.as_ref()
+            {
+                let code = code.to_ascii_lowercase();
+                if !languages.contains(&code) {
+                    languages.push(code);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
 pub struct AuthorGenderFieldRule;
 
 impl ValidationRule for AuthorGenderFieldRule {
@@ -243,10 +443,15 @@ impl ValidationRule for AuthorGenderFieldRule {
                 ..
             } = value.as_ref()
             {
-                if !matches!(gender.as_str(), "F" | "M") {
+                let valid_genders = FieldType::AuthorGender.allowed_values().unwrap_or_default();
+                if !valid_genders.contains(&gender.as_str()) {
                     return ValidationResult::with_error(LintError::FieldValidationError {
                         span: span.clone(),
-                        message: "authorGender must be 'F' or 'M'".to_string(),
+                        message: with_suggestion(
+                            "authorGender must be 'F' or 'M'",
+                            gender,
+                            valid_genders,
+                        ),
                     });
                 }
             }
@@ -292,7 +497,11 @@ impl ValidationRule for BooleanFieldRule {
                         let field_name = field.as_str();
                         return ValidationResult::with_error(LintError::FieldValidationError {
                             span: span.clone(),
-                            message: format!("{field_name} must be 'true' or 'false'"),
+                            message: with_suggestion(
+                                &format!("{field_name} must be 'true' or 'false'"),
+                                bool_val,
+                                &["true", "false"],
+                            ),
                         });
                     }
                 }
@@ -337,12 +546,17 @@ impl ValidationRule for EngagementTypeFieldRule {
                 ..
             } = value.as_ref()
             {
-                let valid_types = ["COMMENT", "REPLY", "RETWEET", "QUOTE"];
+                let valid_types = FieldType::EngagementType
+                    .allowed_values()
+                    .unwrap_or_default();
                 if !valid_types.contains(&engagement_type.as_str()) {
                     return ValidationResult::with_error(LintError::FieldValidationError {
                         span: span.clone(),
-                        message: "engagementType must be 'COMMENT', 'REPLY', 'RETWEET', or 'QUOTE'"
-                            .to_string(),
+                        message: with_suggestion(
+                            "engagementType must be 'COMMENT', 'REPLY', 'RETWEET', or 'QUOTE'",
+                            engagement_type,
+                            valid_types,
+                        ),
                     });
                 }
             }
@@ -382,11 +596,17 @@ impl ValidationRule for VerifiedTypeFieldRule {
                 ..
             } = value.as_ref()
             {
-                if !matches!(verified_type.as_str(), "blue" | "business" | "government") {
+                let valid_types = FieldType::AuthorVerifiedType
+                    .allowed_values()
+                    .unwrap_or_default();
+                if !valid_types.contains(&verified_type.as_str()) {
                     return ValidationResult::with_error(LintError::FieldValidationError {
                         span: span.clone(),
-                        message: "authorVerifiedType must be 'blue', 'business', or 'government'"
-                            .to_string(),
+                        message: with_suggestion(
+                            "authorVerifiedType must be 'blue', 'business', or 'government'",
+                            verified_type,
+                            valid_types,
+                        ),
                     });
                 }
             }
@@ -405,6 +625,8 @@ impl ValidationRule for VerifiedTypeFieldRule {
     }
 }
 
+/// Brandwatch documents `minuteOfDay` only as a range, so like `authorFollowers` a single
+/// value (`minuteOfDay:720`) is an error; for one minute write `minuteOfDay:[720 TO 720]`.
 pub struct MinuteOfDayFieldRule;
 
 impl ValidationRule for MinuteOfDayFieldRule {
@@ -413,6 +635,22 @@ impl ValidationRule for MinuteOfDayFieldRule {
     }
 
     fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        if let Expression::Field {
+            field: FieldType::MinuteOfDay,
+            value,
+            span,
+        } = expr
+        {
+            if !matches!(value.as_ref(), Expression::Range { .. }) {
+                return ValidationResult::with_error(LintError::FieldValidationError {
+                    span: span.clone(),
+                    message:
+                        "minuteOfDay must be used with a range (e.g., minuteOfDay:[720 TO 780])"
+                            .to_string(),
+                });
+            }
+        }
+
         if let Expression::Range {
             field: Some(FieldType::MinuteOfDay),
             start,
@@ -421,7 +659,11 @@ impl ValidationRule for MinuteOfDayFieldRule {
         } = expr
         {
             if let (Ok(start_num), Ok(end_num)) = (start.parse::<i32>(), end.parse::<i32>()) {
-                if !(0..=1439).contains(&start_num) || !(0..=1439).contains(&end_num) {
+                if [start_num, end_num]
+                    .into_iter()
+                    .flatten()
+                    .any(|minute| !(0..=1439).contains(&minute))
+                {
                     return ValidationResult::with_error(LintError::FieldValidationError {
                         span: span.clone(),
                         message: "minuteOfDay values must be between 0 and 1439".to_string(),
@@ -438,11 +680,47 @@ impl ValidationRule for MinuteOfDayFieldRule {
             Expression::Range {
                 field: Some(FieldType::MinuteOfDay),
                 ..
+            } | Expression::Field {
+                field: FieldType::MinuteOfDay,
+                ..
             }
         )
     }
 }
 
+pub struct EnumeratedRangeFieldRule;
+
+impl ValidationRule for EnumeratedRangeFieldRule {
+    fn name(&self) -> &'static str {
+        "enumerated-range-field"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        if let Expression::Range {
+            field: Some(field),
+            span,
+            ..
+        } = expr
+        {
+            return ValidationResult::with_error(LintError::InvalidFieldRange {
+                span: span.clone(),
+                message: format!("{} does not accept ranges", field.as_str()),
+            });
+        }
+        ValidationResult::new()
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(
+            expr,
+            Expression::Range {
+                field: Some(field),
+                ..
+            } if field.is_enumerated()
+        )
+    }
+}
+
 pub struct RangeFieldRule;
 
 impl ValidationRule for RangeFieldRule {
@@ -455,7 +733,9 @@ impl ValidationRule for RangeFieldRule {
             start, end, span, ..
         } = expr
         {
-            if let (Ok(start_num), Ok(end_num)) = (start.parse::<f64>(), end.parse::<f64>()) {
+            if let (Ok(Some(start_num)), Ok(Some(end_num))) =
+                (start.parse::<f64>(), end.parse::<f64>())
+            {
                 if start_num > end_num {
                     return ValidationResult::with_error(LintError::InvalidFieldRange {
                         span: span.clone(),
@@ -468,7 +748,7 @@ impl ValidationRule for RangeFieldRule {
     }
 
     fn can_validate(&self, expr: &Expression) -> bool {
-        matches!(expr, Expression::Range { .. })
+        matches!(expr, Expression::Range { field, .. } if !field.as_ref().is_some_and(FieldType::is_text))
     }
 }
 
@@ -491,15 +771,17 @@ impl ValidationRule for FollowerCountFieldRule {
                     match (start.parse::<i64>(), end.parse::<i64>()) {
                         (Ok(start_num), Ok(end_num)) => {
                             let mut result = ValidationResult::new();
+                            let bounds: Vec<i64> =
+                                [start_num, end_num].into_iter().flatten().collect();
 
-                            if start_num < 0 || end_num < 0 {
+                            if bounds.iter().any(|count| *count < 0) {
                                 result.errors.push(LintError::InvalidFieldRange {
                                     span: span.clone(),
                                     message: "Follower counts cannot be negative".to_string(),
                                 });
                             }
 
-                            if end_num.to_string().len() > 10 {
+                            if bounds.iter().any(|count| count.to_string().len() > 10) {
                                 result.errors.push(LintError::InvalidFieldRange {
                                     span: span.clone(),
                                     message: "Follower counts cannot exceed 10 digits".to_string(),
@@ -640,3 +922,258 @@ impl ValidationRule for EntityIdFieldRule {
         )
     }
 }
+
+/// Social networks with no star ratings, so `rating:` can't match their posts
+const UNRATED_SITES: &[&str] = &[
+    "twitter.com",
+    "x.com",
+    "facebook.com",
+    "instagram.com",
+    "tiktok.com",
+    "youtube.com",
+    "reddit.com",
+    "tumblr.com",
+    "bsky.app",
+];
+
+/// the content a platform-specific field applies to, e.g. "reviews" for `rating:`
+fn field_platform(field: &FieldType) -> Option<&'static str> {
+    if field.is_reddit_specific() {
+        return Some("Reddit content");
+    }
+    match field {
+        FieldType::Rating => Some("reviews"),
+        _ => None,
+    }
+}
+
+/// whether a lowercased `site:` value can carry content for a platform-specific `field`
+fn site_supports_field(site: &str, field: &FieldType) -> bool {
+    if field.is_reddit_specific() {
+        return site.contains("reddit");
+    }
+    !UNRATED_SITES.iter().any(|unrated| {
+        site == *unrated || site.strip_suffix(unrated).is_some_and(|s| s.ends_with('.'))
+    })
+}
+
+/// Platform-specific fields (subredditNSFW, redditSpoiler, rating, ...) in a query restricted
+/// to a site they can't apply to, e.g. `rating:4 AND site:twitter.com`.
+///
+/// Like PureNegativeRule this needs to see the whole query, so it is called once from validator.rs
+/// rather than registered per expression.
+pub struct PlatformContextRule;
+
+impl PlatformContextRule {
+    pub fn validate_query(&self, query: &Query) -> ValidationResult {
+        let mut platform_fields = Vec::new();
+        let mut sites = Vec::new();
+        Self::collect(&query.expression, true, &mut platform_fields, &mut sites);
+
+        let mut result = ValidationResult::new();
+        for (field, span) in platform_fields {
+            let Some(content) = field_platform(field) else {
+                continue;
+            };
+            if let Some(site) = sites.iter().find(|site| !site_supports_field(site, field)) {
+                let span = span.clone();
+                let message = format!(
+                    "{} only applies to {content}, but this query is restricted to site:{site}",
+                    field.as_str()
+                );
+                // reviews get their own code so W025 overrides and suppressions keep their scope
+                result.warnings.push(if field.is_reddit_specific() {
+                    LintWarning::PlatformConflict { span, message }
+                } else {
+                    LintWarning::ReviewPlatformConflict { span, message }
+                });
+            }
+        }
+        result
+    }
+
+    /// collects platform-specific fields (anywhere) and lowercased `site:` values that are
+    /// not excluded with NOT
+    fn collect<'a>(
+        expr: &'a Expression,
+        included: bool,
+        platform_fields: &mut Vec<(&'a FieldType, &'a Span)>,
+        sites: &mut Vec<String>,
+    ) {
+        match expr {
+            Expression::Field { field, value, span } => {
+                if field_platform(field).is_some() {
+                    platform_fields.push((field, span));
+                } else if *field == FieldType::Site
+                    && included
+                    && let Expression::Term {
+                        term: Term::Word { value: site },
+                        ..
+                    } = value.as_ref()
+                {
+                    sites.push(site.to_lowercase());
+                }
+            }
+            Expression::BooleanOp {
+                operator: BooleanOperator::Not,
+                left,
+                right,
+                ..
+            } => match right {
+                Some(right) => {
+                    Self::collect(left, included, platform_fields, sites);
+                    Self::collect(right, false, platform_fields, sites);
+                }
+                None => Self::collect(left, false, platform_fields, sites),
+            },
+            Expression::BooleanOp { left, right, .. } => {
+                Self::collect(left, included, platform_fields, sites);
+                if let Some(right) = right {
+                    Self::collect(right, included, platform_fields, sites);
+                }
+            }
+            Expression::Group { expression, .. } => {
+                Self::collect(expression, included, platform_fields, sites)
+            }
+            Expression::Proximity { terms, .. } => {
+                for term in terms {
+                    Self::collect(term, included, platform_fields, sites);
+                }
+            }
+            Expression::Range { .. } | Expression::Term { .. } => {}
+        }
+    }
+}
+
+/// `site:example.com AND url:example.com/news`: the url already pins the domain (W030).
+///
+/// Compares fields within each AND chain, so it runs once per query from validator.rs.
+pub struct RedundantSiteRule;
+
+impl RedundantSiteRule {
+    pub fn validate_query(&self, query: &Query) -> ValidationResult {
+        let mut result = ValidationResult::new();
+        Self::check_conjunction(&query.expression, &mut result);
+        result
+    }
+
+    fn check_conjunction(expr: &Expression, result: &mut ValidationResult) {
+        let mut conjuncts = Vec::new();
+        Self::collect_conjuncts(expr, &mut conjuncts);
+
+        let urls: Vec<&str> = conjuncts
+            .iter()
+            .filter_map(|conjunct| Self::field_value(conjunct, FieldType::Url))
+            .collect();
+        for conjunct in &conjuncts {
+            if let Some(site) = Self::field_value(conjunct, FieldType::Site) {
+                let site_domain = Self::domain(site);
+                if let Some(url) = urls.iter().find(|url| {
+                    let url_domain = Self::domain(url);
+                    url_domain == site_domain || url_domain.ends_with(&format!(".{site_domain}"))
+                }) {
+                    result.warnings.push(LintWarning::RedundantSite {
+                        span: conjunct.span().clone(),
+                        message: format!(
+                            "url:{url} already restricts results to {site_domain}, so site:{site} has no effect"
+                        ),
+                    });
+                }
+            }
+        }
+
+        // ORed alternatives inside the chain are conjunctions of their own
+        for conjunct in conjuncts {
+            if let Expression::BooleanOp {
+                operator: BooleanOperator::Or,
+                left,
+                right,
+                ..
+            } = conjunct
+            {
+                Self::check_conjunction(left, result);
+                if let Some(right) = right {
+                    Self::check_conjunction(right, result);
+                }
+            }
+        }
+    }
+
+    /// operands of an AND chain, looking through groups; excluded (NOT) operands are skipped
+    fn collect_conjuncts<'a>(expr: &'a Expression, conjuncts: &mut Vec<&'a Expression>) {
+        match expr {
+            Expression::BooleanOp {
+                operator: BooleanOperator::And,
+                left,
+                right,
+                ..
+            } => {
+                Self::collect_conjuncts(left, conjuncts);
+                if let Some(right) = right {
+                    Self::collect_conjuncts(right, conjuncts);
+                }
+            }
+            Expression::BooleanOp {
+                operator: BooleanOperator::Not,
+                left,
+                right: Some(_),
+                ..
+            } => Self::collect_conjuncts(left, conjuncts),
+            Expression::Group { expression, .. } => Self::collect_conjuncts(expression, conjuncts),
+            _ => conjuncts.push(expr),
+        }
+    }
+
+    fn field_value(expr: &Expression, target: FieldType) -> Option<&str> {
+        match expr {
+            Expression::Field { field, value, .. } if *field == target => match value.as_ref() {
+                Expression::Term {
+                    term: Term::Word { value } | Term::Phrase { value } | Term::Wildcard { value },
+                    ..
+                } => Some(value),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// `https://www.Example.com/news` -> `example.com`
+    fn domain(value: &str) -> String {
+        let value = value.to_lowercase();
+        let rest = value
+            .split_once("://")
+            .map_or(value.as_str(), |(_, rest)| rest);
+        let host = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+        host.trim_start_matches("www.").to_string()
+    }
+}
+
+/// Organization policy: every query must use the configured fields (`require_fields` in bwq.toml).
+///
+/// Runs once per query from validator.rs.
+#[derive(Default)]
+pub struct RequiredFieldsRule {
+    fields: Vec<FieldType>,
+}
+
+impl RequiredFieldsRule {
+    pub fn new(fields: Vec<FieldType>) -> Self {
+        Self { fields }
+    }
+
+    pub fn validate_query(&self, query: &Query) -> ValidationResult {
+        let mut result = ValidationResult::new();
+        for field in &self.fields {
+            if !query.contains_field(field.clone()) {
+                result.errors.push(LintError::MissingRequiredField {
+                    span: query.span.clone(),
+                    message: format!(
+                        "Query must include a {}: filter (required by project configuration)",
+                        field.as_str()
+                    ),
+                });
+            }
+        }
+        result
+    }
+}
@@ -1,5 +1,5 @@
 use crate::ast::*;
-use crate::error::{LintError, LintWarning};
+use crate::error::{LintError, LintWarning, Span};
 use crate::validation::{ValidationContext, ValidationResult, ValidationRule};
 
 pub struct MixedAndOrRule;
@@ -92,15 +92,16 @@ impl ValidationRule for MixedNearRule {
                 operator,
                 left,
                 right,
-                span,
+                ..
             } => {
                 if matches!(operator, BooleanOperator::And) {
                     if let Some(right_expr) = right {
-                        if self.contains_near_at_top_level(left)
-                            || self.contains_near_at_top_level(right_expr)
+                        if let Some(operator_span) = self
+                            .near_operator_span(left)
+                            .or_else(|| self.near_operator_span(right_expr))
                         {
                             return ValidationResult::with_error(LintError::ProximityOperatorError {
-                                span: span.clone(),
+                                span: operator_span.clone(),
                                 message: "Please use parentheses for disambiguation when using the AND operator with a NEAR operator - e.g. (vanilla NEAR/5 chocolate) AND (ice-cream NEAR/5 cake).".to_string(),
                             });
                         }
@@ -108,11 +109,12 @@ impl ValidationRule for MixedNearRule {
                 }
                 if matches!(operator, BooleanOperator::Or) {
                     if let Some(right_expr) = right {
-                        if self.contains_near_at_top_level(right_expr)
-                            || self.contains_near_at_top_level(left)
+                        if let Some(operator_span) = self
+                            .near_operator_span(right_expr)
+                            .or_else(|| self.near_operator_span(left))
                         {
                             return ValidationResult::with_error(LintError::ProximityOperatorError {
-                                span: span.clone(),
+                                span: operator_span.clone(),
                                 message: "You can only use the OR operator with a NEAR operator if they're separated with brackets. Please use parentheses for disambiguation - e.g. (vanilla OR chocolate) NEAR/5 (ice-cream OR cake)".to_string(),
                             });
                         }
@@ -123,7 +125,7 @@ impl ValidationRule for MixedNearRule {
             Expression::Proximity {
                 operator,
                 terms,
-                span,
+                operator_span,
                 ..
             } => {
                 // Only apply AND/OR restrictions to NEAR operators, not tilde operators
@@ -135,19 +137,19 @@ impl ValidationRule for MixedNearRule {
                         // Check for consecutive NEAR mixing
                         if self.contains_near_at_top_level(term) {
                             return ValidationResult::with_error(LintError::ProximityOperatorError {
-                                span: span.clone(),
+                                span: operator_span.clone(),
                                 message: "Please use parentheses for disambiguation when using the NEAR operator with another NEAR operator - e.g. (test NEAR/5 test) NEAR/3 test.".to_string(),
                             });
                         }
                         if self.contains_or_at_top_level(term) {
                             return ValidationResult::with_error(LintError::ProximityOperatorError {
-                                span: span.clone(),
+                                span: operator_span.clone(),
                                 message: "Please use parentheses for disambiguation when using the OR operator with another NEAR operator - e.g. (vanilla OR chocolate) NEAR/5 ice-cream".to_string(),
                             });
                         }
                         if self.contains_and_recursively(term) {
                             return ValidationResult::with_error(LintError::ProximityOperatorError {
-                                span: span.clone(),
+                                span: operator_span.clone(),
                                 message: "The AND operator cannot be used *within* the NEAR operator. Either remove this operator, replace it with a NEAR, or disambiguate with parenthesis, e.g. (vanilla NEAR/5 ice-cream) AND cake.".to_string(),
                             });
                         }
@@ -219,15 +221,19 @@ impl MixedNearRule {
     }
 
     fn contains_near_at_top_level(&self, expr: &Expression) -> bool {
+        self.near_operator_span(expr).is_some()
+    }
+
+    /// span of the NEAR operator if `expr` is an ungrouped NEAR/x or NEAR/xf expression
+    fn near_operator_span<'a>(&self, expr: &'a Expression) -> Option<&'a Span> {
         match expr {
-            Expression::Proximity { operator, .. } => {
-                matches!(
-                    operator,
-                    ProximityOperator::Near { .. } | ProximityOperator::NearForward { .. }
-                )
-            }
-            Expression::Group { .. } => false,
-            _ => false,
+            Expression::Proximity {
+                operator: ProximityOperator::Near { .. } | ProximityOperator::NearForward { .. },
+                operator_span,
+                ..
+            } => Some(operator_span),
+            Expression::Group { .. } => None,
+            _ => None,
         }
     }
 }
@@ -357,6 +363,7 @@ impl ValidationRule for TildeUsageRule {
             operator: ProximityOperator::Proximity { .. },
             terms,
             span,
+            ..
         } = expr
         {
             if let Some(first_term) = terms.first() {
@@ -400,6 +407,299 @@ impl ValidationRule for TildeUsageRule {
     }
 }
 
+/// `NEAR/0` and `"..."~0` (W031). `NEAR/0f` is left alone since it is the way to write an
+/// ordered phrase containing a wildcard (`crowd NEAR/0f strike*`), and a single-word `apple~0`
+/// is fuzzy matching, covered by TildeUsageRule
+pub struct ZeroProximityDistanceRule;
+
+impl ValidationRule for ZeroProximityDistanceRule {
+    fn name(&self) -> &'static str {
+        "zero-proximity-distance"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        if let Expression::Proximity {
+            operator,
+            terms,
+            operator_span,
+            ..
+        } = expr
+        {
+            let zero = match operator {
+                ProximityOperator::Near { distance } => *distance == 0,
+                ProximityOperator::NearForward { .. } => false,
+                ProximityOperator::Proximity { distance } => {
+                    *distance == Some(0)
+                        && !matches!(
+                            terms.first(),
+                            Some(Expression::Term {
+                                term: Term::Word { .. },
+                                ..
+                            })
+                        )
+                }
+            };
+            if zero {
+                return ValidationResult::with_warning(LintWarning::ZeroProximityDistance {
+                    span: operator_span.clone(),
+                    message: "a distance of 0 only matches terms in the same position. Use a quoted phrase for adjacent words, or a distance of 1 or more".to_string(),
+                });
+            }
+        }
+        ValidationResult::new()
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(expr, Expression::Proximity { .. })
+    }
+}
+
+pub struct DoubleNegationRule;
+
+impl ValidationRule for DoubleNegationRule {
+    fn name(&self) -> &'static str {
+        "double-negation"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        if let Expression::BooleanOp {
+            operator: BooleanOperator::Not,
+            left,
+            right,
+            ..
+        } = expr
+        {
+            // the negated side: the only operand of a leading NOT, else the right-hand one
+            let mut negated = right.as_deref().unwrap_or(left);
+            while let Expression::Group { expression, .. } = negated {
+                negated = expression;
+            }
+
+            if let Expression::BooleanOp {
+                operator: BooleanOperator::Not,
+                right: None,
+                span,
+                ..
+            } = negated
+            {
+                return ValidationResult::with_warning(LintWarning::DoubleNegation {
+                    span: span.clone(),
+                    message:
+                        "NOT NOT cancels out, which is almost always a typo. Remove one of the NOTs"
+                            .to_string(),
+                });
+            }
+        }
+        ValidationResult::new()
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(
+            expr,
+            Expression::BooleanOp {
+                operator: BooleanOperator::Not,
+                ..
+            }
+        )
+    }
+}
+
+/// `(NOT apple OR banana)`: the NOT binds to `apple` alone, which reads as if it could negate
+/// the whole OR
+pub struct AmbiguousNotRule;
+
+impl ValidationRule for AmbiguousNotRule {
+    fn name(&self) -> &'static str {
+        "ambiguous-not"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        let Expression::Group { expression, .. } = expr else {
+            return ValidationResult::new();
+        };
+
+        // walk down to the group's first operand, noting whether an OR follows it
+        let mut first = expression.as_ref();
+        let mut followed_by_or = false;
+        while let Expression::BooleanOp {
+            operator,
+            left,
+            right: Some(_),
+            ..
+        } = first
+        {
+            followed_by_or |= *operator == BooleanOperator::Or;
+            first = left;
+        }
+
+        match first {
+            Expression::BooleanOp {
+                operator: BooleanOperator::Not,
+                left: negated,
+                right: None,
+                span,
+            } if followed_by_or => {
+                let negated = negated.inline_text();
+                ValidationResult::with_warning(LintWarning::AmbiguousNot {
+                    span: span.clone(),
+                    message: format!(
+                        "NOT only excludes {negated}, not everything ORed with it. Write (NOT {negated}) OR ... to keep that, or NOT ({negated} OR ...) to exclude them all"
+                    ),
+                })
+            }
+            _ => ValidationResult::new(),
+        }
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(expr, Expression::Group { .. })
+    }
+}
+
+/// `apple OR apple`: identical terms on both sides of an AND or OR (parentheses aside).
+/// Preview (W034), so it only reports with `--preview` or `--extend-select W034`.
+pub struct DuplicateOperandRule;
+
+impl ValidationRule for DuplicateOperandRule {
+    fn name(&self) -> &'static str {
+        "duplicate-operand"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        if let Expression::BooleanOp {
+            operator: operator @ (BooleanOperator::And | BooleanOperator::Or),
+            left,
+            right: Some(right),
+            span,
+        } = expr
+        {
+            if let (Some(left), Some(right)) = (ungrouped_term(left), ungrouped_term(right)) {
+                if left == right {
+                    return ValidationResult::with_warning(LintWarning::DuplicateOperand {
+                        span: span.clone(),
+                        message: format!(
+                            "The same term is on both sides of {}; one copy can be removed",
+                            operator.as_str()
+                        ),
+                    });
+                }
+            }
+        }
+        ValidationResult::new()
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(
+            expr,
+            Expression::BooleanOp {
+                operator: BooleanOperator::And | BooleanOperator::Or,
+                right: Some(_),
+                ..
+            }
+        )
+    }
+}
+
+fn ungrouped_term(mut expr: &Expression) -> Option<&Term> {
+    while let Expression::Group { expression, .. } = expr {
+        expr = expression;
+    }
+    match expr {
+        Expression::Term { term, .. } => Some(term),
+        _ => None,
+    }
+}
+
+/// an uppercase `AND`, `OR`, `NOT` or `NEAR/x` between two other words of a phrase or
+/// case-sensitive value; lowercase `and`/`or` are ordinary words
+fn embedded_operator(value: &str) -> Option<&str> {
+    let words: Vec<_> = value.split_whitespace().collect();
+    words
+        .get(1..words.len().saturating_sub(1))
+        .unwrap_or_default()
+        .iter()
+        .copied()
+        .find(|word| matches!(*word, "AND" | "OR" | "NOT") || word.starts_with("NEAR/"))
+}
+
+/// A whole query that is one quoted phrase with operators inside, e.g. `-q '"apple AND juice"'`
+/// after an extra layer of shell quoting, searches the operators as words.
+///
+/// Heuristic: the top-level expression (parentheses aside) must be a single phrase with an
+/// [`embedded_operator`]. Called once per query from validator.rs.
+pub struct QuotedQueryRule;
+
+impl QuotedQueryRule {
+    pub fn validate_query(&self, query: &Query) -> ValidationResult {
+        let mut expr = &query.expression;
+        while let Expression::Group { expression, .. } = expr {
+            expr = expression;
+        }
+
+        let Expression::Term {
+            term: Term::Phrase { value },
+            span,
+        } = expr
+        else {
+            return ValidationResult::new();
+        };
+
+        let Some(operator) = embedded_operator(value) else {
+            return ValidationResult::new();
+        };
+
+        ValidationResult::with_warning(LintWarning::QuotedQuery {
+            span: span.clone(),
+            message: format!(
+                "The whole query is one quoted phrase, so {operator} is searched as a word. Remove the outer quotes if they came from shell escaping"
+            ),
+        })
+    }
+}
+
+/// `{apple AND juice}`: everything inside the braces is one case-sensitive value, so the
+/// operator is matched as a word. Same [`embedded_operator`] heuristic as [`QuotedQueryRule`].
+pub struct CaseSensitiveOperatorRule;
+
+impl ValidationRule for CaseSensitiveOperatorRule {
+    fn name(&self) -> &'static str {
+        "case-sensitive-operator"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        let Expression::Term {
+            term: Term::CaseSensitive { value },
+            span,
+        } = expr
+        else {
+            return ValidationResult::new();
+        };
+
+        let Some(operator) = embedded_operator(value) else {
+            return ValidationResult::new();
+        };
+
+        ValidationResult::with_warning(LintWarning::OperatorInCaseSensitive {
+            span: span.clone(),
+            message: format!(
+                "{operator} inside {{...}} is matched as a word, not used as an operator. Close the braces around each term instead, e.g. {{{}}} {operator} {{{}}}",
+                value.split_whitespace().next().unwrap_or_default(),
+                value.split_whitespace().last().unwrap_or_default()
+            ),
+        })
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(
+            expr,
+            Expression::Term {
+                term: Term::CaseSensitive { .. },
+                ..
+            }
+        )
+    }
+}
+
 pub struct WildcardPlacementRule;
 
 impl ValidationRule for WildcardPlacementRule {
@@ -407,7 +707,7 @@ impl ValidationRule for WildcardPlacementRule {
         "wildcard-placement"
     }
 
-    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+    fn validate(&self, expr: &Expression, ctx: &ValidationContext) -> ValidationResult {
         match expr {
             Expression::Term {
                 term: Term::Wildcard { value },
@@ -415,6 +715,25 @@ impl ValidationRule for WildcardPlacementRule {
             } => {
                 let mut result = ValidationResult::new();
 
+                // a bare `*` with no operator or field around it is the whole query
+                if value.chars().all(|c| c == '*') {
+                    let error = if ctx.parent_operator.is_none() && ctx.field_context.is_none() {
+                        LintError::MatchAllQuery {
+                            span: span.clone(),
+                            message:
+                                "A standalone wildcard '*' matches everything and is not allowed"
+                                    .to_string(),
+                        }
+                    } else {
+                        LintError::InvalidWildcardPlacement {
+                            span: span.clone(),
+                            message: "A standalone '*' is not a search term. Attach the wildcard to a word, e.g. appl*".to_string(),
+                        }
+                    };
+                    result.errors.push(error);
+                    return result;
+                }
+
                 if value.starts_with('*') || value.starts_with('?') {
                     result
                         .errors
@@ -447,3 +766,102 @@ impl ValidationRule for WildcardPlacementRule {
         }
     }
 }
+
+pub struct PrefixOperatorRule;
+
+impl PrefixOperatorRule {
+    /// `-5`, `+2.5` etc. are legitimate numeric terms, not prefix operators
+    fn is_signed_number(value: &str) -> bool {
+        value[1..].parse::<f64>().is_ok()
+    }
+}
+
+impl ValidationRule for PrefixOperatorRule {
+    fn name(&self) -> &'static str {
+        "prefix-operator"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        let (value, span) = match expr {
+            Expression::Term {
+                term: Term::Word { value } | Term::Wildcard { value },
+                span,
+            } => (value, span),
+            _ => return ValidationResult::new(),
+        };
+
+        let suggestion = if value.starts_with('+') {
+            "Use AND to require a term, e.g. apple AND juice"
+        } else if value.starts_with('-') {
+            "Use NOT to exclude a term, e.g. apple NOT juice"
+        } else {
+            return ValidationResult::new();
+        };
+
+        if value.len() < 2 || Self::is_signed_number(value) {
+            return ValidationResult::new();
+        }
+
+        ValidationResult::with_warning(LintWarning::PrefixOperator {
+            span: span.clone(),
+            message: format!(
+                "'{}' is searched literally; Brandwatch doesn't support '{}' as a term prefix. {suggestion}",
+                value,
+                &value[..1]
+            ),
+        })
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(
+            expr,
+            Expression::Term {
+                term: Term::Word { .. } | Term::Wildcard { .. },
+                ..
+            }
+        )
+    }
+}
+
+pub struct OperatorCaseRule;
+
+impl ValidationRule for OperatorCaseRule {
+    fn name(&self) -> &'static str {
+        "operator-case"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        let Expression::Term {
+            term: Term::Word { value },
+            span,
+        } = expr
+        else {
+            return ValidationResult::new();
+        };
+
+        // all-lowercase `and`/`or` are ordinary words; only mixed casing like `ANd` or `Or`
+        // looks like a mistyped operator
+        let upper = value.to_uppercase();
+        let is_mixed_case = *value != upper && *value != value.to_lowercase();
+        if !is_mixed_case || BooleanOperator::parse(&upper).is_none() {
+            return ValidationResult::new();
+        }
+
+        ValidationResult::with_warning(LintWarning::OperatorCase {
+            span: span.clone(),
+            message: format!(
+                "'{value}' is searched as a word. Did you mean the {upper} operator? Operators must be written in uppercase"
+            ),
+        })
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(
+            expr,
+            Expression::Term {
+                term: Term::Word { .. },
+                ..
+            }
+        )
+    }
+}
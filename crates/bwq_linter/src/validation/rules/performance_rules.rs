@@ -78,3 +78,116 @@ impl ValidationRule for ShortTermRule {
         matches!(expr, Expression::Term { .. })
     }
 }
+
+/// NEAR/x and NEAR/xf with a distance above `max_distance` (W041). Distances that wide match
+/// terms in unrelated sentences and slow the search down; `~` is left alone.
+pub struct LargeNearDistanceRule {
+    max_distance: u32,
+}
+
+impl LargeNearDistanceRule {
+    pub fn new(max_distance: u32) -> Self {
+        Self { max_distance }
+    }
+}
+
+impl Default for LargeNearDistanceRule {
+    fn default() -> Self {
+        Self::new(crate::DEFAULT_MAX_NEAR_DISTANCE)
+    }
+}
+
+impl ValidationRule for LargeNearDistanceRule {
+    fn name(&self) -> &'static str {
+        "large-near-distance"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        if let Expression::Proximity {
+            operator:
+                ProximityOperator::Near { distance } | ProximityOperator::NearForward { distance },
+            operator_span,
+            ..
+        } = expr
+        {
+            if *distance > self.max_distance {
+                return ValidationResult::with_warning(LintWarning::LargeNearDistance {
+                    span: operator_span.clone(),
+                    message: format!(
+                        "a distance of {distance} matches terms far apart and slows the search down. Tighten it to {} or less",
+                        self.max_distance
+                    ),
+                });
+            }
+        }
+        ValidationResult::new()
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(expr, Expression::Proximity { .. })
+    }
+}
+
+/// Common English words that match nearly every mention when used as a whole query
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "he",
+    "her", "his", "i", "if", "in", "is", "it", "its", "me", "my", "no", "not", "of", "on", "or",
+    "our", "she", "so", "that", "the", "their", "them", "they", "this", "to", "us", "was", "we",
+    "were", "what", "when", "which", "who", "will", "with", "you", "your",
+];
+
+/// A whole query that is just `the` (or any single stopword) matches almost everything.
+///
+/// Heuristic: only a bare, all-lowercase word at the top of the query (parentheses aside)
+/// is considered, and it must be a stopword or a single letter. Anything with an uppercase
+/// letter (`AI`, `HP`) is treated as a brand or acronym, and field values (`title:the`) are
+/// never flagged. Called once per query from validator.rs.
+pub struct StopwordQueryRule;
+
+impl StopwordQueryRule {
+    pub fn validate_query(&self, query: &Query) -> ValidationResult {
+        let mut expr = &query.expression;
+        while let Expression::Group { expression, .. } = expr {
+            expr = expression;
+        }
+
+        let Expression::Term {
+            term: Term::Word { value },
+            span,
+        } = expr
+        else {
+            return ValidationResult::new();
+        };
+
+        let is_lowercase = value.chars().all(|c| !c.is_uppercase());
+        let is_single_letter = value.chars().count() == 1 && value.chars().all(char::is_alphabetic);
+        if !is_lowercase || !(is_single_letter || STOPWORDS.contains(&value.as_str())) {
+            return ValidationResult::new();
+        }
+
+        ValidationResult::with_warning(LintWarning::StopwordQuery {
+            span: span.clone(),
+            message: format!(
+                "The query is only the common word '{value}', which matches almost every mention. Add more specific terms"
+            ),
+        })
+    }
+}
+
+/// `language:en AND authorVerified:true`: field and range constraints with no keyword match
+/// every mention that passes the filters. Called once per query from validator.rs.
+pub struct FieldsOnlyQueryRule;
+
+impl FieldsOnlyQueryRule {
+    pub fn validate_query(&self, query: &Query) -> ValidationResult {
+        // every leaf is a field or range once the keywords are gone
+        if !query.keyword_terms().is_empty() {
+            return ValidationResult::new();
+        }
+
+        ValidationResult::with_warning(LintWarning::FieldsOnlyQuery {
+            span: query.span.clone(),
+            message: "The query only filters on fields and has no search terms, so it matches a huge volume. Add at least one keyword".to_string(),
+        })
+    }
+}
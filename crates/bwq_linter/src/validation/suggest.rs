@@ -0,0 +1,62 @@
+/// closest candidate within two edits of `value`, ignoring case
+///
+/// a candidate is only offered when the edits don't rewrite the whole of it, so `X` doesn't
+/// suggest `M` for `authorGender`
+pub fn suggest_closest<'a>(value: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let value = value.to_lowercase();
+    candidates
+        .iter()
+        .map(|candidate| (levenshtein(&value, &candidate.to_lowercase()), *candidate))
+        .filter(|(distance, candidate)| *distance <= 2 && *distance < candidate.chars().count())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// `message` plus a "did you mean" hint when `value` is a near miss of one of `candidates`
+pub fn with_suggestion(message: &str, value: &str, candidates: &[&str]) -> String {
+    match suggest_closest(value, candidates) {
+        Some(candidate) => format!("{message}; did you mean '{candidate}'?"),
+        None => message.to_string(),
+    }
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != *b_char);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest_closest() {
+        let types = ["COMMENT", "REPLY", "RETWEET", "QUOTE"];
+        assert_eq!(suggest_closest("RETWET", &types), Some("RETWEET"));
+        assert_eq!(suggest_closest("retweet", &types), Some("RETWEET"));
+        assert_eq!(suggest_closest("QOUTE", &types), Some("QUOTE"));
+        assert_eq!(suggest_closest("LIKE", &types), None);
+
+        assert_eq!(suggest_closest("f", &["F", "M"]), Some("F"));
+        assert_eq!(suggest_closest("X", &["F", "M"]), None);
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("blue", "blue"), 0);
+    }
+}
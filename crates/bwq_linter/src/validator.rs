@@ -1,11 +1,29 @@
+use std::collections::BTreeMap;
+use std::time::Instant;
+
 use crate::ast::*;
-use crate::error::{LintError, LintReport};
-use crate::validation::{ValidationEngine, rules::PureNegativeRule};
+use crate::config::RuleSeverity;
+use crate::error::{LintError, LintReport, LintResult};
+use crate::validation::{
+    ValidationContext, ValidationEngine, ValidationRule,
+    engine::{check_deadline, timeout_error},
+    rules::{
+        FieldsOnlyQueryRule, PlatformContextRule, PureNegativeRule, QuotedQueryRule,
+        RedundantSiteRule, RequiredFieldsRule, StopwordQueryRule,
+    },
+};
 
 /// plugin-based query-level validator
 pub struct Validator {
     engine: ValidationEngine,
     pure_negative_rule: PureNegativeRule,
+    platform_context_rule: PlatformContextRule,
+    required_fields_rule: RequiredFieldsRule,
+    stopword_query_rule: StopwordQueryRule,
+    redundant_site_rule: RedundantSiteRule,
+    quoted_query_rule: QuotedQueryRule,
+    fields_only_query_rule: FieldsOnlyQueryRule,
+    expensive_rules: bool,
 }
 
 impl Validator {
@@ -13,12 +31,73 @@ impl Validator {
         Self {
             engine: ValidationEngine::new(),
             pure_negative_rule: PureNegativeRule,
+            platform_context_rule: PlatformContextRule,
+            required_fields_rule: RequiredFieldsRule::default(),
+            stopword_query_rule: StopwordQueryRule,
+            redundant_site_rule: RedundantSiteRule,
+            quoted_query_rule: QuotedQueryRule,
+            fields_only_query_rule: FieldsOnlyQueryRule,
+            expensive_rules: true,
         }
     }
 
+    /// fields every query must use somewhere (E031 otherwise)
+    pub fn require_fields(&mut self, fields: Vec<FieldType>) {
+        self.required_fields_rule = RequiredFieldsRule::new(fields);
+    }
+
+    /// run the whole-query rules (E031, W025, W028, W030, W033, W042) after the per-node pass; the server
+    /// turns them off while the user is typing
+    pub fn set_expensive_rules(&mut self, enabled: bool) {
+        self.expensive_rules = enabled;
+    }
+
+    /// `[rules]` overrides for the per-node rules (see [`ValidationEngine::set_rule_severities`])
+    pub fn set_rule_severities(&mut self, severities: BTreeMap<String, RuleSeverity>) {
+        self.engine.set_rule_severities(severities);
+    }
+
+    /// see [`ValidationEngine::set_max_near_distance`]
+    pub fn set_max_near_distance(&mut self, max_distance: u32) {
+        self.engine.set_max_near_distance(max_distance);
+    }
+
+    /// register a custom rule (e.g. organization-specific conventions) alongside the built-in ones
+    pub fn register_rule(&mut self, rule: Box<dyn ValidationRule>) {
+        self.engine.register_rule(rule);
+    }
+
     pub fn validate(&mut self, query: &Query) -> LintReport {
-        let mut report = self.engine.validate(query);
+        self.validate_until(query, None)
+            .expect("validation without a deadline can't time out")
+    }
 
+    /// like [`Self::validate`], but gives up with E033 once `deadline` passes
+    pub fn validate_until(
+        &mut self,
+        query: &Query,
+        deadline: Option<Instant>,
+    ) -> LintResult<LintReport> {
+        self.validate_from(query, &ValidationContext::default(), deadline)
+    }
+
+    /// validate `query` as if it sat under `ctx`, e.g. a field value checked on its own with
+    /// `field_context` preset, or a subtree known to be `inside_group`
+    pub fn validate_with_context(&self, query: &Query, ctx: ValidationContext) -> LintReport {
+        self.validate_from(query, &ctx, None)
+            .expect("validation without a deadline can't time out")
+    }
+
+    fn validate_from(
+        &self,
+        query: &Query,
+        ctx: &ValidationContext,
+        deadline: Option<Instant>,
+    ) -> LintResult<LintReport> {
+        let expired = || check_deadline(deadline).map_err(|_| timeout_error(query));
+        let mut report = self.engine.validate_from(query, ctx, deadline)?;
+
+        expired()?;
         if self
             .pure_negative_rule
             .is_pure_negative_query(&query.expression)
@@ -29,7 +108,36 @@ impl Validator {
             });
         }
 
-        report
+        if !self.expensive_rules {
+            return Ok(report);
+        }
+
+        expired()?;
+        let platform_context = self.platform_context_rule.validate_query(query);
+        report.errors.extend(platform_context.errors);
+        report.warnings.extend(platform_context.warnings);
+
+        expired()?;
+        let required_fields = self.required_fields_rule.validate_query(query);
+        report.errors.extend(required_fields.errors);
+
+        expired()?;
+        let stopword = self.stopword_query_rule.validate_query(query);
+        report.warnings.extend(stopword.warnings);
+
+        expired()?;
+        let redundant_site = self.redundant_site_rule.validate_query(query);
+        report.warnings.extend(redundant_site.warnings);
+
+        expired()?;
+        let quoted_query = self.quoted_query_rule.validate_query(query);
+        report.warnings.extend(quoted_query.warnings);
+
+        expired()?;
+        let fields_only = self.fields_only_query_rule.validate_query(query);
+        report.warnings.extend(fields_only.warnings);
+
+        Ok(report)
     }
 }
 
@@ -44,6 +152,7 @@ mod tests {
     use super::*;
     use crate::lexer::Lexer;
     use crate::parser::Parser;
+    use crate::validation::{ValidationContext, ValidationResult};
 
     #[test]
     fn test_wildcard_validation() {
@@ -154,6 +263,157 @@ mod tests {
         assert!(report.errors.iter().any(|e| e.code() == "E004"));
     }
 
+    struct ForbiddenWordRule;
+
+    impl ValidationRule for ForbiddenWordRule {
+        fn name(&self) -> &'static str {
+            "forbidden-word"
+        }
+
+        fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+            if let Expression::Term {
+                term: Term::Word { value },
+                span,
+            } = expr
+                && value == "forbidden"
+            {
+                return ValidationResult::with_error(LintError::ValidationError {
+                    span: span.clone(),
+                    message: "'forbidden' is not allowed".to_string(),
+                });
+            }
+            ValidationResult::new()
+        }
+
+        fn can_validate(&self, expr: &Expression) -> bool {
+            matches!(expr, Expression::Term { .. })
+        }
+    }
+
+    struct SlowRule;
+
+    impl ValidationRule for SlowRule {
+        fn name(&self) -> &'static str {
+            "slow"
+        }
+
+        fn validate(&self, _expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            ValidationResult::new()
+        }
+
+        fn can_validate(&self, expr: &Expression) -> bool {
+            matches!(expr, Expression::Term { .. })
+        }
+    }
+
+    #[test]
+    fn test_validation_deadline() {
+        let query = (0..20)
+            .map(|i| format!("term{i}"))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        let tokens = Lexer::new(&query).tokenize().unwrap();
+        let result = Parser::new(tokens).unwrap().parse().unwrap();
+
+        let mut validator = Validator::new();
+        validator.register_rule(Box::new(SlowRule));
+
+        let deadline = Instant::now() + std::time::Duration::from_millis(30);
+        let err = validator
+            .validate_until(&result.query, Some(deadline))
+            .unwrap_err();
+        assert_eq!(err.code(), "E033");
+        assert_eq!(err.span(), &result.query.span);
+
+        assert!(validator.validate_until(&result.query, None).is_ok());
+    }
+
+    /// flags bare words that end up as a title: value
+    struct TitleWordRule;
+
+    impl ValidationRule for TitleWordRule {
+        fn name(&self) -> &'static str {
+            "title-word"
+        }
+
+        fn validate(&self, expr: &Expression, ctx: &ValidationContext) -> ValidationResult {
+            if ctx.field_context == Some(FieldType::Title) && ctx.inside_group {
+                return ValidationResult::with_error(LintError::ValidationError {
+                    span: expr.span().clone(),
+                    message: "title word".to_string(),
+                });
+            }
+            ValidationResult::new()
+        }
+
+        fn can_validate(&self, expr: &Expression) -> bool {
+            matches!(expr, Expression::Term { .. })
+        }
+    }
+
+    #[test]
+    fn test_validate_with_context() {
+        let tokens = Lexer::new("apple juice").tokenize().unwrap();
+        let result = Parser::new(tokens).unwrap().parse().unwrap();
+
+        let mut validator = Validator::new();
+        validator.register_rule(Box::new(TitleWordRule));
+        assert!(!validator.validate(&result.query).has_errors());
+
+        let ctx = ValidationContext {
+            inside_group: true,
+            field_context: Some(FieldType::Title),
+            ..Default::default()
+        };
+        let report = validator.validate_with_context(&result.query, ctx);
+        assert_eq!(report.errors.len(), 2);
+
+        // without inside_group the rule stays quiet, so the whole preset reached it
+        let ctx = ValidationContext {
+            field_context: Some(FieldType::Title),
+            ..Default::default()
+        };
+        assert!(
+            !validator
+                .validate_with_context(&result.query, ctx)
+                .has_errors()
+        );
+    }
+
+    #[test]
+    fn test_register_custom_rule() {
+        let mut lexer = Lexer::new("apple AND forbidden");
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens).unwrap();
+        let result = parser.parse().unwrap();
+
+        let mut validator = Validator::new();
+        assert!(validator.validate(&result.query).is_clean());
+
+        validator.register_rule(Box::new(ForbiddenWordRule));
+        let report = validator.validate(&result.query);
+
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].to_string(), "'forbidden' is not allowed");
+        assert_eq!(report.errors[0].span().start.column, 11);
+    }
+
+    #[test]
+    fn test_pure_negative_group() {
+        let mut lexer = Lexer::new("NOT (apple OR banana)");
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens).unwrap();
+        let result = parser.parse().unwrap();
+        let mut validator = Validator::new();
+        let report = validator.validate(&result.query);
+
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].code(), "E013");
+        assert_eq!(report.errors[0].span().start.column, 1);
+        assert_eq!(report.errors[0].span().end.column, 22);
+    }
+
     #[test]
     fn test_performance_warnings() {
         let mut lexer = Lexer::new("#*test");
@@ -174,4 +434,22 @@ mod tests {
         let report = validator.validate(&result.query);
         assert!(report.is_clean());
     }
+
+    #[test]
+    fn test_required_fields() {
+        let parse = |query: &str| {
+            let tokens = Lexer::new(query).tokenize().unwrap();
+            Parser::new(tokens).unwrap().parse().unwrap().query
+        };
+
+        let mut validator = Validator::new();
+        validator.require_fields(vec![FieldType::Language]);
+
+        let report = validator.validate(&parse("apple AND juice"));
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].code(), "E031");
+
+        let report = validator.validate(&parse("apple AND (language:en OR language:fr)"));
+        assert!(report.is_clean());
+    }
 }
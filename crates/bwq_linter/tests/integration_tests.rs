@@ -239,6 +239,8 @@ impl FileTestExpectation {
 #[test_case("NOT bitter", TestExpectation::ErrorCode("E013"); "pure negative query error")]
 #[test_case("NOT term1 AND term2", TestExpectation::ValidNoWarnings; "leading NOT with AND")]
 #[test_case("NOT term1 NOT term2", TestExpectation::ErrorCode("E013"); "double NOT pure negative query error")]
+#[test_case("NOT (apple OR banana)", TestExpectation::ErrorCode("E013"); "NOT group pure negative query error")]
+#[test_case("apple NOT (bitter)", TestExpectation::ValidNoWarnings; "NOT group with positive operand")]
 fn test_basic_boolean_syntax(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, query);
@@ -305,6 +307,11 @@ fn test_case_sensitive_matching() {
 
     // with spaces
     test.assert_valid_no_warnings("{Brand Watch}");
+    test.assert_valid_no_warnings("{salt and pepper}");
+
+    // operators inside the braces are part of the value
+    test.assert_warning_code("{apple AND juice}", "W037");
+    test.assert_warning_code("{Apple NEAR/3 Juice} OR soda", "W037");
 }
 
 #[test]
@@ -332,7 +339,26 @@ fn test_special_character_syntax(query: &str, expected: TestExpectation) {
 #[test_case("apple AND ()", TestExpectation::ErrorCode("E002"); "empty parentheses")]
 #[test_case("NOT bitter", TestExpectation::ErrorCode("E013"); "pure negative query")]
 #[test_case("authorGender:", TestExpectation::ErrorCode("E002"); "field missing value")]
-#[test_case("NEAR/3f juice", TestExpectation::ErrorCode("E002"); "missing left NEAR operand")]
+#[test_case("NEAR/3f juice", TestExpectation::ErrorCode("E039"); "missing left NEAR operand")]
+#[test_case("(apple AND) OR banana", TestExpectation::ErrorCode("E002"); "dangling AND inside group")]
+#[test_case("(apple OR) AND banana", TestExpectation::ErrorCode("E002"); "dangling OR inside group")]
+#[test_case("((apple AND) OR banana) AND juice", TestExpectation::ErrorCode("E002"); "dangling AND inside nested group")]
+#[test_case("(OR apple)", TestExpectation::ErrorCode("E002"); "leading OR inside group")]
+#[test_case("banana AND (AND apple)", TestExpectation::ErrorCode("E002"); "leading AND inside group")]
+#[test_case("(apple AND)", TestExpectation::ErrorCode("E002"); "trailing AND closing the only group")]
+#[test_case("title:(apple OR)", TestExpectation::ErrorCode("E002"); "trailing OR inside field group")]
+#[test_case("apple NEAR/3", TestExpectation::ErrorCode("E039"); "NEAR missing right term")]
+#[test_case("NEAR/3 apple", TestExpectation::ErrorCode("E039"); "NEAR missing left term")]
+#[test_case("(apple NEAR/3f) OR juice", TestExpectation::ErrorCode("E039"); "NEAR/f missing right term in group")]
+#[test_case("*", TestExpectation::ErrorCode("E038"); "standalone wildcard query")]
+#[test_case("(*)", TestExpectation::ErrorCode("E038"); "grouped standalone wildcard query")]
+#[test_case("* AND apple", TestExpectation::ErrorCode("E004"); "standalone wildcard term")]
+#[test_case("apple AND AND juice", TestExpectation::ErrorCode("E037"); "repeated AND")]
+#[test_case("apple OR OR juice", TestExpectation::ErrorCode("E037"); "repeated OR")]
+#[test_case("apple AND OR juice", TestExpectation::ErrorCode("E037"); "AND followed by OR")]
+#[test_case("apple ] juice", TestExpectation::ErrorCode("E035"); "stray closing bracket")]
+#[test_case("apple } juice", TestExpectation::ErrorCode("E036"); "stray closing brace")]
+#[test_case("apple ]", TestExpectation::ErrorCode("E035"); "trailing stray closing bracket")]
 fn test_invalid_query_patterns(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, query);
@@ -394,6 +420,8 @@ fn test_url_like_strings(query: &str, expected: TestExpectation) {
 #[test_case("rating:6", TestExpectation::ErrorCode("E009"); "rating too high")]
 #[test_case("rating:[-1 TO 3]", TestExpectation::ErrorCode("E009"); "rating range with negative")]
 #[test_case("rating:[x TO y]", TestExpectation::ErrorCode("E009"); "invalid rating with literal letters")]
+#[test_case("rating:[* TO 3]", TestExpectation::ValidNoWarnings; "open-ended rating range")]
+#[test_case("rating:[* TO 9]", TestExpectation::ErrorCode("E009"); "open-ended rating range out of bounds")]
 #[test_case("rating:[1 to 5]", TestExpectation::ErrorCode("E008"); "invalid rating with lowercase to")]
 fn test_rating_field_validation(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
@@ -420,11 +448,146 @@ fn test_boolean_field_validation(query: &str, expected: TestExpectation) {
     expected.assert(&mut test, query);
 }
 
+#[test_case("subredditNSFW:true", TestExpectation::ValidNoWarnings; "reddit field alone")]
+#[test_case("redditSpoiler:false AND site:reddit.com", TestExpectation::ValidNoWarnings; "reddit field with reddit site")]
+#[test_case("subredditNSFW:true AND site:twitter.com", TestExpectation::ValidWithWarning("W025"); "reddit field with twitter site")]
+#[test_case("(apple AND redditSpoiler:true) AND site:twitter.com", TestExpectation::ValidWithWarning("W025"); "grouped reddit field with twitter site")]
+#[test_case("subredditNSFW:true NOT site:twitter.com", TestExpectation::ValidNoWarnings; "reddit field with excluded site")]
+#[test_case("rating:4", TestExpectation::ValidNoWarnings; "rating alone")]
+#[test_case("rating:4 AND site:twitter.com", TestExpectation::ValidWithWarning("W044"); "rating with twitter site")]
+#[test_case("rating:[3 TO 5] AND site:mobile.twitter.com", TestExpectation::ValidWithWarning("W044"); "rating range with twitter subdomain")]
+#[test_case("rating:4 AND site:trustpilot.com", TestExpectation::ValidNoWarnings; "rating with review site")]
+#[test_case("rating:4 NOT site:twitter.com", TestExpectation::ValidNoWarnings; "rating with excluded twitter site")]
+fn test_platform_field_context_validation(query: &str, expected: TestExpectation) {
+    let mut test = QueryTest::new();
+    expected.assert(&mut test, query);
+}
+
+#[test_case("+apple", TestExpectation::ValidWithWarning("W026"); "plus prefix required term")]
+#[test_case("apple AND -juice", TestExpectation::ValidWithWarning("W026"); "minus prefix excluded term")]
+#[test_case("apple AND -5", TestExpectation::ValidNoWarnings; "negative number")]
+#[test_case("apple AND -2.5", TestExpectation::ValidNoWarnings; "negative decimal")]
+#[test_case("apple-juice", TestExpectation::ValidNoWarnings; "hyphenated word")]
+fn test_prefix_operator_validation(query: &str, expected: TestExpectation) {
+    let mut test = QueryTest::new();
+    expected.assert(&mut test, query);
+}
+
+#[test_case("title:apple juice", TestExpectation::ValidWithWarning("W027"); "text field followed by bare word")]
+#[test_case("author:john smith jr", TestExpectation::ValidWithWarning("W027"); "text field followed by several bare words")]
+#[test_case("url:example.com news", TestExpectation::ValidWithWarning("W027"); "url field followed by bare word")]
+#[test_case("city:new york", TestExpectation::ValidWithWarning("W038"); "unquoted multi-word city")]
+#[test_case("apple AND region:north rhine westphalia", TestExpectation::ValidWithWarning("W038"); "unquoted multi-word region")]
+#[test_case("city:\"new york\"", TestExpectation::ValidNoWarnings; "quoted multi-word city")]
+#[test_case("city:berlin AND apple", TestExpectation::ValidNoWarnings; "single-word city")]
+#[test_case("title:\"apple juice\"", TestExpectation::ValidNoWarnings; "quoted text field phrase")]
+#[test_case("title:apple AND juice", TestExpectation::ValidNoWarnings; "explicit AND after text field")]
+#[test_case("language:en juice", TestExpectation::ValidWithWarning("W001"); "non-text field followed by bare word")]
+fn test_unquoted_field_phrase_validation(query: &str, expected: TestExpectation) {
+    let mut test = QueryTest::new();
+    expected.assert(&mut test, query);
+}
+
+#[test_case("author:AND", TestExpectation::ValidWithWarning("W043"); "AND as field value")]
+#[test_case("apple AND title:OR", TestExpectation::ValidWithWarning("W043"); "OR as field value")]
+#[test_case("author:TO", TestExpectation::ValidWithWarning("W043"); "TO as field value")]
+#[test_case("author:\"AND\"", TestExpectation::ValidNoWarnings; "quoted operator as field value")]
+#[test_case("author:and", TestExpectation::ValidNoWarnings; "lowercase word as field value")]
+fn test_operator_field_value_validation(query: &str, expected: TestExpectation) {
+    let mut test = QueryTest::new();
+    expected.assert(&mut test, query);
+}
+
+#[test_case("the", TestExpectation::ValidWithWarning("W028"); "single stopword query")]
+#[test_case("(a)", TestExpectation::ValidWithWarning("W028"); "grouped single letter query")]
+#[test_case("AI", TestExpectation::ValidNoWarnings; "uppercase acronym")]
+#[test_case("The", TestExpectation::ValidNoWarnings; "capitalized word treated as brand-like")]
+#[test_case("apple", TestExpectation::ValidNoWarnings; "single non-stopword")]
+#[test_case("title:the", TestExpectation::ValidNoWarnings; "stopword as field value")]
+#[test_case("the AND apple", TestExpectation::ValidNoWarnings; "stopword with other terms")]
+fn test_stopword_query_validation(query: &str, expected: TestExpectation) {
+    let mut test = QueryTest::new();
+    expected.assert(&mut test, query);
+}
+
+#[test_case("apple ANd juice", TestExpectation::ValidWithWarning("W029"); "mixed case AND")]
+#[test_case("apple Or juice", TestExpectation::ValidWithWarning("W029"); "capitalized OR")]
+#[test_case("apple AND nOT juice", TestExpectation::ValidWithWarning("W029"); "mixed case NOT")]
+#[test_case("apple AND Android", TestExpectation::ValidNoWarnings; "word starting with and")]
+#[test_case("apple AND Oregon", TestExpectation::ValidNoWarnings; "word starting with or")]
+fn test_operator_case_validation(query: &str, expected: TestExpectation) {
+    let mut test = QueryTest::new();
+    expected.assert(&mut test, query);
+}
+
+#[test]
+fn test_lowercase_operator_words_are_not_operator_typos() {
+    let mut linter = BrandwatchLinter::new();
+    let report = linter.lint("apple and juice or soda").unwrap();
+    assert!(report.warnings.iter().all(|w| w.code() != "W029"));
+}
+
+#[test_case("authorFollowers:>1000", TestExpectation::ErrorCode("E032"); "greater than shorthand")]
+#[test_case("authorFollowers:<500", TestExpectation::ErrorCode("E032"); "less than shorthand")]
+#[test_case("apple AND authorFollowers:>=1000", TestExpectation::ErrorCode("E032"); "greater or equal shorthand")]
+fn test_comparison_shorthand_validation(query: &str, expected: TestExpectation) {
+    let mut test = QueryTest::new();
+    expected.assert(&mut test, query);
+}
+
+#[test_case("site:example.com AND url:example.com/news", TestExpectation::ValidWithWarning("W030"); "site and url on same domain")]
+#[test_case("apple AND site:example.com AND url:\"https://www.example.com/news\"", TestExpectation::ValidWithWarning("W030"); "site and full url on same domain")]
+#[test_case("(site:example.com OR site:other.org) AND url:example.com/news", TestExpectation::ValidNoWarnings; "site alternatives with url")]
+#[test_case("site:example.com AND url:other.org/news", TestExpectation::ValidNoWarnings; "site and url on different domains")]
+#[test_case("site:example.com OR url:example.com/news", TestExpectation::ValidNoWarnings; "site or url on same domain")]
+#[test_case("site:example.com NOT url:example.com/news", TestExpectation::ValidNoWarnings; "site excluding a url on the same domain")]
+fn test_redundant_site_validation(query: &str, expected: TestExpectation) {
+    let mut test = QueryTest::new();
+    expected.assert(&mut test, query);
+}
+
+#[test_case("apple NEAR/0 juice", TestExpectation::ValidWithWarning("W031"); "NEAR with zero distance")]
+#[test_case("crowd NEAR/0f strike*", TestExpectation::ValidNoWarnings; "forward NEAR zero as wildcard phrase")]
+#[test_case("apple NEAR/0f juice", TestExpectation::ValidNoWarnings; "forward NEAR zero as ordered phrase")]
+#[test_case("apple NEAR/1f juice", TestExpectation::ValidNoWarnings; "forward NEAR with distance one")]
+#[test_case("\"apple juice\"~0", TestExpectation::ValidWithWarning("W031"); "phrase tilde with zero distance")]
+#[test_case("apple NEAR/1 juice", TestExpectation::ValidNoWarnings; "NEAR with distance one")]
+#[test_case("\"apple juice\"~2", TestExpectation::ValidNoWarnings; "phrase tilde with distance two")]
+fn test_zero_proximity_distance_validation(query: &str, expected: TestExpectation) {
+    let mut test = QueryTest::new();
+    expected.assert(&mut test, query);
+}
+
+#[test_case("engagementType:[COMMENT TO REPLY]", TestExpectation::ErrorCode("E011"); "engagement type range")]
+#[test_case("authorGender:[F TO M]", TestExpectation::ErrorCode("E011"); "author gender range")]
+#[test_case("authorVerified:[false TO true]", TestExpectation::ErrorCode("E011"); "boolean field range")]
+#[test_case("rating:[2 TO 4]", TestExpectation::ValidNoWarnings; "rating range still accepted")]
+#[test_case("apple NOT NOT bitter", TestExpectation::ValidWithWarning("W032"); "double not")]
+#[test_case("apple NOT (NOT bitter)", TestExpectation::ValidWithWarning("W032"); "double not through group")]
+#[test_case("NOT NOT bitter", TestExpectation::ErrorCodeWithWarning("E013", "W032"); "leading double not")]
+#[test_case("apple NOT bitter", TestExpectation::ValidNoWarnings; "single not")]
+#[test_case("apple NOT (banana NOT bitter)", TestExpectation::ValidNoWarnings; "not over a positive term")]
+#[test_case("juice AND (NOT apple OR banana)", TestExpectation::ValidWithWarning("W039"); "group starting with not before or")]
+#[test_case("juice AND (NOT (apple OR banana))", TestExpectation::ValidNoWarnings; "not over a grouped or")]
+#[test_case("juice AND ((NOT apple) OR banana)", TestExpectation::ValidNoWarnings; "explicitly grouped not before or")]
+#[test_case("juice AND (NOT apple AND banana)", TestExpectation::ValidNoWarnings; "group starting with not before and")]
+#[test_case("\"apple AND juice\"", TestExpectation::ValidWithWarning("W033"); "whole query quoted with and")]
+#[test_case("(\"apple OR orange NEAR/3 juice\")", TestExpectation::ValidWithWarning("W033"); "whole query quoted in group")]
+#[test_case("\"salt and pepper\"", TestExpectation::ValidNoWarnings; "phrase with lowercase and")]
+#[test_case("\"AND\" OR \"AND then\"", TestExpectation::ValidNoWarnings; "operators quoted within a larger query")]
+#[test_case("\"apple AND juice\" OR banana", TestExpectation::ValidNoWarnings; "quoted phrase that is not the whole query")]
+// preview rules are off by default
+#[test_case("apple OR apple", TestExpectation::ValidNoWarnings; "duplicate operand is preview only")]
 #[test_case("language:en", TestExpectation::ValidNoWarnings; "valid 2-char language code")]
 #[test_case("language:fr", TestExpectation::ValidNoWarnings; "valid french language code")]
 #[test_case("language:ENG", TestExpectation::ValidWithWarning("W001"); "uppercase language code warning")]
 #[test_case("language:english", TestExpectation::ValidWithWarning("W001"); "full language name warning")]
 #[test_case("languag:e", TestExpectation::ValidNoWarnings; "invalid field operator is valid")]
+#[test_case("apple AND language:en AND language:es", TestExpectation::ValidWithWarning("W036"); "anded languages conflict")]
+#[test_case("apple AND (language:en AND (juice OR soda)) AND language:es", TestExpectation::ValidWithWarning("W036"); "grouped anded languages conflict")]
+#[test_case("apple AND (language:en OR language:es)", TestExpectation::ValidNoWarnings; "ored languages do not conflict")]
+#[test_case("(apple AND language:en) OR (juice AND language:es)", TestExpectation::ValidNoWarnings; "languages in separate or branches")]
+#[test_case("apple AND language:en AND language:EN", TestExpectation::ValidWithWarning("W001"); "repeated language is not a conflict")]
 fn test_language_field_validation(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, query);
@@ -453,6 +616,8 @@ fn test_verified_type_field_validation(query: &str, expected: TestExpectation) {
 #[test_case("minuteOfDay:[720 TO 780]", TestExpectation::ValidNoWarnings; "valid minute of day noon to 1pm")]
 #[test_case("minuteOfDay:[-1 TO 100]", TestExpectation::ErrorCode("E009"); "minute of day with negative")]
 #[test_case("minuteOfDay:[0 TO 1440]", TestExpectation::ErrorCode("E009"); "minute of day over max")]
+#[test_case("minuteOfDay:720", TestExpectation::ErrorCode("E009"); "minute of day single value")]
+#[test_case("minuteOfDay:[720 TO 720]", TestExpectation::ValidNoWarnings; "minute of day single minute as range")]
 fn test_minute_of_day_field_validation(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, query);
@@ -463,13 +628,27 @@ fn test_minute_of_day_field_validation(query: &str, expected: TestExpectation) {
 #[test_case("authorFollowers:[100000 TO 1000]", TestExpectation::ErrorCode("E011"); "invalid author followers start greater than end")]
 #[test_case("authorFollowers:[0 TO 10000000000]", TestExpectation::ErrorCode("E011"); "invalid author followers over max digits")]
 #[test_case("authorFollowers:[x TO y]", TestExpectation::ErrorCode("E009"); "invalid author followers with literal letters")]
+#[test_case("authorFollowers:[1000 TO *]", TestExpectation::ValidNoWarnings; "open-ended author followers range")]
+#[test_case("authorFollowers:[-5 TO *]", TestExpectation::ErrorCode("E011"); "open-ended author followers negative")]
 #[test_case("authorFollowers:term", TestExpectation::ErrorCode("E009"); "authorFollowers requires range not term")]
 fn test_author_followers_field_validation(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, query);
 }
 
+#[test_case("subreddit:nba", TestExpectation::ValidNoWarnings; "valid subreddit name")]
+#[test_case("subreddit:nba_2k", TestExpectation::ValidNoWarnings; "subreddit name with underscore")]
+#[test_case("subreddit:r/nba", TestExpectation::ValidWithWarning("W035"); "subreddit with r/ prefix")]
+#[test_case("subreddit:\"n ba\"", TestExpectation::ValidWithWarning("W035"); "subreddit with a space")]
+#[test_case("blogName:my-blog", TestExpectation::ValidNoWarnings; "valid blog name")]
+#[test_case("blogName:\"my blog\"", TestExpectation::ValidWithWarning("W035"); "blog name with a space")]
 #[test_case("country:gbr", TestExpectation::ValidNoWarnings; "valid country code")]
+#[test_case("country:xxx", TestExpectation::ValidWithWarning("W001"); "unknown country code")]
+#[test_case("country:(gbr OR usa)", TestExpectation::ValidNoWarnings; "grouped country codes")]
+#[test_case("country:(gbr OR xxx)", TestExpectation::ValidWithWarning("W001"); "unknown code in grouped country codes")]
+#[test_case("language:(en OR es)", TestExpectation::ValidNoWarnings; "grouped language codes")]
+#[test_case("rating:(3 OR 6)", TestExpectation::ErrorCode("E009"); "invalid rating in grouped values")]
+#[test_case("engagementType:(COMMENT OR [A TO B])", TestExpectation::ErrorCode("E011"); "range in grouped enum values")]
 #[test_case("region:usa.fl", TestExpectation::ValidNoWarnings; "valid region code")]
 #[test_case("city:\"deu.berlin.berlin\"", TestExpectation::ValidNoWarnings; "valid city code")]
 fn test_location_field_validation(query: &str, expected: TestExpectation) {
@@ -506,9 +685,11 @@ fn test_entity_id_field_validation(query: &str, expected: TestExpectation) {
 // Tests for misc validation
 // ============================================================================
 
-#[test_case("apple NEAR/150 juice", TestExpectation::ValidNoWarnings; "NEAR with large distance should not generate warnings")]
+#[test_case("apple NEAR/100 juice", TestExpectation::ValidNoWarnings; "NEAR at the distance limit should not generate warnings")]
+#[test_case("apple NEAR/150 juice", TestExpectation::ValidWithWarning("W041"); "NEAR with large distance should generate warning")]
+#[test_case("apple NEAR/150f juice", TestExpectation::ValidWithWarning("W041"); "forward NEAR with large distance should generate warning")]
 #[test_case("apple* OR juice*", TestExpectation::ValidNoWarnings; "multiple wildcards in OR")]
-#[test_case("a", TestExpectation::ValidNoWarnings; "single character should not generate warnings")]
+#[test_case("a", TestExpectation::ValidWithWarning("W028"); "single character only warns as a stopword query")]
 #[test_case("42 OR 24*", TestExpectation::ValidNoWarnings; "mixing pure numbers and numeric wildcards")]
 fn test_performance_edge_cases(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
@@ -593,12 +774,17 @@ fn test_near_operator_interaction_validation() {
         "(test NEAR/5 testing) NEAR/5 (test NEAR/5 test) OR test",
         "(test NEAR/5 testing) NEAR/5 (test NEAR/5 test) NEAR/5 test",
         "test NEAR/5 test NEAR/5 test",
+        "apple NEAR/3 juice NEAR/5f drink",
     ];
 
     for query in mixed_near_boolean_cases {
         test.assert_error_code(query, "E010");
     }
 
+    // a parenthesized inner NEAR settles the chaining
+    test.assert_valid("(apple NEAR/3 juice) NEAR/5 drink");
+    test.assert_valid("apple NEAR/3 (juice NEAR/5 drink)");
+
     // Test AND within NEAR operands (should fail)
     let near_with_and_cases = vec![
         "(test AND test) NEAR/5 (test)",
@@ -646,9 +832,9 @@ fn test_near_operator_interaction_validation() {
 #[test_case("resources/test/fixtures/valid/complex_social_media.bwq", FileTestExpectation::ValidNoWarnings; "complex social media query")]
 #[test_case("resources/test/fixtures/valid/valid_real_world_multiline.bwq", FileTestExpectation::ValidNoWarnings; "real world multiline query")]
 #[test_case("resources/test/fixtures/valid/complex_near.bwq", FileTestExpectation::ValidNoWarnings; "complex NEAR operations")]
-#[test_case("resources/test/fixtures/valid/field_operations.bwq", FileTestExpectation::ValidNoWarnings; "field operations")]
 #[test_case("resources/test/fixtures/valid/comments_and_wildcards.bwq", FileTestExpectation::ValidNoWarnings; "comments and wildcards")]
 #[test_case("resources/test/fixtures/invalid/invalid_mixed_operators.bwq", FileTestExpectation::ErrorCode("E012"); "invalid mixed operators")]
+#[test_case("resources/test/fixtures/warning/field_operations.bwq", FileTestExpectation::WarningCode("W044"); "rating on a site without reviews")]
 fn test_fixture_files(file_path: &str, expected: FileTestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, file_path);
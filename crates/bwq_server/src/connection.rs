@@ -1,8 +1,9 @@
 use anyhow::Result;
 use lsp_server::{self as lsp, Connection};
 use lsp_types::{
-    HoverProviderCapability, InitializeParams, ServerCapabilities, TextDocumentSyncCapability,
-    TextDocumentSyncKind,
+    CompletionOptions, HoverProviderCapability, InitializeParams, OneOf, RenameOptions,
+    SelectionRangeProviderCapability, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextDocumentSyncOptions, TextDocumentSyncSaveOptions,
 };
 
 pub(crate) struct ConnectionInitializer {
@@ -10,6 +11,10 @@ pub(crate) struct ConnectionInitializer {
 }
 
 impl ConnectionInitializer {
+    pub(crate) fn new(connection: Connection) -> Self {
+        Self { connection }
+    }
+
     pub(crate) fn stdio() -> (Self, lsp::IoThreads) {
         let (connection, threads) = Connection::stdio();
         (Self { connection }, threads)
@@ -43,12 +48,28 @@ impl ConnectionInitializer {
 
 pub(crate) fn server_capabilities(enable_hover: bool) -> ServerCapabilities {
     ServerCapabilities {
-        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                ..Default::default()
+            },
+        )),
         hover_provider: if enable_hover {
             Some(HoverProviderCapability::Simple(true))
         } else {
             None
         },
+        rename_provider: Some(OneOf::Right(RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: Default::default(),
+        })),
+        selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![":".to_string(), "(".to_string()]),
+            ..Default::default()
+        }),
         ..Default::default()
     }
 }
@@ -1,18 +1,55 @@
 use anyhow::Result;
-use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use lsp_types::{Diagnostic, DiagnosticSeverity, Range};
 
-use crate::utils::span_to_range;
-use bwq_linter::{
-    BrandwatchLinter,
-    ast::Query,
-    error::{LintError, LintWarning},
-};
+use bwq_linter::{BrandwatchLinter, ast::Query};
 
-pub struct DiagnosticsHandler;
+/// size above which a document gets an informational "this is large" diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComplexityBudget {
+    pub max_operators: usize,
+    pub max_depth: usize,
+}
+
+impl Default for ComplexityBudget {
+    fn default() -> Self {
+        Self {
+            max_operators: 1000,
+            max_depth: 20,
+        }
+    }
+}
+
+impl ComplexityBudget {
+    /// read `{"maxOperators": n, "maxDepth": n}`, keeping defaults for missing keys
+    pub fn from_json(value: &serde_json::Value) -> Self {
+        let default = Self::default();
+        let get = |key: &str, fallback: usize| {
+            value
+                .get(key)
+                .and_then(|v| v.as_u64())
+                .map_or(fallback, |v| v as usize)
+        };
+        Self {
+            max_operators: get("maxOperators", default.max_operators),
+            max_depth: get("maxDepth", default.max_depth),
+        }
+    }
+}
+
+pub struct DiagnosticsHandler {
+    budget: ComplexityBudget,
+}
 
 impl DiagnosticsHandler {
     pub fn new() -> Self {
-        Self
+        Self {
+            budget: ComplexityBudget::default(),
+        }
+    }
+
+    pub fn with_complexity_budget(mut self, budget: ComplexityBudget) -> Self {
+        self.budget = budget;
+        self
     }
 
     pub fn analyze_content_with_ast(
@@ -25,42 +62,46 @@ impl DiagnosticsHandler {
         let analysis = linter.analyze_for_server(content);
 
         for error in &analysis.errors {
-            diagnostics.push(self.error_to_diagnostic(error));
+            diagnostics.push(error.to_lsp_diagnostic());
         }
 
         for warning in &analysis.warnings {
-            diagnostics.push(self.warning_to_diagnostic(warning));
+            diagnostics.push(warning.to_lsp_diagnostic());
+        }
+
+        if let Some(diagnostic) = analysis
+            .ast
+            .as_ref()
+            .and_then(|ast| self.complexity_diagnostic(ast))
+        {
+            diagnostics.push(diagnostic);
         }
 
         Ok((diagnostics, analysis.ast))
     }
 
-    fn error_to_diagnostic(&self, error: &LintError) -> Diagnostic {
-        Diagnostic {
-            range: span_to_range(error.span()),
-            severity: Some(DiagnosticSeverity::ERROR),
-            code: Some(NumberOrString::String(error.code().to_string())),
-            code_description: None,
-            source: Some("bwq".to_string()),
-            message: error.to_string(),
-            related_information: None,
-            tags: None,
-            data: None,
+    /// informational note at the top of the file when the query is over budget
+    fn complexity_diagnostic(&self, ast: &Query) -> Option<Diagnostic> {
+        let operators = ast.operator_count();
+        let depth = ast.max_depth();
+        if operators <= self.budget.max_operators && depth <= self.budget.max_depth {
+            return None;
         }
-    }
 
-    fn warning_to_diagnostic(&self, warning: &LintWarning) -> Diagnostic {
-        Diagnostic {
-            range: span_to_range(warning.span()),
-            severity: Some(DiagnosticSeverity::WARNING),
-            code: Some(NumberOrString::String(warning.code().to_string())),
+        Some(Diagnostic {
+            range: Range::default(),
+            severity: Some(DiagnosticSeverity::INFORMATION),
+            code: None,
             code_description: None,
             source: Some("bwq".to_string()),
-            message: format!("{warning}"),
+            message: format!(
+                "Large query ({operators} operators, nesting depth {depth}; budget is {} operators, depth {}), diagnostics may be slow",
+                self.budget.max_operators, self.budget.max_depth
+            ),
             related_information: None,
             tags: None,
             data: None,
-        }
+        })
     }
 }
 
@@ -118,4 +159,41 @@ mod tests {
             .collect();
         assert!(errors.is_empty(), "Valid query should not have errors");
     }
+
+    #[test]
+    fn test_complexity_budget_diagnostic() {
+        let mut linter = BrandwatchLinter::new();
+        let info = |diagnostics: &[Diagnostic]| {
+            diagnostics
+                .iter()
+                .filter(|d| d.severity == Some(DiagnosticSeverity::INFORMATION))
+                .count()
+        };
+
+        let terms: Vec<String> = (0..1200).map(|i| format!("term{i}")).collect();
+        let content = terms.join(" OR ");
+        let (diagnostics, _) = DiagnosticsHandler::new()
+            .analyze_content_with_ast(&content, &mut linter)
+            .unwrap();
+        assert_eq!(info(&diagnostics), 1);
+        let diagnostic = diagnostics
+            .iter()
+            .find(|d| d.severity == Some(DiagnosticSeverity::INFORMATION))
+            .unwrap();
+        assert_eq!(diagnostic.range, Range::default());
+        assert!(diagnostic.message.contains("1199 operators"));
+
+        let (diagnostics, _) = DiagnosticsHandler::new()
+            .analyze_content_with_ast("apple AND juice", &mut linter)
+            .unwrap();
+        assert_eq!(info(&diagnostics), 0);
+
+        let budget = ComplexityBudget::from_json(&serde_json::json!({ "maxDepth": 1 }));
+        assert_eq!(budget.max_operators, 1000);
+        let (diagnostics, _) = DiagnosticsHandler::new()
+            .with_complexity_budget(budget)
+            .analyze_content_with_ast("((apple OR juice) AND fresh) OR soda", &mut linter)
+            .unwrap();
+        assert_eq!(info(&diagnostics), 1);
+    }
 }
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use anyhow::{Context, bail};
+use lsp_server::{Connection, Message, Notification, Request, RequestId};
+use lsp_types::{
+    DidOpenTextDocumentParams, InitializeParams, InitializedParams, PublishDiagnosticsParams,
+    TextDocumentItem, Uri,
+    notification::{
+        DidOpenTextDocument, Exit, Initialized, Notification as NotificationTrait,
+        PublishDiagnostics,
+    },
+    request::{Initialize, Request as RequestTrait, Shutdown},
+};
+
+use crate::Result;
+use crate::connection::ConnectionInitializer;
+use crate::server::Server;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Start a server on an in-process connection and drive it through
+/// initialize -> didOpen -> publishDiagnostics -> shutdown -> exit.
+///
+/// Returns an error describing the first step that failed.
+pub fn health_check() -> Result<()> {
+    let (server_side, client) = Connection::memory();
+
+    let server_thread = std::thread::spawn(move || -> Result<()> {
+        let worker_threads = std::num::NonZeroUsize::new(1).unwrap();
+        Server::new(worker_threads, ConnectionInitializer::new(server_side))?.run()
+    });
+
+    let client_result = drive_client(&client);
+    // closing the client side unblocks the server if the client bailed out early
+    drop(client);
+
+    let server_result = server_thread
+        .join()
+        .map_err(|_| anyhow::anyhow!("server thread panicked"))?;
+
+    client_result.and(server_result.context("server exited with an error"))
+}
+
+fn drive_client(client: &Connection) -> Result<()> {
+    let initialize = Request::new(
+        RequestId::from(1),
+        Initialize::METHOD.to_string(),
+        InitializeParams::default(),
+    );
+    client.sender.send(initialize.into())?;
+    expect_response(client, RequestId::from(1)).context("initialize")?;
+
+    send_notification::<Initialized>(client, InitializedParams {})?;
+
+    let uri: Uri = "file:///health-check.bwq".parse()?;
+    send_notification::<DidOpenTextDocument>(
+        client,
+        DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "bwq".to_string(),
+                version: 1,
+                text: "rating:6".to_string(),
+            },
+        },
+    )?;
+    let diagnostics = expect_diagnostics(client).context("publishDiagnostics")?;
+    if diagnostics.uri != uri || diagnostics.diagnostics.is_empty() {
+        bail!("publishDiagnostics: expected an error for the health check document");
+    }
+
+    let shutdown = Request::new(RequestId::from(2), Shutdown::METHOD.to_string(), ());
+    client.sender.send(shutdown.into())?;
+    expect_response(client, RequestId::from(2)).context("shutdown")?;
+
+    send_notification::<Exit>(client, ())?;
+    Ok(())
+}
+
+fn send_notification<N: NotificationTrait>(client: &Connection, params: N::Params) -> Result<()> {
+    client
+        .sender
+        .send(Notification::new(N::METHOD.to_string(), params).into())?;
+    Ok(())
+}
+
+fn expect_response(client: &Connection, id: RequestId) -> Result<()> {
+    loop {
+        match client.receiver.recv_timeout(TIMEOUT)? {
+            Message::Response(response) if response.id == id => {
+                return match response.error {
+                    Some(error) => bail!("server returned an error: {}", error.message),
+                    None => Ok(()),
+                };
+            }
+            _ => continue,
+        }
+    }
+}
+
+fn expect_diagnostics(client: &Connection) -> Result<PublishDiagnosticsParams> {
+    loop {
+        match client.receiver.recv_timeout(TIMEOUT)? {
+            Message::Notification(notification)
+                if notification.method == PublishDiagnostics::METHOD =>
+            {
+                return Ok(serde_json::from_value(notification.params)?);
+            }
+            _ => continue,
+        }
+    }
+}
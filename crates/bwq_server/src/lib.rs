@@ -1,5 +1,6 @@
 mod connection;
 mod diagnostics_handler;
+mod health;
 mod request_queue;
 pub mod server;
 pub mod task;
@@ -11,6 +12,9 @@ use crate::server::Server;
 use anyhow::Context;
 use std::num::NonZeroUsize;
 
+pub use diagnostics_handler::ComplexityBudget;
+pub use health::health_check;
+
 pub(crate) type Result<T> = anyhow::Result<T>;
 
 pub fn run() -> Result<()> {
@@ -10,12 +10,16 @@ use lsp_types::{
 };
 
 use crate::connection::{ConnectionInitializer, server_capabilities};
+use crate::diagnostics_handler::ComplexityBudget;
 use crate::server::handlers::EntitySearchParams;
 use crate::task::{TaskExecutor, TaskResponse};
 use crate::wikidata::{EntityInfo, EntitySearchResult};
 
 pub mod client;
+mod completion;
 pub mod handlers;
+mod rename;
+mod selection_range;
 pub mod session;
 mod utils;
 
@@ -55,6 +59,14 @@ impl Server {
             .and_then(|v| v.as_bool())
             .unwrap_or(true); // Default to enabled
 
+        // e.g. {"complexityBudget": {"maxOperators": 500, "maxDepth": 10}}
+        let complexity_budget = init_params
+            .initialization_options
+            .as_ref()
+            .and_then(|opts| opts.get("complexityBudget"))
+            .map(ComplexityBudget::from_json)
+            .unwrap_or_default();
+
         let capabilities = server_capabilities(enable_hover);
 
         let connection = connection_initializer.initialize_finish(
@@ -65,7 +77,11 @@ impl Server {
         )?;
 
         let (task_response_sender, task_response_receiver) = crossbeam_channel::bounded(16);
-        let task_executor = TaskExecutor::new(worker_threads, task_response_sender);
+        let task_executor = TaskExecutor::with_complexity_budget(
+            worker_threads,
+            task_response_sender,
+            complexity_budget,
+        );
 
         Ok(Self {
             connection,
@@ -143,7 +159,7 @@ impl Server {
                                 if document_version == current_version {
                                     // Check if this will evict an entry from the LRU cache
                                     let will_evict = self.session.ast_cache.len() == self.session.ast_cache.cap().get() && !self.session.ast_cache.contains(&params.uri);
-                                    self.session.ast_cache.put(params.uri.clone(), ast);
+                                    self.session.ast_cache.put(params.uri.clone(), *ast);
 
                                     if will_evict {
                                         tracing::debug!("AST CACHE: LRU evicted entry - cache size: {}", self.session.ast_cache.len());
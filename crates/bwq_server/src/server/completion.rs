@@ -0,0 +1,147 @@
+use bwq_linter::ast::{Expression, FieldType, Query};
+use lsp_types::{CompletionItem, CompletionItemKind};
+
+/// completions at the byte `offset`: the allowed values when the cursor follows `field:` for an
+/// enumerated field (or sits inside `field:( ... )`), otherwise every field name
+pub fn completions(content: &str, ast: Option<&Query>, offset: usize) -> Vec<CompletionItem> {
+    let before = content.get(..offset).unwrap_or(content);
+    let word_start = before
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace() || matches!(c, '(' | ')' | '"'))
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let word = &before[word_start..];
+
+    if let Some((name, _)) = word.split_once(':') {
+        // `http://` or `unknownField:` offer nothing rather than field names mid-value
+        return FieldType::parse(name)
+            .map(|field| value_items(&field))
+            .unwrap_or_default();
+    }
+
+    let enclosing_field = ast.and_then(|ast| {
+        ast.nodes_at_offset(offset)
+            .into_iter()
+            .rev()
+            .find_map(|node| match node {
+                Expression::Field { field, value, .. }
+                    if matches!(value.as_ref(), Expression::Group { .. }) =>
+                {
+                    Some(field.clone())
+                }
+                _ => None,
+            })
+    });
+    match enclosing_field {
+        Some(field) if field.allowed_values().is_some() => value_items(&field),
+        _ => FieldType::ALL.iter().map(field_item).collect(),
+    }
+}
+
+fn field_item(field: &FieldType) -> CompletionItem {
+    CompletionItem {
+        label: field.as_str().to_string(),
+        kind: Some(CompletionItemKind::FIELD),
+        detail: Some(field_detail(field)),
+        insert_text: Some(format!("{}:", field.as_str())),
+        ..Default::default()
+    }
+}
+
+fn value_items(field: &FieldType) -> Vec<CompletionItem> {
+    field
+        .allowed_values()
+        .unwrap_or_default()
+        .iter()
+        .map(|value| CompletionItem {
+            label: value.to_string(),
+            kind: Some(CompletionItemKind::ENUM_MEMBER),
+            detail: Some(format!("{} value", field.as_str())),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// the kind of value a field expects, e.g. "rating: range, e.g. rating:[1 TO 5]"
+fn field_detail(field: &FieldType) -> String {
+    let name = field.as_str();
+    match field.allowed_values() {
+        Some(["true", "false"]) => format!("{name}: boolean (true or false)"),
+        Some(values) => format!("{name}: enum ({})", values.join(", ")),
+        None if field.is_range() => format!("{name}: range, e.g. {name}:[1 TO 5]"),
+        None if field.is_text() => format!("{name}: text, quote multi-word values"),
+        None => format!("{name}: value"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(items: &[CompletionItem]) -> Vec<&str> {
+        items.iter().map(|item| item.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_field_name_completions() {
+        let items = completions("apple AND ", None, 10);
+        assert_eq!(items.len(), FieldType::ALL.len());
+
+        let rating = items.iter().find(|item| item.label == "rating").unwrap();
+        assert_eq!(rating.insert_text.as_deref(), Some("rating:"));
+        assert_eq!(
+            rating.detail.as_deref(),
+            Some("rating: range, e.g. rating:[1 TO 5]")
+        );
+
+        let verified = items
+            .iter()
+            .find(|item| item.label == "authorVerified")
+            .unwrap();
+        assert_eq!(
+            verified.detail.as_deref(),
+            Some("authorVerified: boolean (true or false)")
+        );
+    }
+
+    #[test]
+    fn test_enum_value_completions() {
+        let content = "apple AND engagementType:RE";
+        let items = completions(content, None, content.len());
+        assert_eq!(labels(&items), vec!["COMMENT", "REPLY", "RETWEET", "QUOTE"]);
+
+        let content = "(authorVerifiedType:";
+        assert_eq!(
+            labels(&completions(content, None, content.len())),
+            vec!["blue", "business", "government"]
+        );
+
+        // multibyte whitespace before the word, e.g. a non-breaking space
+        let content = "apple\u{a0}engagementType:";
+        assert_eq!(
+            labels(&completions(content, None, content.len())),
+            vec!["COMMENT", "REPLY", "RETWEET", "QUOTE"]
+        );
+        let content = "apple\u{3000}";
+        assert_eq!(
+            completions(content, None, content.len()).len(),
+            FieldType::ALL.len()
+        );
+
+        // free-text fields have no values to offer
+        assert!(completions("title:", None, 6).is_empty());
+        assert!(completions("unknownField:", None, 13).is_empty());
+    }
+
+    #[test]
+    fn test_enum_values_inside_field_group() {
+        let content = "authorGender:(F OR M)";
+        let (_, ast) = bwq_linter::BrandwatchLinter::new()
+            .lint_for_server(content)
+            .unwrap();
+        assert_eq!(
+            labels(&completions(content, Some(&ast), 19)),
+            vec!["F", "M"]
+        );
+    }
+}
@@ -4,9 +4,13 @@ use lsp_types::*;
 use serde_json::Value;
 
 use crate::server::client::Client;
+use crate::server::completion::completions;
+use crate::server::rename;
+use crate::server::selection_range::selection_range;
 use crate::server::session::{AstState, DocumentState, Session};
 use crate::server::utils;
 use crate::task::TaskExecutor;
+use crate::utils::span_to_range;
 
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct EntitySearchParams {
@@ -114,6 +118,7 @@ pub fn handle_did_open(
             diagnostics_request.content,
             diagnostics_request.document_version,
             None,
+            true,
         )?;
     }
     Ok(())
@@ -146,6 +151,7 @@ pub fn handle_did_change(
                     diagnostics_request.content,
                     diagnostics_request.document_version,
                     None,
+                    false,
                 )?;
             }
         }
@@ -154,6 +160,29 @@ pub fn handle_did_change(
     Ok(())
 }
 
+/// re-lint with the whole-query rules, which [`handle_did_change`] skips
+pub fn handle_did_save(
+    session: &mut Session,
+    task_executor: &TaskExecutor,
+    params: DidSaveTextDocumentParams,
+) -> Result<()> {
+    let uri = params.text_document.uri;
+    let Some(content) = session.documents.get(&uri).map(|doc| doc.content.clone()) else {
+        return Ok(());
+    };
+
+    if let Some(diagnostics_request) = session.prepare_diagnostics(&uri, &content) {
+        task_executor.schedule_diagnostics(
+            diagnostics_request.uri,
+            diagnostics_request.content,
+            diagnostics_request.document_version,
+            None,
+            true,
+        )?;
+    }
+    Ok(())
+}
+
 pub fn handle_hover_request(
     session: &mut Session,
     client: &Client,
@@ -209,6 +238,7 @@ pub fn handle_hover_request(
                         diagnostics_request.content,
                         diagnostics_request.document_version,
                         None,
+                        true,
                     )?;
                 }
                 let response = Response::new_ok(req.id, serde_json::Value::Null);
@@ -239,6 +269,178 @@ pub fn handle_hover_request(
     Ok(())
 }
 
+pub fn handle_prepare_rename_request(
+    session: &mut Session,
+    client: &Client,
+    req: Request,
+) -> Result<()> {
+    let params: TextDocumentPositionParams = match serde_json::from_value(req.params) {
+        Ok(params) => params,
+        Err(e) => {
+            let response = Response::new_err(
+                req.id,
+                lsp_server::ErrorCode::InvalidParams as i32,
+                format!("Invalid prepare rename params: {e}"),
+            );
+            client.send_response(response)?;
+            return Ok(());
+        }
+    };
+
+    let uri = params.text_document.uri;
+    let range = session.documents.get(&uri).and_then(|doc| {
+        let byte_position = utils::lsp_position_to_byte_position(&doc.content, params.position);
+        let ast = session.ast_cache.get(&uri)?;
+        rename::term_at_offset(ast, byte_position)
+            .map(|(_, span)| PrepareRenameResponse::Range(span_to_range(span)))
+    });
+
+    client.send_response(Response::new_ok(req.id, serde_json::to_value(range)?))?;
+    Ok(())
+}
+
+pub fn handle_rename_request(session: &mut Session, client: &Client, req: Request) -> Result<()> {
+    let params: RenameParams = match serde_json::from_value(req.params) {
+        Ok(params) => params,
+        Err(e) => {
+            let response = Response::new_err(
+                req.id,
+                lsp_server::ErrorCode::InvalidParams as i32,
+                format!("Invalid rename params: {e}"),
+            );
+            client.send_response(response)?;
+            return Ok(());
+        }
+    };
+
+    let uri = params.text_document_position.text_document.uri;
+    let position = params.text_document_position.position;
+
+    // renames work from the cached AST, like hover; before the first parse there's nothing to rename
+    let Some(byte_position) = session
+        .documents
+        .get(&uri)
+        .map(|doc| utils::lsp_position_to_byte_position(&doc.content, position))
+    else {
+        client.send_response(Response::new_ok(req.id, serde_json::Value::Null))?;
+        return Ok(());
+    };
+    let Some(ast) = session.ast_cache.get(&uri) else {
+        client.send_response(Response::new_ok(req.id, serde_json::Value::Null))?;
+        return Ok(());
+    };
+    let Some((term, _)) = rename::term_at_offset(ast, byte_position) else {
+        client.send_response(Response::new_ok(req.id, serde_json::Value::Null))?;
+        return Ok(());
+    };
+
+    if let Err(message) = rename::validate_new_name(term, &params.new_name) {
+        let response =
+            Response::new_err(req.id, lsp_server::ErrorCode::InvalidParams as i32, message);
+        client.send_response(response)?;
+        return Ok(());
+    }
+
+    let edits = rename::rename_spans(ast, byte_position)
+        .iter()
+        .map(|span| TextEdit {
+            range: span_to_range(span),
+            new_text: params.new_name.clone(),
+        })
+        .collect();
+    let workspace_edit = WorkspaceEdit {
+        changes: Some([(uri, edits)].into_iter().collect()),
+        ..Default::default()
+    };
+
+    client.send_response(Response::new_ok(
+        req.id,
+        serde_json::to_value(workspace_edit)?,
+    ))?;
+    Ok(())
+}
+
+pub fn handle_selection_range_request(
+    session: &mut Session,
+    client: &Client,
+    req: Request,
+) -> Result<()> {
+    let params: SelectionRangeParams = match serde_json::from_value(req.params) {
+        Ok(params) => params,
+        Err(e) => {
+            let response = Response::new_err(
+                req.id,
+                lsp_server::ErrorCode::InvalidParams as i32,
+                format!("Invalid selection range params: {e}"),
+            );
+            client.send_response(response)?;
+            return Ok(());
+        }
+    };
+
+    let uri = params.text_document.uri;
+    let content = session.documents.get(&uri).map(|doc| doc.content.clone());
+    let ast = session.ast_cache.get(&uri);
+
+    // one result per requested position; an empty range where there's no AST node
+    let ranges: Vec<_> = params
+        .positions
+        .into_iter()
+        .map(|position| {
+            content
+                .as_deref()
+                .zip(ast)
+                .and_then(|(content, ast)| {
+                    let byte_position = utils::lsp_position_to_byte_position(content, position);
+                    selection_range(ast, byte_position)
+                })
+                .unwrap_or(SelectionRange {
+                    range: lsp_types::Range::new(position, position),
+                    parent: None,
+                })
+        })
+        .collect();
+
+    client.send_response(Response::new_ok(req.id, serde_json::to_value(ranges)?))?;
+    Ok(())
+}
+
+pub fn handle_completion_request(
+    session: &mut Session,
+    client: &Client,
+    req: Request,
+) -> Result<()> {
+    let params: CompletionParams = match serde_json::from_value(req.params) {
+        Ok(params) => params,
+        Err(e) => {
+            let response = Response::new_err(
+                req.id,
+                lsp_server::ErrorCode::InvalidParams as i32,
+                format!("Invalid completion params: {e}"),
+            );
+            client.send_response(response)?;
+            return Ok(());
+        }
+    };
+
+    let position = params.text_document_position;
+    let items = match session.documents.get(&position.text_document.uri) {
+        Some(doc) => {
+            let byte_position =
+                utils::lsp_position_to_byte_position(&doc.content, position.position);
+            let ast = session.ast_cache.get(&position.text_document.uri);
+            completions(&doc.content, ast, byte_position)
+        }
+        None => Vec::new(),
+    };
+
+    client.send_response(Response::new_ok(
+        req.id,
+        serde_json::to_value(CompletionResponse::Array(items))?,
+    ))?;
+    Ok(())
+}
+
 // Direct dispatch functions - no trait wrapper indirection
 pub fn dispatch_request(
     session: &mut Session,
@@ -252,6 +454,10 @@ pub fn dispatch_request(
             Ok(())
         }
         "textDocument/hover" => handle_hover_request(session, client, task_executor, req),
+        "textDocument/prepareRename" => handle_prepare_rename_request(session, client, req),
+        "textDocument/rename" => handle_rename_request(session, client, req),
+        "textDocument/selectionRange" => handle_selection_range_request(session, client, req),
+        "textDocument/completion" => handle_completion_request(session, client, req),
         "bwq/searchEntities" => handle_entity_search_request(client, task_executor, req),
         _ => {
             let response = Response::new_err(
@@ -280,6 +486,10 @@ pub fn dispatch_notification(
             let params: DidChangeTextDocumentParams = serde_json::from_value(not.params)?;
             handle_did_change(session, task_executor, params)
         }
+        "textDocument/didSave" => {
+            let params: DidSaveTextDocumentParams = serde_json::from_value(not.params)?;
+            handle_did_save(session, task_executor, params)
+        }
         "textDocument/didClose" => {
             let params: DidCloseTextDocumentParams = serde_json::from_value(not.params)?;
             handle_did_close(session, client, params)
@@ -0,0 +1,119 @@
+use std::mem;
+
+use bwq_linter::ast::{Expression, Query, Term};
+use bwq_linter::error::Span;
+use bwq_linter::lexer::Lexer;
+use bwq_linter::parser::Parser;
+
+/// the term under the byte `offset` and its span, if there is one to rename
+pub fn term_at_offset(query: &Query, offset: usize) -> Option<(&Term, &Span)> {
+    match query.node_at_offset(offset)? {
+        Expression::Term { term, span } => Some((term, span)),
+        _ => None,
+    }
+}
+
+/// spans of every term identical to the one under `offset`, in document order
+///
+/// terms only match within the same kind, so renaming `apple` leaves `{apple}` alone
+/// unless the cursor is on the case-sensitive one
+pub fn rename_spans(query: &Query, offset: usize) -> Vec<Span> {
+    let Some((target, _)) = term_at_offset(query, offset) else {
+        return Vec::new();
+    };
+
+    let mut spans = Vec::new();
+    collect_matching_terms(&query.expression, target, &mut spans);
+    spans
+}
+
+/// check `new_name` is a single term of the same kind as `term`, e.g. still a `"phrase"`
+pub fn validate_new_name(term: &Term, new_name: &str) -> Result<(), String> {
+    let parsed = Lexer::new(new_name)
+        .tokenize()
+        .and_then(Parser::new)
+        .and_then(|mut parser| parser.parse());
+
+    match parsed {
+        Ok(result) if result.errors.is_empty() => match &result.query.expression {
+            Expression::Term { term: renamed, .. }
+                if mem::discriminant(renamed) == mem::discriminant(term) =>
+            {
+                Ok(())
+            }
+            _ => Err(format!(
+                "'{new_name}' is not a single term of the same kind as the one being renamed"
+            )),
+        },
+        _ => Err(format!("'{new_name}' is not a valid term")),
+    }
+}
+
+fn collect_matching_terms(expr: &Expression, target: &Term, spans: &mut Vec<Span>) {
+    match expr {
+        Expression::Term { term, span } => {
+            if term == target {
+                spans.push(span.clone());
+            }
+        }
+        Expression::BooleanOp { left, right, .. } => {
+            collect_matching_terms(left, target, spans);
+            if let Some(right) = right {
+                collect_matching_terms(right, target, spans);
+            }
+        }
+        Expression::Group { expression, .. } => collect_matching_terms(expression, target, spans),
+        Expression::Proximity { terms, .. } => {
+            for term in terms {
+                collect_matching_terms(term, target, spans);
+            }
+        }
+        Expression::Field { value, .. } => collect_matching_terms(value, target, spans),
+        Expression::Range { .. } => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bwq_linter::BrandwatchLinter;
+
+    fn parse(query: &str) -> Query {
+        BrandwatchLinter::new().lint_for_server(query).unwrap().1
+    }
+
+    #[test]
+    fn test_rename_spans() {
+        let query = parse("apple OR (title:apple AND {apple}) OR \"apple pie\" OR apple~5 pie");
+        let offsets: Vec<_> = rename_spans(&query, 1)
+            .iter()
+            .map(|span| span.start.byte_offset)
+            .collect();
+        assert_eq!(offsets, vec![0, 16, 53]);
+
+        let case_sensitive: Vec<_> = rename_spans(&query, 27)
+            .iter()
+            .map(|span| span.start.byte_offset)
+            .collect();
+        assert_eq!(case_sensitive, vec![26]);
+
+        assert!(rename_spans(&query, 6).is_empty());
+    }
+
+    #[test]
+    fn test_validate_new_name() {
+        let word = Term::Word {
+            value: "apple".to_string(),
+        };
+        assert!(validate_new_name(&word, "banana").is_ok());
+        assert!(validate_new_name(&word, "banana split").is_err());
+        assert!(validate_new_name(&word, "OR").is_err());
+        assert!(validate_new_name(&word, "\"banana split\"").is_err());
+
+        let phrase = Term::Phrase {
+            value: "apple pie".to_string(),
+        };
+        assert!(validate_new_name(&phrase, "\"banana split\"").is_ok());
+        assert!(validate_new_name(&phrase, "\"banana").is_err());
+    }
+}
@@ -0,0 +1,23 @@
+use bwq_linter::ast::Query;
+use lsp_types::SelectionRange;
+
+use crate::utils::span_to_range;
+
+/// nested ranges for "expand selection": the innermost node at the byte `offset`, with each
+/// enclosing node as its parent up to the root
+pub fn selection_range(query: &Query, offset: usize) -> Option<SelectionRange> {
+    query
+        .nodes_at_offset(offset)
+        .into_iter()
+        .map(|node| span_to_range(node.span()))
+        .fold(None, |parent: Option<SelectionRange>, range| {
+            // a node spanning exactly its parent (e.g. a lone term) adds no expansion step
+            if parent.as_ref().is_some_and(|parent| parent.range == range) {
+                return parent;
+            }
+            Some(SelectionRange {
+                range,
+                parent: parent.map(Box::new),
+            })
+        })
+}
@@ -61,7 +61,7 @@ impl Session {
                 cache_len,
                 cache_cap
             );
-            Self::find_entity_id_in_expression(&ast.expression, position)
+            Self::find_entity_id_in_query(ast, position)
         } else {
             None
         }
@@ -90,54 +90,26 @@ impl Session {
         }
     }
 
-    fn find_entity_id_in_expression(
-        expr: &bwq_linter::ast::Expression,
-        position: usize,
-    ) -> Option<String> {
-        use bwq_linter::ast::{Expression, FieldType};
+    fn find_entity_id_in_query(query: &Query, position: usize) -> Option<String> {
+        use bwq_linter::ast::{Expression, FieldType, Term};
 
-        match expr {
-            Expression::Field {
-                field: FieldType::EntityId,
-                value,
-                span,
-            } => {
-                if position >= span.start.offset && position <= span.end.offset {
-                    if let Expression::Term { term, .. } = value.as_ref() {
-                        match term {
-                            bwq_linter::ast::Term::Word { value } => Some(value.clone()),
-                            _ => None,
-                        }
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            }
-            Expression::BooleanOp { left, right, .. } => {
-                // recursively search in left and right operands
-                if let Some(entity_id) = Self::find_entity_id_in_expression(left, position) {
-                    Some(entity_id)
-                } else if let Some(right) = right {
-                    Self::find_entity_id_in_expression(right, position)
-                } else {
-                    None
-                }
-            }
-            Expression::Group { expression, .. } => {
-                Self::find_entity_id_in_expression(expression, position)
-            }
-            Expression::Proximity { terms, .. } => {
-                // search in all proximity terms
-                for term in terms {
-                    if let Some(entity_id) = Self::find_entity_id_in_expression(term, position) {
-                        return Some(entity_id);
-                    }
-                }
-                None
-            }
-            _ => None,
-        }
+        query
+            .nodes_at_offset(position)
+            .into_iter()
+            .rev()
+            .find_map(|node| match node {
+                Expression::Field {
+                    field: FieldType::EntityId,
+                    value,
+                    ..
+                } => match value.as_ref() {
+                    Expression::Term {
+                        term: Term::Word { value },
+                        ..
+                    } => Some(value.clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
     }
 }
@@ -68,3 +68,39 @@ pub fn lsp_position_to_byte_position(text: &str, position: Position) -> usize {
 
     line_start + line_byte_offset
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bwq_linter::BrandwatchLinter;
+    use bwq_linter::ast::{Expression, Term};
+
+    #[test]
+    fn test_tab_indented_positions() {
+        // a tab is one UTF-16 unit for the client and one column for the lexer, so positions
+        // round-trip without any tab expansion
+        let content = "apple AND\n\t\tentityId:123\r\n\tOR juice";
+        let (_, ast) = BrandwatchLinter::new().lint_for_server(content).unwrap();
+
+        let word_at = |line, character| {
+            let offset = lsp_position_to_byte_position(content, Position { line, character });
+            match ast.node_at_offset(offset) {
+                Some(Expression::Term {
+                    term: Term::Word { value },
+                    span,
+                }) => Some((value.clone(), crate::utils::span_to_range(span))),
+                _ => None,
+            }
+        };
+
+        let (value, range) = word_at(1, 12).unwrap();
+        assert_eq!(value, "123");
+        assert_eq!((range.start.line, range.start.character), (1, 11));
+
+        let (value, range) = word_at(2, 5).unwrap();
+        assert_eq!(value, "juice");
+        assert_eq!((range.start.line, range.start.character), (2, 4));
+
+        assert_eq!(word_at(1, 1), None);
+    }
+}
@@ -5,6 +5,7 @@ use lsp_types::{PublishDiagnosticsParams, Uri};
 use std::num::NonZeroUsize;
 use std::thread;
 
+use crate::diagnostics_handler::ComplexityBudget;
 use crate::request_queue::CancellationToken;
 
 /// Background task executor for async operations
@@ -15,6 +16,15 @@ pub struct TaskExecutor {
 
 impl TaskExecutor {
     pub fn new(worker_threads: NonZeroUsize, response_sender: Sender<TaskResponse>) -> Self {
+        Self::with_complexity_budget(worker_threads, response_sender, ComplexityBudget::default())
+    }
+
+    /// like [`Self::new`], flagging documents over `budget` with an informational diagnostic
+    pub fn with_complexity_budget(
+        worker_threads: NonZeroUsize,
+        response_sender: Sender<TaskResponse>,
+        budget: ComplexityBudget,
+    ) -> Self {
         let (task_sender, task_receiver) = crossbeam_channel::bounded(8);
 
         let mut handles = Vec::new();
@@ -26,7 +36,7 @@ impl TaskExecutor {
             let handle = thread::Builder::new()
                 .name(format!("bwq-worker-{i}"))
                 .spawn(move || {
-                    worker_loop(receiver, sender);
+                    worker_loop(receiver, sender, budget);
                 })
                 .expect("Failed to spawn worker thread");
 
@@ -40,18 +50,22 @@ impl TaskExecutor {
     }
 
     /// Schedule a diagnostics task for background processing (internal)
+    ///
+    /// `expensive_rules` also runs the whole-query rules; edits skip them and saves run them.
     pub(crate) fn schedule_diagnostics(
         &self,
         uri: Uri,
         content: String,
         document_version: Option<i32>,
         cancellation_token: Option<CancellationToken>,
+        expensive_rules: bool,
     ) -> Result<()> {
         let task = BackgroundTask::Diagnostics {
             uri,
             content,
             document_version,
             cancellation_token,
+            expensive_rules,
         };
 
         self.task_sender.send(task)?;
@@ -60,7 +74,7 @@ impl TaskExecutor {
 
     /// Schedule a diagnostics task without cancellation token (for tests)
     pub fn schedule_diagnostics_simple(&self, uri: Uri, content: String) -> Result<()> {
-        self.schedule_diagnostics(uri, content, None, None)
+        self.schedule_diagnostics(uri, content, None, None, true)
     }
 
     /// Schedule an entity lookup task for background processing
@@ -97,6 +111,7 @@ enum BackgroundTask {
         content: String,
         document_version: Option<i32>,
         cancellation_token: Option<CancellationToken>,
+        expensive_rules: bool,
     },
     EntityLookup {
         request_id: lsp_server::RequestId,
@@ -111,7 +126,7 @@ enum BackgroundTask {
 pub enum TaskResponse {
     Diagnostics {
         params: PublishDiagnosticsParams,
-        ast: Option<Query>,
+        ast: Option<Box<Query>>,
         document_version: Option<i32>,
     },
     EntityInfo {
@@ -124,13 +139,18 @@ pub enum TaskResponse {
     },
 }
 
-fn worker_loop(receiver: Receiver<BackgroundTask>, sender: Sender<TaskResponse>) {
+fn worker_loop(
+    receiver: Receiver<BackgroundTask>,
+    sender: Sender<TaskResponse>,
+    budget: ComplexityBudget,
+) {
     use crate::diagnostics_handler::DiagnosticsHandler;
     use crate::wikidata::WikiDataClient;
     use bwq_linter::BrandwatchLinter;
 
     let mut linter = BrandwatchLinter::new();
-    let diagnostics_handler = DiagnosticsHandler::new();
+    let mut cheap_linter = BrandwatchLinter::new().with_expensive_rules(false);
+    let diagnostics_handler = DiagnosticsHandler::new().with_complexity_budget(budget);
     let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
     let mut wikidata_client = WikiDataClient::new().expect("Failed to create WikiData client");
 
@@ -143,6 +163,7 @@ fn worker_loop(receiver: Receiver<BackgroundTask>, sender: Sender<TaskResponse>)
                 content,
                 document_version,
                 cancellation_token,
+                expensive_rules,
             } => {
                 // Check if request was cancelled before processing
                 if let Some(ref token) = cancellation_token {
@@ -154,7 +175,13 @@ fn worker_loop(receiver: Receiver<BackgroundTask>, sender: Sender<TaskResponse>)
 
                 tracing::trace!("Processing diagnostics for {:?}", uri);
 
-                match diagnostics_handler.analyze_content_with_ast(&content, &mut linter) {
+                let linter = if expensive_rules {
+                    &mut linter
+                } else {
+                    &mut cheap_linter
+                };
+
+                match diagnostics_handler.analyze_content_with_ast(&content, linter) {
                     Ok((diagnostics, ast)) => {
                         // Check cancellation again before sending result
                         if let Some(ref token) = cancellation_token {
@@ -170,7 +197,7 @@ fn worker_loop(receiver: Receiver<BackgroundTask>, sender: Sender<TaskResponse>)
                                 diagnostics,
                                 version: None,
                             },
-                            ast,
+                            ast: ast.map(Box::new),
                             document_version,
                         };
 
@@ -1,5 +1,9 @@
 use anyhow::Result;
-use bwq_server::server::{client::Client, handlers, session::Session};
+use bwq_server::server::{
+    client::Client,
+    handlers,
+    session::{AstState, DocumentState, Session},
+};
 use bwq_server::task::TaskExecutor;
 use crossbeam_channel::{Receiver, Sender, bounded};
 use lsp_server::{Connection, Message, Request};
@@ -182,3 +186,256 @@ fn test_valid_entity_search_params() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn test_rename_repeated_term() -> Result<()> {
+    let mut session = Session::new(true);
+    let (tx, rx): (Sender<Message>, Receiver<Message>) = bounded(1);
+    let (_req_tx, req_rx) = bounded(1);
+    let connection = Connection {
+        sender: tx,
+        receiver: req_rx,
+    };
+    let client = Client::new(&connection);
+
+    let worker_threads = NonZeroUsize::new(1).unwrap();
+    let (response_sender, _response_receiver) = crossbeam_channel::bounded(16);
+    let task_executor = TaskExecutor::new(worker_threads, response_sender);
+
+    let uri: lsp_types::Uri = "file:///test.bwq".parse().unwrap();
+    let content = "acme OR (title:acme AND {acme})\nOR acme";
+    let (_, ast) = bwq_linter::BrandwatchLinter::new().lint_for_server(content)?;
+    session.documents.insert(
+        uri.clone(),
+        DocumentState {
+            content: content.to_string(),
+            version: 1,
+            ast_state: AstState::Cached,
+        },
+    );
+    session.ast_cache.put(uri.clone(), ast);
+
+    let rename_request = |new_name: &str| Request {
+        id: lsp_server::RequestId::from(1),
+        method: "textDocument/rename".to_string(),
+        params: serde_json::json!({
+            "textDocument": {"uri": "file:///test.bwq"},
+            "position": {"line": 1, "character": 4},
+            "newName": new_name,
+        }),
+    };
+
+    handlers::dispatch_request(
+        &mut session,
+        &client,
+        &task_executor,
+        rename_request("globex"),
+    )?;
+    let Message::Response(response) = rx.try_recv()? else {
+        panic!("expected a response");
+    };
+    let edit: lsp_types::WorkspaceEdit = serde_json::from_value(response.result.unwrap())?;
+    let ranges: Vec<_> = edit.changes.unwrap()[&uri]
+        .iter()
+        .map(|edit| {
+            assert_eq!(edit.new_text, "globex");
+            (
+                edit.range.start.line,
+                edit.range.start.character,
+                edit.range.end.character,
+            )
+        })
+        .collect();
+    // `{acme}` is case-sensitive, a different term, and keeps its name
+    assert_eq!(ranges, vec![(0, 0, 4), (0, 15, 19), (1, 3, 7)]);
+
+    handlers::dispatch_request(
+        &mut session,
+        &client,
+        &task_executor,
+        rename_request("two words"),
+    )?;
+    let Message::Response(response) = rx.try_recv()? else {
+        panic!("expected a response");
+    };
+    assert!(response.error.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_selection_range_nests_to_root() -> Result<()> {
+    let mut session = Session::new(true);
+    let (tx, rx): (Sender<Message>, Receiver<Message>) = bounded(1);
+    let (_req_tx, req_rx) = bounded(1);
+    let connection = Connection {
+        sender: tx,
+        receiver: req_rx,
+    };
+    let client = Client::new(&connection);
+
+    let worker_threads = NonZeroUsize::new(1).unwrap();
+    let (response_sender, _response_receiver) = crossbeam_channel::bounded(16);
+    let task_executor = TaskExecutor::new(worker_threads, response_sender);
+
+    let uri: lsp_types::Uri = "file:///test.bwq".parse().unwrap();
+    let content = "apple AND (juice OR title:drink)";
+    let (_, ast) = bwq_linter::BrandwatchLinter::new().lint_for_server(content)?;
+    session.documents.insert(
+        uri.clone(),
+        DocumentState {
+            content: content.to_string(),
+            version: 1,
+            ast_state: AstState::Cached,
+        },
+    );
+    session.ast_cache.put(uri.clone(), ast);
+
+    let request = Request {
+        id: lsp_server::RequestId::from(1),
+        method: "textDocument/selectionRange".to_string(),
+        params: serde_json::json!({
+            "textDocument": {"uri": "file:///test.bwq"},
+            "positions": [{"line": 0, "character": 28}],
+        }),
+    };
+    handlers::dispatch_request(&mut session, &client, &task_executor, request)?;
+    let Message::Response(response) = rx.try_recv()? else {
+        panic!("expected a response");
+    };
+
+    let ranges: Vec<lsp_types::SelectionRange> = serde_json::from_value(response.result.unwrap())?;
+    assert_eq!(ranges.len(), 1);
+
+    // drink -> title:drink -> juice OR title:drink -> (group) -> whole query
+    let mut chain = Vec::new();
+    let mut current = Some(&ranges[0]);
+    while let Some(selection) = current {
+        chain.push((
+            selection.range.start.character,
+            selection.range.end.character,
+        ));
+        current = selection.parent.as_deref();
+    }
+    assert_eq!(chain, vec![(26, 31), (20, 31), (11, 31), (10, 32), (0, 32)]);
+    Ok(())
+}
+
+#[test]
+fn test_completion_offers_enum_values() -> Result<()> {
+    let mut session = Session::new(true);
+    let (tx, rx): (Sender<Message>, Receiver<Message>) = bounded(1);
+    let (_req_tx, req_rx) = bounded(1);
+    let connection = Connection {
+        sender: tx,
+        receiver: req_rx,
+    };
+    let client = Client::new(&connection);
+
+    let worker_threads = NonZeroUsize::new(1).unwrap();
+    let (response_sender, _response_receiver) = crossbeam_channel::bounded(16);
+    let task_executor = TaskExecutor::new(worker_threads, response_sender);
+
+    let uri: lsp_types::Uri = "file:///test.bwq".parse().unwrap();
+    session.documents.insert(
+        uri.clone(),
+        DocumentState {
+            content: "apple AND engagementType:".to_string(),
+            version: 1,
+            ast_state: AstState::Cached,
+        },
+    );
+
+    let request = Request {
+        id: lsp_server::RequestId::from(1),
+        method: "textDocument/completion".to_string(),
+        params: serde_json::json!({
+            "textDocument": {"uri": "file:///test.bwq"},
+            "position": {"line": 0, "character": 25},
+        }),
+    };
+    handlers::dispatch_request(&mut session, &client, &task_executor, request)?;
+    let Message::Response(response) = rx.try_recv()? else {
+        panic!("expected a response");
+    };
+
+    let items: Vec<lsp_types::CompletionItem> = serde_json::from_value(response.result.unwrap())?;
+    let labels: Vec<_> = items.iter().map(|item| item.label.as_str()).collect();
+    assert_eq!(labels, vec!["COMMENT", "REPLY", "RETWEET", "QUOTE"]);
+    Ok(())
+}
+
+#[test]
+fn test_save_runs_expensive_rules() -> Result<()> {
+    let mut session = Session::new(true);
+    let (tx, _rx): (Sender<Message>, Receiver<Message>) = bounded(1);
+    let (_req_tx, req_rx) = bounded(1);
+    let connection = Connection {
+        sender: tx,
+        receiver: req_rx,
+    };
+    let client = Client::new(&connection);
+
+    let worker_threads = NonZeroUsize::new(1).unwrap();
+    let (response_sender, response_receiver) = crossbeam_channel::bounded(16);
+    let task_executor = TaskExecutor::new(worker_threads, response_sender);
+
+    let notification = |method: &str, params: Value| lsp_server::Notification {
+        method: method.to_string(),
+        params,
+    };
+    // W028 (stopword-only query) comes from a whole-query rule
+    let codes = || -> Result<Vec<String>> {
+        let bwq_server::task::TaskResponse::Diagnostics { params, .. } =
+            response_receiver.recv_timeout(std::time::Duration::from_secs(5))?
+        else {
+            panic!("expected diagnostics");
+        };
+        Ok(params
+            .diagnostics
+            .into_iter()
+            .filter_map(|d| match d.code {
+                Some(lsp_types::NumberOrString::String(code)) => Some(code),
+                _ => None,
+            })
+            .collect())
+    };
+
+    handlers::dispatch_notification(
+        &mut session,
+        &client,
+        &task_executor,
+        notification(
+            "textDocument/didOpen",
+            serde_json::json!({"textDocument": {
+                "uri": "file:///test.bwq", "languageId": "bwq", "version": 1, "text": "apple",
+            }}),
+        ),
+    )?;
+    assert!(codes()?.is_empty());
+
+    handlers::dispatch_notification(
+        &mut session,
+        &client,
+        &task_executor,
+        notification(
+            "textDocument/didChange",
+            serde_json::json!({
+                "textDocument": {"uri": "file:///test.bwq", "version": 2},
+                "contentChanges": [{"text": "the"}],
+            }),
+        ),
+    )?;
+    assert!(!codes()?.contains(&"W028".to_string()));
+
+    handlers::dispatch_notification(
+        &mut session,
+        &client,
+        &task_executor,
+        notification(
+            "textDocument/didSave",
+            serde_json::json!({"textDocument": {"uri": "file:///test.bwq"}}),
+        ),
+    )?;
+    assert_eq!(codes()?, vec!["W028".to_string()]);
+    Ok(())
+}
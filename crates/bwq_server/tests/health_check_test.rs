@@ -0,0 +1,4 @@
+#[test]
+fn test_health_check_round_trip() {
+    bwq_server::health_check().expect("in-process server should pass its health check");
+}